@@ -0,0 +1,33 @@
+use pugl_egui::{EguiView, egui};
+use pugl_rs::{ControlFlow, Event, OpenGl, World};
+
+fn main() {
+    let mut world = World::new_program().unwrap();
+    let mut egui = EguiView::new();
+    let mut counter = 0;
+
+    let view = world
+        .new_view(OpenGl::default())
+        .with_size(400, 300)
+        .with_event_handler(move |view, event| {
+            if matches!(event, Event::Close) {
+                view.world().quit();
+            }
+
+            egui.handle_event(view, event, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.heading("Hello from pugl!");
+                    if ui.button("Click me").clicked() {
+                        counter += 1;
+                    }
+                    ui.label(format!("Clicked {counter} times"));
+                });
+            });
+        })
+        .realize()
+        .unwrap();
+
+    view.show_aggressive().unwrap();
+
+    world.run(|_| ControlFlow::Wait).unwrap();
+}
@@ -0,0 +1,340 @@
+#![doc = include_str!("../README.md")]
+
+pub use egui;
+
+use egui::{
+    CursorIcon, Modifiers as EguiModifiers, MouseWheelUnit, PointerButton, Pos2, RawInput, Vec2,
+    ViewportId,
+};
+use pugl_rs::{
+    Event, EventInput, Key, Modifiers, MouseButton, MouseCursor, OpenGl, OpenGlContext,
+    ScrollDirection, View, WorldInstant,
+};
+use std::{ffi::CString, sync::Arc, time::Duration};
+
+/// The state of an `egui` user interface drawn in a `pugl-rs` view.
+///
+/// Pass every event of the view to [`EguiView::handle_event`], which takes care of the input, the painter lifecycle,
+/// the repaints, the clipboard and the mouse cursor.
+///
+/// The GL resources are freed on [`Event::Unrealize`], so the view must keep passing its events until it is dropped.
+pub struct EguiView {
+    context: egui::Context,
+    painter: Option<egui_glow::Painter>,
+    input: RawInput,
+    start: Option<WorldInstant>,
+    repaint: bool,
+}
+
+impl EguiView {
+    /// Creates the state with a new `egui` context.
+    pub fn new() -> Self {
+        Self::with_context(egui::Context::default())
+    }
+
+    /// Creates the state with an existing `egui` context.
+    pub fn with_context(context: egui::Context) -> Self {
+        Self {
+            context,
+            painter: None,
+            input: RawInput::default(),
+            start: None,
+            repaint: true,
+        }
+    }
+
+    /// Returns the `egui` context.
+    pub fn context(&self) -> &egui::Context {
+        &self.context
+    }
+
+    /// Handles an event of the view, and runs `ui` to draw the user interface on [`Event::Expose`].
+    pub fn handle_event(
+        &mut self,
+        view: &View<OpenGl>,
+        event: Event<OpenGl>,
+        ui: impl FnMut(&egui::Context),
+    ) {
+        let scale = view.system_scale() as f32;
+
+        match event {
+            Event::Realize { backend } => {
+                let gl = unsafe { load_gl(&backend) };
+                self.painter = egui_glow::Painter::new(Arc::new(gl), "", None, true).ok();
+            }
+
+            // the context is current while the view is unrealized, which also happens when the view is dropped
+            Event::Unrealize { .. } => {
+                if let Some(mut painter) = self.painter.take() {
+                    painter.destroy();
+                }
+            }
+
+            Event::Configure { rect, .. } => {
+                self.input.screen_rect = Some(egui::Rect::from_min_size(
+                    Pos2::ZERO,
+                    Vec2::new(rect.w as f32, rect.h as f32) / scale,
+                ));
+                self.repaint = true;
+            }
+
+            Event::Update if self.repaint => {
+                view.obscure_view();
+            }
+
+            Event::Expose { .. } => {
+                self.paint(view, scale, ui);
+            }
+
+            Event::FocusIn { .. } => self.push(egui::Event::WindowFocused(true)),
+            Event::FocusOut { .. } => self.push(egui::Event::WindowFocused(false)),
+
+            Event::PointerMotion { input } | Event::PointerIn { input, .. } => {
+                let pos = self.pointer_position(&input, scale);
+                self.push(egui::Event::PointerMoved(pos));
+            }
+
+            Event::PointerOut { input, .. } => {
+                self.update_modifiers(&input);
+                self.push(egui::Event::PointerGone);
+            }
+
+            Event::ButtonPress { input, button } | Event::ButtonRelease { input, button } => {
+                let pressed = matches!(event, Event::ButtonPress { .. });
+                let pos = self.pointer_position(&input, scale);
+                if let Some(button) = pointer_button(button) {
+                    self.push(egui::Event::PointerButton {
+                        pos,
+                        button,
+                        pressed,
+                        modifiers: self.input.modifiers,
+                    });
+                }
+            }
+
+            Event::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            } => {
+                self.update_modifiers(&input);
+                let (unit, delta) = match direction {
+                    ScrollDirection::Smooth => (
+                        MouseWheelUnit::Point,
+                        Vec2::new(dx as f32, dy as f32) / scale,
+                    ),
+                    _ => (MouseWheelUnit::Line, Vec2::new(dx as f32, dy as f32)),
+                };
+
+                self.push(egui::Event::MouseWheel {
+                    unit,
+                    delta,
+                    modifiers: self.input.modifiers,
+                });
+            }
+
+            Event::KeyPress { input, key, .. } | Event::KeyRelease { input, key, .. } => {
+                let pressed = matches!(event, Event::KeyPress { .. });
+                self.update_modifiers(&input);
+
+                let modifiers = self.input.modifiers;
+                if pressed && modifiers.command {
+                    match key {
+                        Key::Char('c') => self.push(egui::Event::Copy),
+                        Key::Char('x') => self.push(egui::Event::Cut),
+                        Key::Char('v') => {
+                            let _ = view.paste_clipboard();
+                        }
+                        _ => {}
+                    }
+                }
+
+                if let Some(key) = egui_key(key) {
+                    self.push(egui::Event::Key {
+                        key,
+                        physical_key: None,
+                        pressed,
+                        repeat: false,
+                        modifiers,
+                    });
+                }
+            }
+
+            Event::KeyText { input, text, .. } => {
+                self.update_modifiers(&input);
+
+                let modifiers = self.input.modifiers;
+                let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+                if !text.is_empty() && !modifiers.ctrl && !modifiers.mac_cmd {
+                    self.push(egui::Event::Text(text));
+                }
+            }
+
+            Event::Clipboard { text, .. } => {
+                self.push(egui::Event::Paste(text.to_owned()));
+            }
+
+            _ => {}
+        }
+    }
+
+    fn push(&mut self, event: egui::Event) {
+        self.input.events.push(event);
+        self.repaint = true;
+    }
+
+    fn pointer_position(&mut self, input: &EventInput, scale: f32) -> Pos2 {
+        self.update_modifiers(input);
+
+        Pos2::new(input.position.x as f32, input.position.y as f32) / scale
+    }
+
+    fn update_modifiers(&mut self, input: &EventInput) {
+        let mods = input.mods;
+        let mac = cfg!(target_os = "macos");
+
+        self.input.modifiers = EguiModifiers {
+            alt: mods.contains(Modifiers::ALT),
+            ctrl: mods.contains(Modifiers::CTRL),
+            shift: mods.contains(Modifiers::SHIFT),
+            mac_cmd: mac && mods.contains(Modifiers::SUPER),
+            command: if mac {
+                mods.contains(Modifiers::SUPER)
+            } else {
+                mods.contains(Modifiers::CTRL)
+            },
+        };
+    }
+
+    fn paint(&mut self, view: &View<OpenGl>, scale: f32, mut ui: impl FnMut(&egui::Context)) {
+        let Some(painter) = self.painter.as_mut() else {
+            return;
+        };
+
+        let now = view.world().time();
+        self.input.time = Some((now - *self.start.get_or_insert(now)).as_secs_f64());
+        self.input
+            .viewports
+            .entry(ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(scale);
+
+        let output = self.context.run(self.input.take(), |ctx| ui(ctx));
+
+        self.repaint = output
+            .viewport_output
+            .get(&ViewportId::ROOT)
+            .is_some_and(|viewport| viewport.repaint_delay == Duration::ZERO);
+
+        if !output.platform_output.copied_text.is_empty() {
+            let _ = view.copy_clipboard(&output.platform_output.copied_text);
+        }
+
+        let _ = view.set_cursor(mouse_cursor(output.platform_output.cursor_icon));
+
+        let (width, height) = view.size();
+        let clear_color = egui::Rgba::from(self.context.style().visuals.panel_fill).to_array();
+        egui_glow::painter::clear(painter.gl(), [width, height], clear_color);
+
+        let primitives = self
+            .context
+            .tessellate(output.shapes, output.pixels_per_point);
+        painter.paint_and_update_textures(
+            [width, height],
+            output.pixels_per_point,
+            &primitives,
+            &output.textures_delta,
+        );
+    }
+}
+
+impl Default for EguiView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe fn load_gl(context: &OpenGlContext) -> glow::Context {
+    unsafe {
+        glow::Context::from_loader_function(|name| {
+            let name = CString::new(name).unwrap();
+            context.get_proc_address(&name) as *const _
+        })
+    }
+}
+
+fn pointer_button(button: MouseButton) -> Option<PointerButton> {
+    match button {
+        MouseButton::Left => Some(PointerButton::Primary),
+        MouseButton::Right => Some(PointerButton::Secondary),
+        MouseButton::Middle => Some(PointerButton::Middle),
+        MouseButton::Back => Some(PointerButton::Extra1),
+        MouseButton::Forward => Some(PointerButton::Extra2),
+        MouseButton::Other(_) => None,
+    }
+}
+
+fn egui_key(key: Key) -> Option<egui::Key> {
+    use egui::Key as E;
+
+    Some(match key {
+        Key::Left => E::ArrowLeft,
+        Key::Right => E::ArrowRight,
+        Key::Up => E::ArrowUp,
+        Key::Down => E::ArrowDown,
+        Key::Home => E::Home,
+        Key::End => E::End,
+        Key::PageUp => E::PageUp,
+        Key::PageDown => E::PageDown,
+        Key::Insert => E::Insert,
+        Key::F1 => E::F1,
+        Key::F2 => E::F2,
+        Key::F3 => E::F3,
+        Key::F4 => E::F4,
+        Key::F5 => E::F5,
+        Key::F6 => E::F6,
+        Key::F7 => E::F7,
+        Key::F8 => E::F8,
+        Key::F9 => E::F9,
+        Key::F10 => E::F10,
+        Key::F11 => E::F11,
+        Key::F12 => E::F12,
+        Key::NumpadEnter => E::Enter,
+        Key::Char('\r' | '\n') => E::Enter,
+        Key::Char('\t') => E::Tab,
+        Key::Char('\x08') => E::Backspace,
+        Key::Char('\x1b') => E::Escape,
+        Key::Char('\x7f') => E::Delete,
+        Key::Char(' ') => E::Space,
+        Key::Char(char) => E::from_name(&char.to_ascii_uppercase().to_string())?,
+        _ => return None,
+    })
+}
+
+fn mouse_cursor(icon: CursorIcon) -> MouseCursor {
+    match icon {
+        CursorIcon::Text | CursorIcon::VerticalText => MouseCursor::Caret,
+        CursorIcon::Crosshair => MouseCursor::Crosshair,
+        CursorIcon::PointingHand => MouseCursor::Hand,
+        CursorIcon::NotAllowed | CursorIcon::NoDrop => MouseCursor::NotAllowed,
+        CursorIcon::AllScroll | CursorIcon::Move | CursorIcon::Grab | CursorIcon::Grabbing => {
+            MouseCursor::Scroll
+        }
+        CursorIcon::ResizeHorizontal
+        | CursorIcon::ResizeEast
+        | CursorIcon::ResizeWest
+        | CursorIcon::ResizeColumn => MouseCursor::ResizeWE,
+        CursorIcon::ResizeVertical
+        | CursorIcon::ResizeNorth
+        | CursorIcon::ResizeSouth
+        | CursorIcon::ResizeRow => MouseCursor::ResizeNS,
+        CursorIcon::ResizeNwSe | CursorIcon::ResizeNorthWest | CursorIcon::ResizeSouthEast => {
+            MouseCursor::ResizeNWSE
+        }
+        CursorIcon::ResizeNeSw | CursorIcon::ResizeNorthEast | CursorIcon::ResizeSouthWest => {
+            MouseCursor::ResizeNESW
+        }
+        _ => MouseCursor::Arrow,
+    }
+}
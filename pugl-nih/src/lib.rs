@@ -0,0 +1,243 @@
+#![doc = include_str!("../README.md")]
+
+use nih_plug::prelude::{Editor, GuiContext, ParentWindowHandle};
+use pugl_rs::{Backend, NativeView, PluginEditor, UnrealizedView, ViewProxy, World};
+use std::{
+    any::Any,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+// doc only import
+#[allow(unused_imports)]
+use pugl_rs::View;
+
+/// The rate of the event loop of the editor, around the refresh rate of most displays.
+const IDLE_INTERVAL: Duration = Duration::from_millis(15);
+
+type BuildView<B> = dyn Fn(&World, Arc<dyn GuiContext>) -> UnrealizedView<B> + Send + Sync;
+
+/// An [`Editor`] for `nih-plug` plugins that draws the user interface in a `pugl-rs` view, as an alternative to `baseview`.
+///
+/// The adapter embeds the view into the window of the host and manages its lifecycle:
+/// - The view is created by the closure passed to [`PuglEditor::new`] every time the host opens the editor (in a new [`World`]),
+///   and is dropped when the host closes the editor.
+/// - The size of the view is managed by the adapter. The size passed to [`PuglEditor::new`] is in logical pixels,
+///   and is multiplied by the scale factor provided by the host (or by [`View::system_scale`] if the host doesn't provide one).
+/// - The view is redrawn whenever a parameter changes.
+/// - On Linux the editor runs its own event loop on a separate thread, like `baseview` does.
+///   On Windows and MacOS the events are dispatched by the event loop of the host,
+///   and the rest of the loop (like [`World::add_idle_callback`]) runs from a timer, see [`PluginEditor::start_idle_timer`].
+/// ```ignore
+/// fn editor(&mut self, _: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+///     Some(Box::new(PuglEditor::new((640, 480), |world, context| {
+///         world.new_view(OpenGl::default()).with_event_handler(|view, event| {
+///             // ...
+///         })
+///     })))
+/// }
+/// ```
+pub struct PuglEditor<B: Backend> {
+    shared: Arc<Shared<B>>,
+}
+
+struct Shared<B: Backend> {
+    build: Box<BuildView<B>>,
+    // logical size
+    size: Mutex<(u32, u32)>,
+    // the bits of the `f32` scale factor provided by the host, zero if there is none
+    scale: AtomicU32,
+    view: Mutex<Option<(ViewProxy, f64)>>,
+}
+
+impl<B: Backend + 'static> PuglEditor<B> {
+    /// Creates an editor with the given logical size, which creates its view with `build`.
+    pub fn new(
+        size: (u32, u32),
+        build: impl Fn(&World, Arc<dyn GuiContext>) -> UnrealizedView<B> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                build: Box::new(build),
+                size: Mutex::new(size),
+                scale: AtomicU32::new(0),
+                view: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+impl<B: Backend + 'static> Shared<B> {
+    fn open(
+        self: &Arc<Self>,
+        parent: NativeView,
+        context: Arc<dyn GuiContext>,
+    ) -> Option<OpenEditor<B>> {
+        let world = World::new_module().ok()?;
+        let view = (self.build)(&world, context);
+
+        let scale = match f32::from_bits(self.scale.load(Ordering::Relaxed)) {
+            scale if scale > 0.0 => scale as f64,
+            _ => view.system_scale(),
+        };
+
+        let (width, height) = *self.size.lock().unwrap();
+        let view = view.with_size(
+            (width as f64 * scale).round() as u32,
+            (height as f64 * scale).round() as u32,
+        );
+
+        #[allow(unused_mut)]
+        let mut editor = PluginEditor::attach(view, parent).ok()?;
+        #[cfg(not(target_os = "linux"))]
+        editor.start_idle_timer(IDLE_INTERVAL).ok()?;
+
+        // the host decides whether the editor is visible, so failing to show it is not an error
+        let _ = editor.view().show();
+        let proxy = editor.view().proxy();
+        *self.view.lock().unwrap() = Some((proxy.clone(), scale));
+
+        Some(OpenEditor {
+            editor,
+            shared: self.clone(),
+            proxy,
+        })
+    }
+}
+
+/// An open editor, which is closed when this is dropped.
+struct OpenEditor<B: Backend> {
+    // only driven by the editor thread on Linux, elsewhere the host dispatches its events
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    editor: PluginEditor<B>,
+    shared: Arc<Shared<B>>,
+    proxy: ViewProxy,
+}
+
+impl<B: Backend> Drop for OpenEditor<B> {
+    fn drop(&mut self) {
+        // the editor may have been opened again in the meantime
+        let mut view = self.shared.view.lock().unwrap();
+        if view.as_ref().is_some_and(|(proxy, _)| *proxy == self.proxy) {
+            *view = None;
+        }
+    }
+}
+
+impl<B: Backend + 'static> Editor for PuglEditor<B> {
+    fn spawn(
+        &self,
+        parent: ParentWindowHandle,
+        context: Arc<dyn GuiContext>,
+    ) -> Box<dyn Any + Send> {
+        let parent = unsafe {
+            match parent {
+                ParentWindowHandle::X11Window(window) => NativeView::from_raw(window as usize),
+                ParentWindowHandle::AppKitNsView(view) => NativeView::from_raw(view as usize),
+                ParentWindowHandle::Win32Hwnd(hwnd) => NativeView::from_raw(hwnd as usize),
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        {
+            Box::new(EditorThread::spawn(self.shared.clone(), parent, context))
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            Box::new(self.shared.open(parent, context))
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        let mut size = self.shared.size.lock().unwrap();
+        if let Some((view, scale)) = &*self.shared.view.lock().unwrap()
+            && let Some((width, height)) = view.size()
+        {
+            *size = (
+                (width as f64 / scale).round() as u32,
+                (height as f64 / scale).round() as u32,
+            );
+        }
+
+        *size
+    }
+
+    fn set_scale_factor(&self, factor: f32) -> bool {
+        // applied the next time the editor is opened, hosts set the scale factor before opening the editor
+        self.shared.scale.store(factor.to_bits(), Ordering::Relaxed);
+        true
+    }
+
+    fn param_value_changed(&self, _: &str, _: f32) {
+        self.obscure_view();
+    }
+
+    fn param_modulation_changed(&self, _: &str, _: f32) {
+        self.obscure_view();
+    }
+
+    fn param_values_changed(&self) {
+        self.obscure_view();
+    }
+}
+
+impl<B: Backend> PuglEditor<B> {
+    fn obscure_view(&self) {
+        if let Some((view, _)) = &*self.shared.view.lock().unwrap() {
+            view.obscure_view();
+        }
+    }
+}
+
+/// An editor running its own event loop, the loop is stopped and the view is dropped when this is dropped.
+#[cfg(target_os = "linux")]
+struct EditorThread {
+    closed: Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(target_os = "linux")]
+impl EditorThread {
+    fn spawn<B: Backend + 'static>(
+        shared: Arc<Shared<B>>,
+        parent: NativeView,
+        context: Arc<dyn GuiContext>,
+    ) -> Self {
+        let closed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let thread = std::thread::spawn({
+            let closed = closed.clone();
+            move || {
+                let Some(mut open) = shared.open(parent, context) else {
+                    return;
+                };
+
+                while !closed.load(Ordering::Relaxed) {
+                    if open.editor.idle().is_err() {
+                        break;
+                    }
+
+                    std::thread::sleep(IDLE_INTERVAL);
+                }
+            }
+        });
+
+        Self {
+            closed,
+            thread: Some(thread),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for EditorThread {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -1,7 +1,14 @@
 fn main() {
     let os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
 
+    // pugl is built with `cc` directly, meson is not used
+    println!("cargo:rerun-if-changed=pugl/src");
+    println!("cargo:rerun-if-changed=pugl/include");
     println!("cargo:rerun-if-changed=pugl/meson.build");
+    println!("cargo:rerun-if-changed=shim");
+
+    emit_version();
+    emit_commit();
 
     if os == "linux" {
         build_linux();
@@ -14,6 +21,69 @@ fn main() {
     }
 }
 
+/// Reads the version of the vendored pugl from its `meson.build` and passes it to the crate as `PUGL_VERSION`.
+fn emit_version() {
+    let version = std::fs::read_to_string("pugl/meson.build")
+        .ok()
+        .and_then(|meson| {
+            let start = meson.find("version: '")? + "version: '".len();
+            let end = start + meson[start..].find('\'')?;
+            Some(meson[start..end].to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=PUGL_VERSION={}", version);
+}
+
+/// Reads the checked out commit of the `pugl` submodule and passes it to the crate as `PUGL_COMMIT`.
+///
+/// The git metadata is read directly, since `git` may not be installed, and isn't packaged with the crate.
+fn emit_commit() {
+    let commit = git_dir("pugl")
+        .and_then(|git_dir| {
+            println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+            head_commit(&git_dir)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=PUGL_COMMIT={}", commit);
+}
+
+/// The git directory of a checkout, `.git` of a submodule is a file pointing to it.
+fn git_dir(checkout: &str) -> Option<std::path::PathBuf> {
+    let dot_git = std::path::Path::new(checkout).join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let link = std::fs::read_to_string(&dot_git).ok()?;
+    let path = link.trim().strip_prefix("gitdir:")?.trim();
+    Some(std::path::Path::new(checkout).join(path))
+}
+
+fn head_commit(git_dir: &std::path::Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    // submodules are usually checked out at a detached HEAD
+    let Some(reference) = head.strip_prefix("ref:") else {
+        return Some(head.to_string());
+    };
+
+    let reference = reference.trim();
+    if let Ok(commit) = std::fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+
+    std::fs::read_to_string(git_dir.join("packed-refs"))
+        .ok()?
+        .lines()
+        .find_map(|line| {
+            let (commit, name) = line.split_once(' ')?;
+            (name == reference).then(|| commit.to_string())
+        })
+}
+
 fn build_linux() {
     let mut build = cc::Build::new();
 
@@ -23,9 +93,24 @@ fn build_linux() {
     build.file("pugl/src/x11.c");
     build.file("pugl/src/x11_stub.c");
 
+    // pugl looks up the text of key presses into an 8 byte buffer, the shim keeps the complete text of long input method commits
+    build.define("Xutf8LookupString", "puglRsUtf8LookupString");
+    build.file("shim/x11_text.c");
+
+    if cfg!(feature = "xcursor") {
+        build.define("HAVE_XCURSOR", None);
+    }
+    if cfg!(feature = "xrandr") {
+        build.define("HAVE_XRANDR", None);
+    }
+    if cfg!(feature = "xsync") {
+        build.define("HAVE_XSYNC", None);
+    }
+
     #[cfg(feature = "cairo")]
     {
         let cairo = pkg_config::Config::new()
+            .statik(cfg!(feature = "static-link"))
             .probe("cairo")
             .expect("system library `cairo` is not found");
 
@@ -43,29 +128,49 @@ fn build_linux() {
 
     build.compile("pugl_x11");
 
-    let mut libs = vec!["X11", "Xext", "Xrandr", "Xcursor"];
-    if cfg!(feature = "cairo") {
-        libs.push("cairo");
+    // cairo is linked by pkg-config
+    link_lib("X11");
+    if cfg!(feature = "xcursor") {
+        link_lib("Xcursor");
+    }
+    if cfg!(feature = "xrandr") {
+        link_lib("Xrandr");
+    }
+    if cfg!(feature = "xsync") {
+        link_lib("Xext");
     }
     if cfg!(feature = "opengl") {
-        libs.push("GL");
+        link_lib("GL");
     }
     if cfg!(feature = "vulkan") {
-        libs.push("vulkan");
+        link_lib("vulkan");
     }
+}
 
-    println!(
-        "cargo:rustc-flags={}",
-        libs.iter()
-            .map(|lib| format!("-l {}", lib))
-            .collect::<Vec<_>>()
-            .join(" ")
-    );
+/// Links a native library, statically if the `static-link` feature is enabled.
+///
+/// This is only used for the libraries that can actually be linked statically, system libraries (like `user32` or `AppKit`) are always linked dynamically.
+fn link_lib(name: &str) {
+    if cfg!(feature = "static-link") {
+        println!("cargo:rustc-link-lib=static={}", name);
+    } else {
+        println!("cargo:rustc-link-lib=dylib={}", name);
+    }
 }
 
 fn build_windows() {
+    let env = std::env::var("CARGO_CFG_TARGET_ENV").unwrap();
     let mut build = cc::Build::new();
 
+    if env == "msvc" {
+        build.define("_CRT_SECURE_NO_WARNINGS", None);
+        build.flag_if_supported("/utf-8");
+    } else {
+        // older mingw headers target windows xp by default, which hides the APIs that pugl uses
+        build.define("_WIN32_WINNT", "0x0601");
+        build.define("WINVER", "0x0601");
+    }
+
     build.include("pugl/include");
     build.file("pugl/src/common.c");
     build.file("pugl/src/internal.c");
@@ -92,6 +197,9 @@ fn build_windows() {
     if cfg!(feature = "opengl") {
         println!("cargo:rustc-link-lib=dylib=opengl32");
     }
+
+    // the import libraries have the same names on both toolchains (`user32.lib` for msvc, `libuser32.a` for mingw),
+    // and the vulkan loader is not linked, since pugl loads `vulkan-1.dll` at runtime
 }
 
 fn build_macos() {
@@ -0,0 +1,86 @@
+// Keeps the complete text of input method commits, which pugl truncates to the 8 byte buffer of its text events.
+//
+// pugl is compiled with `Xutf8LookupString` defined to `puglRsUtf8LookupString` (see `build.rs`), so every text lookup of pugl goes through here.
+// pugl gets the complete characters that fit into its buffer, and the whole text of the last lookup is returned by `puglRsGetLookupText`.
+
+#undef Xutf8LookupString
+
+#include <X11/Xlib.h>
+#include <X11/Xutil.h>
+
+#include <stddef.h>
+#include <stdlib.h>
+#include <string.h>
+
+int puglRsUtf8LookupString(XIC ic, XKeyPressedEvent* event, char* buffer, int bytes, KeySym* keysym, Status* status);
+const char* puglRsGetLookupText(size_t* length);
+void puglRsClearLookupText(void);
+
+// per thread, since every world is used from a single thread
+static _Thread_local char* text = NULL;
+static _Thread_local int capacity = 0;
+static _Thread_local int length = 0;
+
+static int
+reserve(int size)
+{
+  if (size <= capacity) {
+    return 1;
+  }
+
+  char* const grown = (char*)realloc(text, (size_t)size);
+  if (!grown) {
+    return 0;
+  }
+
+  text = grown;
+  capacity = size;
+  return 1;
+}
+
+int
+puglRsUtf8LookupString(XIC ic, XKeyPressedEvent* event, char* buffer, int bytes, KeySym* keysym, Status* status)
+{
+  length = 0;
+  if (bytes <= 1 || !reserve(64)) {
+    return Xutf8LookupString(ic, event, buffer, bytes, keysym, status);
+  }
+
+  int found = Xutf8LookupString(ic, event, text, capacity, keysym, status);
+  if (*status == XBufferOverflow) {
+    // the input method keeps the text until it is looked up with a large enough buffer
+    if (!reserve(found)) {
+      return Xutf8LookupString(ic, event, buffer, bytes, keysym, status);
+    }
+
+    found = Xutf8LookupString(ic, event, text, capacity, keysym, status);
+  }
+
+  if ((*status != XLookupChars && *status != XLookupBoth) || found <= 0) {
+    return 0;
+  }
+
+  // only complete characters are passed to pugl, with room for a nul byte
+  length = found;
+  int prefix = length < bytes ? length : bytes - 1;
+  while (prefix > 0 && prefix < length && ((unsigned char)text[prefix] & 0xC0) == 0x80) {
+    --prefix;
+  }
+
+  memcpy(buffer, text, (size_t)prefix);
+  buffer[prefix] = '\0';
+  return prefix;
+}
+
+const char*
+puglRsGetLookupText(size_t* const out_length)
+{
+  *out_length = (size_t)length;
+  return text;
+}
+
+void
+puglRsClearLookupText(void)
+{
+  length = 0;
+}
@@ -5,3 +5,20 @@
 
 mod generated;
 pub use generated::*;
+
+/// Version of the vendored `pugl` library, as declared in its `meson.build` (for example `"0.5.5"`).
+pub const PUGL_VERSION: &str = env!("PUGL_VERSION");
+
+/// Commit of the vendored `pugl` library, as checked out in the `pugl` submodule (`"unknown"` if the git metadata isn't available).
+pub const PUGL_COMMIT: &str = env!("PUGL_COMMIT");
+
+#[cfg(target_os = "linux")]
+unsafe extern "C" {
+    /// Returns the complete text of the last key press that `pugl` looked up on this thread, which may be longer than the text of a `PUGL_TEXT` event.
+    ///
+    /// The text is not nul-terminated, and stays valid until the next key press is looked up.
+    pub fn puglRsGetLookupText(length: *mut usize) -> *const std::os::raw::c_char;
+
+    /// Forgets the text of the last key press, so it isn't taken for the text of a later key press that isn't looked up with an input method.
+    pub fn puglRsClearLookupText();
+}
@@ -0,0 +1,139 @@
+//! Benchmarks of the event dispatch path.
+//!
+//! These create real views, so they need a display server (for example `Xvfb` on Linux).
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use pugl_rs::{
+    Event, EventInput, Modifiers, OwnedEvent, Rect, ScreenPoint, ViewPoint, World, WorldInstant,
+};
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::Duration;
+
+const EVENTS: usize = 10_000;
+/// Events sent through the window system per iteration, which stays below the message queue limit of Windows (10000 per thread)
+const SENT_EVENTS: usize = 1_000;
+
+fn motion(i: usize) -> OwnedEvent {
+    OwnedEvent::PointerMotion {
+        input: EventInput {
+            time: WorldInstant::from_secs_f64(i as f64 * 0.001),
+            position: ViewPoint::new((i % 200) as f64, (i / 200) as f64),
+            root_position: ScreenPoint::new((i % 200) as f64, (i / 200) as f64),
+            mods: Modifiers::empty(),
+            hint: false,
+        },
+    }
+}
+
+fn dispatch(c: &mut Criterion) {
+    let mut world = World::new_program().unwrap();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let exposes = Arc::new(AtomicUsize::new(0));
+    let view = world
+        .new_view(())
+        .with_size(200, 200)
+        .with_event_handler({
+            let counter = counter.clone();
+            let exposes = exposes.clone();
+            move |_, event| {
+                if let Event::Expose { .. } = event {
+                    exposes.fetch_add(1, Ordering::Relaxed);
+                }
+                black_box(event);
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .realize()
+        .unwrap();
+
+    let events = (0..EVENTS).map(motion).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("dispatch");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+
+    group.bench_function("motion storm", |b| {
+        b.iter(|| {
+            for event in &events {
+                view.inject_event(event.clone());
+            }
+        })
+    });
+
+    world.set_global_event_hook(|view, event| {
+        black_box((view, event));
+    });
+    group.bench_function("motion storm with hook", |b| {
+        b.iter(|| {
+            for event in &events {
+                view.inject_event(event.clone());
+            }
+        })
+    });
+    world.clear_global_event_hook();
+
+    world.set_slow_event_handler(Duration::from_secs(1), |view, event, elapsed| {
+        black_box((view, event, elapsed));
+    });
+    group.bench_function("motion storm with slow handler", |b| {
+        b.iter(|| {
+            for event in &events {
+                view.inject_event(event.clone());
+            }
+        })
+    });
+    world.clear_slow_event_handler();
+
+    group.bench_function("expose flood", |b| {
+        b.iter(|| {
+            for i in 0..EVENTS {
+                view.obscure_region(Rect {
+                    x: (i % 200) as i32,
+                    y: (i / 200 % 200) as i32,
+                    w: 1,
+                    h: 1,
+                });
+            }
+            world.poll().unwrap();
+        })
+    });
+
+    // the events above are dispatched directly, these go through the event queue of the window system and `World::update`
+    group.throughput(Throughput::Elements(SENT_EVENTS as u64));
+    group.bench_function("sent motion events", |b| {
+        b.iter(|| {
+            let target = counter.load(Ordering::Relaxed) + SENT_EVENTS;
+            for event in &events[..SENT_EVENTS] {
+                view.send_event(event.clone()).unwrap();
+            }
+            while counter.load(Ordering::Relaxed) < target {
+                world.update(Some(Duration::from_millis(100))).unwrap();
+            }
+        })
+    });
+
+    // exposes are only dispatched to a mapped view
+    view.show().unwrap();
+    while exposes.load(Ordering::Relaxed) == 0 {
+        world.update(Some(Duration::from_millis(100))).unwrap();
+    }
+
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("redisplay", |b| {
+        b.iter(|| {
+            let target = exposes.load(Ordering::Relaxed) + 1;
+            view.obscure_view();
+            while exposes.load(Ordering::Relaxed) < target {
+                world.update(Some(Duration::from_millis(100))).unwrap();
+            }
+        })
+    });
+
+    group.finish();
+    black_box(counter.load(Ordering::Relaxed));
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
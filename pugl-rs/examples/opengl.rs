@@ -1,4 +1,4 @@
-use pugl_rs::{Event, OpenGl, World};
+use pugl_rs::{ControlFlow, Event, OpenGl, World};
 
 fn main() {
     let mut world = World::new_program().unwrap();
@@ -10,7 +10,7 @@ fn main() {
         .with_size(200, 200)
         .with_event_handler(|view, event| {
             if matches!(event, Event::Close) {
-                std::process::exit(0);
+                view.world().quit();
             }
 
             if matches!(event, Event::Update) {
@@ -34,9 +34,7 @@ fn main() {
         .realize()
         .unwrap();
 
-    view.show_aggressive();
+    view.show_aggressive().unwrap();
 
-    loop {
-        let _ = world.update(None);
-    }
+    world.run(|_| ControlFlow::Wait).unwrap();
 }
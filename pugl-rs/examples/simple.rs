@@ -7,10 +7,6 @@ fn main() {
         .with_resizable(false)
         .with_size(200, 200)
         .with_event_handler(|view, event| {
-            if matches!(event, Event::Close) {
-                std::process::exit(0);
-            }
-
             if matches!(
                 event,
                 Event::ButtonPress {
@@ -18,7 +14,7 @@ fn main() {
                     ..
                 }
             ) {
-                view.paste_clipboard();
+                let _ = view.paste_clipboard();
             }
 
             if matches!(
@@ -28,7 +24,7 @@ fn main() {
                     ..
                 }
             ) {
-                view.copy_clipboard("waow");
+                let _ = view.copy_clipboard("waow");
             }
 
             if matches!(event, Event::Update) {
@@ -40,9 +36,7 @@ fn main() {
         .realize()
         .unwrap();
 
-    view.show_aggressive();
+    view.show_aggressive().unwrap();
 
-    loop {
-        let _ = world.update(None);
-    }
+    world.run_until_all_views_closed().unwrap();
 }
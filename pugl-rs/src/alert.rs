@@ -0,0 +1,345 @@
+use crate::{Backend, NativeView, View};
+
+/// The icon of an [`Alert`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AlertLevel {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+/// The buttons of an [`Alert`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AlertButtons {
+    #[default]
+    Ok,
+    OkCancel,
+    YesNo,
+}
+
+/// The button chosen in an [`Alert`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlertResponse {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
+
+impl AlertButtons {
+    /// The response for the first (confirming) button, or the second button.
+    fn response(self, confirmed: bool) -> AlertResponse {
+        match (self, confirmed) {
+            (AlertButtons::Ok, _) => AlertResponse::Ok,
+            (AlertButtons::OkCancel, true) => AlertResponse::Ok,
+            (AlertButtons::OkCancel, false) => AlertResponse::Cancel,
+            (AlertButtons::YesNo, true) => AlertResponse::Yes,
+            (AlertButtons::YesNo, false) => AlertResponse::No,
+        }
+    }
+}
+
+/// A native modal message box, for reporting errors or asking for confirmation in applications without a widget toolkit.
+///
+/// The alert blocks until it is closed, so the views are not redrawn in the meantime.
+/// - Windows: `MessageBoxW`, owned by the window of the parent view.
+/// - MacOS: `NSAlert`, which is application-modal instead of being attached to the parent window as a sheet.
+/// - X11: `zenity`, `kdialog` or `xmessage`, whichever is installed first, attached to the parent view if supported.
+///
+/// Closing the alert without choosing a button (or not being able to show it at all) is reported as
+/// [`AlertResponse::Cancel`] or [`AlertResponse::No`].
+/// ```no_run
+/// # use pugl_rs::*;
+/// # fn close(view: &View<()>) {
+/// let response = Alert::new("Save the changes before closing?")
+///     .with_parent(view)
+///     .with_level(AlertLevel::Warning)
+///     .with_buttons(AlertButtons::YesNo)
+///     .show();
+///
+/// if response == AlertResponse::Yes {
+///     // ...
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Alert {
+    title: String,
+    message: String,
+    level: AlertLevel,
+    buttons: AlertButtons,
+    parent: Option<NativeView>,
+}
+
+impl Alert {
+    /// Creates an [`AlertLevel::Info`] alert with the message and an "OK" button.
+    pub fn new(message: &str) -> Self {
+        Self {
+            title: String::new(),
+            message: message.to_owned(),
+            level: AlertLevel::default(),
+            buttons: AlertButtons::default(),
+            parent: None,
+        }
+    }
+
+    /// Set the title of the alert window (MacOS shows it in bold above the message instead).
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+
+    /// Set the icon of the alert.
+    pub fn with_level(mut self, level: AlertLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the buttons of the alert.
+    pub fn with_buttons(mut self, buttons: AlertButtons) -> Self {
+        self.buttons = buttons;
+        self
+    }
+
+    /// Make the alert a transient dialog of the window of the view, so it stays on top of it.
+    pub fn with_parent<B: Backend>(mut self, view: &View<B>) -> Self {
+        self.parent = Some(view.native());
+        self
+    }
+
+    /// Shows the alert and returns the chosen button.
+    pub fn show(&self) -> AlertResponse {
+        self.buttons.response(platform::show(self).unwrap_or(false))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Alert, AlertButtons, AlertLevel};
+    use std::process::Command;
+
+    /// Returns whether the first button was chosen, or `None` if there is no dialog available.
+    pub fn show(alert: &Alert) -> Option<bool> {
+        zenity(alert)
+            .or_else(|| kdialog(alert))
+            .or_else(|| xmessage(alert))
+    }
+
+    /// Returns `None` if the command is not installed.
+    fn status(mut command: Command) -> Option<bool> {
+        command.status().ok().map(|status| status.success())
+    }
+
+    fn zenity(alert: &Alert) -> Option<bool> {
+        let mut command = Command::new("zenity");
+
+        match (alert.buttons, alert.level) {
+            (AlertButtons::Ok, AlertLevel::Info) => command.arg("--info"),
+            (AlertButtons::Ok, AlertLevel::Warning) => command.arg("--warning"),
+            (AlertButtons::Ok, AlertLevel::Error) => command.arg("--error"),
+            (AlertButtons::OkCancel, _) => {
+                command.args(["--question", "--ok-label=OK", "--cancel-label=Cancel"])
+            }
+            (AlertButtons::YesNo, _) => {
+                command.args(["--question", "--ok-label=Yes", "--cancel-label=No"])
+            }
+        };
+
+        if alert.buttons != AlertButtons::Ok {
+            command.arg(match alert.level {
+                AlertLevel::Info => "--icon-name=dialog-question",
+                AlertLevel::Warning => "--icon-name=dialog-warning",
+                AlertLevel::Error => "--icon-name=dialog-error",
+            });
+        }
+
+        if !alert.title.is_empty() {
+            command.arg(format!("--title={}", alert.title));
+        }
+
+        if let Some(parent) = alert.parent {
+            command.arg(format!("--attach={}", parent.as_raw()));
+        }
+
+        command.args(["--no-markup", &format!("--text={}", alert.message)]);
+        status(command)
+    }
+
+    fn kdialog(alert: &Alert) -> Option<bool> {
+        let mut command = Command::new("kdialog");
+
+        if !alert.title.is_empty() {
+            command.args(["--title", &alert.title]);
+        }
+
+        if let Some(parent) = alert.parent {
+            command.args(["--attach", &parent.as_raw().to_string()]);
+        }
+
+        match (alert.buttons, alert.level) {
+            (AlertButtons::Ok, AlertLevel::Info) => command.arg("--msgbox"),
+            (AlertButtons::Ok, AlertLevel::Warning) => command.arg("--sorry"),
+            (AlertButtons::Ok, AlertLevel::Error) => command.arg("--error"),
+            (AlertButtons::OkCancel, _) => command.args([
+                "--yes-label",
+                "OK",
+                "--no-label",
+                "Cancel",
+                "--warningyesno",
+            ]),
+            (AlertButtons::YesNo, AlertLevel::Info) => command.arg("--yesno"),
+            (AlertButtons::YesNo, _) => command.arg("--warningyesno"),
+        };
+
+        command.arg(&alert.message);
+        status(command)
+    }
+
+    fn xmessage(alert: &Alert) -> Option<bool> {
+        let mut command = Command::new("xmessage");
+        command.args(["-center", "-buttons"]);
+        command.arg(match alert.buttons {
+            AlertButtons::Ok => "OK:0",
+            AlertButtons::OkCancel => "OK:0,Cancel:1",
+            AlertButtons::YesNo => "Yes:0,No:1",
+        });
+
+        // xmessage has no title, no icon and can't be attached to a window
+        if alert.title.is_empty() {
+            command.arg(&alert.message);
+        } else {
+            command.arg(format!("{}\n\n{}", alert.title, alert.message));
+        }
+
+        status(command)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{Alert, AlertButtons, AlertLevel};
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn MessageBoxW(hwnd: isize, text: *const u16, caption: *const u16, kind: u32) -> i32;
+        fn GetAncestor(hwnd: isize, flags: u32) -> isize;
+    }
+
+    const GA_ROOT: u32 = 2;
+
+    const MB_OK: u32 = 0x0;
+    const MB_OKCANCEL: u32 = 0x1;
+    const MB_YESNO: u32 = 0x4;
+    const MB_ICONERROR: u32 = 0x10;
+    const MB_ICONWARNING: u32 = 0x30;
+    const MB_ICONINFORMATION: u32 = 0x40;
+    const MB_TASKMODAL: u32 = 0x2000;
+
+    const IDOK: i32 = 1;
+    const IDYES: i32 = 6;
+
+    pub fn show(alert: &Alert) -> Option<bool> {
+        let kind = match alert.buttons {
+            AlertButtons::Ok => MB_OK,
+            AlertButtons::OkCancel => MB_OKCANCEL,
+            AlertButtons::YesNo => MB_YESNO,
+        } | match alert.level {
+            AlertLevel::Info => MB_ICONINFORMATION,
+            AlertLevel::Warning => MB_ICONWARNING,
+            AlertLevel::Error => MB_ICONERROR,
+        };
+
+        let owner = match alert.parent {
+            Some(parent) => unsafe { GetAncestor(parent.as_raw() as isize, GA_ROOT) },
+            None => 0,
+        };
+
+        // without an owner, disable the other windows of the thread instead
+        let kind = if owner == 0 {
+            kind | MB_TASKMODAL
+        } else {
+            kind
+        };
+
+        let text = wide(&alert.message);
+        let caption = wide(&alert.title);
+        let result = unsafe { MessageBoxW(owner, text.as_ptr(), caption.as_ptr(), kind) };
+
+        match result {
+            0 => None,
+            result => Some(result == IDOK || result == IDYES),
+        }
+    }
+
+    fn wide(string: &str) -> Vec<u16> {
+        string.encode_utf16().chain([0]).collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Alert, AlertButtons, AlertLevel};
+    use crate::{is_main_thread, objc, with_c_str};
+
+    const NS_ALERT_STYLE_WARNING: usize = 0;
+    const NS_ALERT_STYLE_INFORMATIONAL: usize = 1;
+    const NS_ALERT_STYLE_CRITICAL: usize = 2;
+    const NS_ALERT_FIRST_BUTTON_RETURN: isize = 1000;
+
+    pub fn show(alert: &Alert) -> Option<bool> {
+        // AppKit alerts can only be used on the main thread
+        if !is_main_thread() {
+            return None;
+        }
+
+        unsafe {
+            let ns_alert = objc::send_class(c"NSAlert", c"new");
+            if ns_alert.is_null() {
+                return None;
+            }
+
+            // the title is the bold message text, the message is the informative text below it
+            let (text, informative) = if alert.title.is_empty() {
+                (alert.message.as_str(), "")
+            } else {
+                (alert.title.as_str(), alert.message.as_str())
+            };
+
+            let text = with_c_str(text, |text| objc::ns_string(text));
+            let informative = with_c_str(informative, |informative| objc::ns_string(informative));
+            objc::send_with(ns_alert, c"setMessageText:", text);
+            objc::send_with(ns_alert, c"setInformativeText:", informative);
+
+            let style = match alert.level {
+                AlertLevel::Info => NS_ALERT_STYLE_INFORMATIONAL,
+                AlertLevel::Warning => NS_ALERT_STYLE_WARNING,
+                AlertLevel::Error => NS_ALERT_STYLE_CRITICAL,
+            };
+            objc::send_index(ns_alert, c"setAlertStyle:", style);
+
+            let labels: &[&std::ffi::CStr] = match alert.buttons {
+                AlertButtons::Ok => &[c"OK"],
+                AlertButtons::OkCancel => &[c"OK", c"Cancel"],
+                AlertButtons::YesNo => &[c"Yes", c"No"],
+            };
+            for label in labels {
+                objc::send_with(ns_alert, c"addButtonWithTitle:", objc::ns_string(label));
+            }
+
+            let response = objc::send_integer(ns_alert, c"runModal");
+            objc::send(ns_alert, c"release");
+
+            Some(response == NS_ALERT_FIRST_BUTTON_RETURN)
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::Alert;
+
+    pub fn show(_: &Alert) -> Option<bool> {
+        None
+    }
+}
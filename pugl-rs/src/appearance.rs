@@ -0,0 +1,718 @@
+use crate::{Backend, IdleCallbackId, View, World};
+
+/// The color scheme (light or dark theme) preferred by the user, see [`World::color_scheme`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    /// The user has no preference, or the preference is not known
+    NoPreference,
+}
+
+impl World {
+    /// Returns the color scheme preferred by the user, so the user interface can follow the theme of the system
+    /// (and match [`UnrealizedView::with_dark_frame`](crate::UnrealizedView::with_dark_frame) to it).
+    ///
+    /// - Windows: the "app mode" setting (`AppsUseLightTheme` in the registry).
+    /// - MacOS: the appearance selected in the system settings (`AppleInterfaceStyle`).
+    /// - X11: the GTK theme name of the XSettings manager (`Net/ThemeName`) of the desktop environment, or the `GTK_THEME` environment variable.
+    ///   The default theme (`Adwaita`) is reported as [`ColorScheme::NoPreference`], since it is used with either preference.
+    ///   The XDG desktop portal is not queried, since it requires D-Bus.
+    pub fn color_scheme(&self) -> ColorScheme {
+        platform::color_scheme(self)
+    }
+
+    /// Add a callback that is called with the new color scheme whenever the color scheme preferred by the user changes, see [`World::color_scheme`].
+    ///
+    /// The color scheme is only checked again after the system reports a change of the settings:
+    /// - Windows: `WM_SETTINGCHANGE`, which is received by the hidden view of [`World::waker`].
+    /// - MacOS: the `AppleInterfaceThemeChangedNotification` distributed notification.
+    /// - X11: a change of the settings of the XSettings manager. Without an XSettings manager the callback is never called.
+    ///
+    /// The notifications wake up the main loop, and the callback is called from an idle callback (see [`World::add_idle_callback`])
+    /// that can be removed with [`World::remove_idle_callback`].
+    pub fn add_color_scheme_callback(
+        &self,
+        mut callback: impl FnMut(&World, ColorScheme) + Send + 'static,
+    ) -> IdleCallbackId {
+        let mut current = self.color_scheme();
+        let mut watcher = platform::Watcher::new(self);
+
+        self.add_idle_callback(move |world| {
+            if !watcher.as_mut().is_some_and(|watcher| watcher.changed()) {
+                return;
+            }
+
+            let scheme = world.color_scheme();
+            if scheme != current {
+                current = scheme;
+                callback(world, scheme);
+            }
+        })
+    }
+}
+
+impl<B: Backend> View<B> {
+    /// Returns the ICC profile of the display the view is on, so color-accurate visualizations can transform their output on wide-gamut displays.
+    ///
+    /// Returns `None` if the display has no profile, in which case sRGB can be assumed.
+    /// - Windows: the profile of the device context of the view (`GetICMProfileW`).
+    /// - MacOS: the color space of the screen of the window (`NSScreen.colorSpace`).
+    /// - X11: the `_ICC_PROFILE` property of the root window, set by color management daemons like `colord`.
+    ///   This is the profile of the first monitor, since `pugl` doesn't report which monitor the view is on.
+    pub fn color_profile(&self) -> Option<Vec<u8>> {
+        platform::color_profile(self.world(), self.native().as_raw())
+            .filter(|profile| !profile.is_empty())
+    }
+}
+
+/// Returns the color scheme for a theme name like `Adwaita-dark` or `Adwaita:dark`.
+///
+/// `Adwaita` is the default theme of GTK, which the desktop environments that follow the color scheme
+/// of the XDG desktop portal keep regardless of the preference, so it says nothing about the preference.
+#[cfg(target_os = "linux")]
+fn theme_color_scheme(theme: &str) -> ColorScheme {
+    let theme = theme.to_ascii_lowercase();
+    if theme.contains("dark") {
+        ColorScheme::Dark
+    } else if theme == "adwaita" || theme.is_empty() {
+        ColorScheme::NoPreference
+    } else {
+        ColorScheme::Light
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{ColorScheme, theme_color_scheme};
+    use crate::{
+        World,
+        x11::{event_hook, xlib::*},
+    };
+    use std::{
+        mem::MaybeUninit,
+        os::raw::{c_char, c_long, c_ulong, c_void},
+        ptr::null_mut,
+        slice::from_raw_parts,
+        sync::{
+            Mutex, PoisonError,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    /// Number of XSettings changes seen by [`settings_event`].
+    static CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+    /// The `_XSETTINGS_SETTINGS` and `MANAGER` atoms, which are the same for every connection to the server.
+    static ATOMS: Mutex<(c_ulong, c_ulong)> = Mutex::new((0, 0));
+
+    /// Watches the XSettings manager for [`World::add_color_scheme_callback`].
+    ///
+    /// `pugl` drops the events of windows it doesn't know, so the events of the manager are picked up by an [`event_hook`].
+    pub struct Watcher {
+        display: Display,
+        owner: c_ulong,
+        seen: usize,
+    }
+
+    unsafe impl Send for Watcher {}
+
+    impl Watcher {
+        pub fn new(world: &World) -> Option<Self> {
+            unsafe {
+                let display = world.native().as_raw();
+                if display.is_null() {
+                    return None;
+                }
+
+                *ATOMS.lock().unwrap() = (
+                    XInternAtom(display, c"_XSETTINGS_SETTINGS".as_ptr(), 0),
+                    XInternAtom(display, c"MANAGER".as_ptr(), 0),
+                );
+                event_hook::add(display, PROPERTY_NOTIFY, settings_event);
+                event_hook::add(display, CLIENT_MESSAGE, settings_event);
+
+                // a new manager announces itself to the clients of the root window
+                select_input(display, XDefaultRootWindow(display), STRUCTURE_NOTIFY_MASK);
+
+                let mut watcher = Self {
+                    display,
+                    owner: 0,
+                    seen: CHANGES.load(Ordering::Relaxed),
+                };
+                watcher.watch_owner();
+                Some(watcher)
+            }
+        }
+
+        /// Selects the property changes of the current manager.
+        fn watch_owner(&mut self) {
+            unsafe {
+                let owner = settings_owner(self.display);
+                if owner != 0 && owner != self.owner {
+                    select_input(self.display, owner, PROPERTY_CHANGE_MASK);
+                }
+
+                self.owner = owner;
+            }
+        }
+
+        pub fn changed(&mut self) -> bool {
+            let changes = CHANGES.load(Ordering::Relaxed);
+            if changes == self.seen {
+                return false;
+            }
+
+            self.seen = changes;
+            self.watch_owner();
+            true
+        }
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            unsafe {
+                event_hook::remove(self.display, PROPERTY_NOTIFY, settings_event);
+                event_hook::remove(self.display, CLIENT_MESSAGE, settings_event);
+            }
+        }
+    }
+
+    fn settings_event(_: Display, event: *const c_void) {
+        let event = unsafe { &*(event as *const XEventHeader) };
+        let (settings, manager) = *ATOMS.lock().unwrap_or_else(PoisonError::into_inner);
+        let changed = match event.type_ {
+            PROPERTY_NOTIFY => event.atom == settings,
+            CLIENT_MESSAGE => event.atom == manager && event.format == 32,
+            _ => false,
+        };
+
+        if changed {
+            CHANGES.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Adds to the events the client selected on a window, which may already be selected by `pugl`.
+    unsafe fn select_input(display: Display, window: c_ulong, mask: c_long) {
+        unsafe {
+            let mut attributes = MaybeUninit::<XWindowAttributes>::zeroed();
+            if XGetWindowAttributes(display, window, attributes.as_mut_ptr()) != 0 {
+                let selected = attributes.assume_init().your_event_mask;
+                XSelectInput(display, window, selected | mask);
+                XFlush(display);
+            }
+        }
+    }
+
+    pub fn color_scheme(world: &World) -> ColorScheme {
+        if let Some(theme) = unsafe { xsettings_theme(world.native().as_raw()) } {
+            return theme_color_scheme(&theme);
+        }
+
+        match std::env::var("GTK_THEME") {
+            Ok(theme) => theme_color_scheme(&theme),
+            Err(_) => ColorScheme::NoPreference,
+        }
+    }
+
+    pub fn color_profile(world: &World, _: usize) -> Option<Vec<u8>> {
+        unsafe {
+            let display = world.native().as_raw();
+            if display.is_null() {
+                return None;
+            }
+
+            let property = XInternAtom(display, c"_ICC_PROFILE".as_ptr(), 1);
+            if property == 0 {
+                return None;
+            }
+
+            get_property(display, XDefaultRootWindow(display), property)
+        }
+    }
+
+    /// Reads an 8-bit window property.
+    unsafe fn get_property(
+        display: *mut c_void,
+        window: c_ulong,
+        property: c_ulong,
+    ) -> Option<Vec<u8>> {
+        unsafe {
+            let mut actual_type = 0;
+            let mut actual_format = 0;
+            let mut items = 0;
+            let mut bytes_after = 0;
+            let mut data = null_mut();
+            let status = XGetWindowProperty(
+                display,
+                window,
+                property,
+                0,
+                c_long::MAX / 4,
+                0,
+                ANY_PROPERTY_TYPE,
+                &mut actual_type,
+                &mut actual_format,
+                &mut items,
+                &mut bytes_after,
+                &mut data,
+            );
+
+            if status != SUCCESS || data.is_null() {
+                return None;
+            }
+
+            let value = if actual_format == 8 {
+                Some(from_raw_parts(data, items as usize).to_vec())
+            } else {
+                None
+            };
+
+            XFree(data as *mut c_void);
+            value
+        }
+    }
+
+    /// Returns the window of the XSettings manager of the default screen, or `0` if there is no manager.
+    unsafe fn settings_owner(display: Display) -> c_ulong {
+        unsafe {
+            let selection = format!("_XSETTINGS_S{}\0", XDefaultScreen(display));
+            let selection = XInternAtom(display, selection.as_ptr() as *const c_char, 0);
+            XGetSelectionOwner(display, selection)
+        }
+    }
+
+    /// Reads `Net/ThemeName` from the settings of the XSettings manager of the default screen.
+    unsafe fn xsettings_theme(display: *mut c_void) -> Option<String> {
+        unsafe {
+            if display.is_null() {
+                return None;
+            }
+
+            let owner = settings_owner(display);
+            if owner == 0 {
+                return None;
+            }
+
+            let property = XInternAtom(display, c"_XSETTINGS_SETTINGS".as_ptr(), 0);
+            parse_theme(&get_property(display, owner, property)?)
+        }
+    }
+
+    /// Finds the `Net/ThemeName` string in the XSettings wire format.
+    fn parse_theme(data: &[u8]) -> Option<String> {
+        let big_endian = *data.first()? == 1;
+        let u16_at = |at: usize| {
+            let bytes = data.get(at..at + 2)?.try_into().ok()?;
+            Some(if big_endian {
+                u16::from_be_bytes(bytes)
+            } else {
+                u16::from_le_bytes(bytes)
+            } as usize)
+        };
+        let u32_at = |at: usize| {
+            let bytes = data.get(at..at + 4)?.try_into().ok()?;
+            Some(if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            } as usize)
+        };
+        let pad = |len: usize| (len + 3) & !3;
+
+        const TYPE_INTEGER: u8 = 0;
+        const TYPE_STRING: u8 = 1;
+        const TYPE_COLOR: u8 = 2;
+
+        let count = u32_at(8)?;
+        let mut at = 12;
+        for _ in 0..count {
+            let kind = *data.get(at)?;
+            let name_len = u16_at(at + 2)?;
+            let name = data.get(at + 4..at + 4 + name_len)?;
+            // type, padding, name length, name and the serial of the last change
+            at += 4 + pad(name_len) + 4;
+
+            match kind {
+                TYPE_INTEGER => at += 4,
+                TYPE_COLOR => at += 8,
+                TYPE_STRING => {
+                    let len = u32_at(at)?;
+                    let value = data.get(at + 4..at + 4 + len)?;
+                    if name == b"Net/ThemeName" {
+                        return Some(String::from_utf8_lossy(value).into_owned());
+                    }
+
+                    at += 4 + pad(len);
+                }
+                _ => return None,
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::ColorScheme;
+    use crate::{World, WorldWaker};
+    use std::{
+        ffi::OsString,
+        os::{raw::c_void, windows::ffi::OsStringExt},
+        ptr::null_mut,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    #[link(name = "advapi32")]
+    unsafe extern "system" {
+        fn RegGetValueW(
+            key: isize,
+            sub_key: *const u16,
+            value: *const u16,
+            flags: u32,
+            kind: *mut u32,
+            data: *mut c_void,
+            size: *mut u32,
+        ) -> i32;
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetDC(hwnd: isize) -> isize;
+        fn ReleaseDC(hwnd: isize, hdc: isize) -> i32;
+    }
+
+    #[link(name = "gdi32")]
+    unsafe extern "system" {
+        fn GetICMProfileW(hdc: isize, len: *mut u32, file_name: *mut u16) -> i32;
+    }
+
+    type SubclassProc = unsafe extern "system" fn(isize, u32, usize, isize, usize, usize) -> isize;
+
+    #[link(name = "comctl32")]
+    unsafe extern "system" {
+        fn SetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: usize) -> i32;
+        fn RemoveWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize) -> i32;
+        fn DefSubclassProc(hwnd: isize, message: u32, wparam: usize, lparam: isize) -> isize;
+    }
+
+    const HKEY_CURRENT_USER: isize = 0x8000_0001u32 as i32 as isize;
+    const RRF_RT_REG_DWORD: u32 = 0x10;
+    const ERROR_SUCCESS: i32 = 0;
+    const WM_SETTINGCHANGE: u32 = 0x001a;
+    const WM_NCDESTROY: u32 = 0x0082;
+
+    /// Watches the settings for [`World::add_color_scheme_callback`].
+    ///
+    /// `WM_SETTINGCHANGE` is broadcast to every top-level window, so it is received by the hidden view of the waker,
+    /// which is subclassed to catch it before `pugl` ignores it.
+    pub struct Watcher {
+        hwnd: isize,
+        /// Shared with the subclass, the address is the id of the subclass
+        shared: Box<Shared>,
+    }
+
+    struct Shared {
+        changed: AtomicBool,
+        waker: WorldWaker,
+    }
+
+    impl Watcher {
+        pub fn new(world: &World) -> Option<Self> {
+            let shared = Box::new(Shared {
+                changed: AtomicBool::new(false),
+                waker: world.waker().ok()?,
+            });
+            let hwnd = world.waker_native()? as isize;
+            let data = &*shared as *const Shared as usize;
+
+            match unsafe { SetWindowSubclass(hwnd, subclass_proc, data, data) } {
+                0 => None,
+                _ => Some(Self { hwnd, shared }),
+            }
+        }
+
+        pub fn changed(&mut self) -> bool {
+            self.shared.changed.swap(false, Ordering::Relaxed)
+        }
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            let id = &*self.shared as *const Shared as usize;
+            unsafe {
+                RemoveWindowSubclass(self.hwnd, subclass_proc, id);
+            }
+        }
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: isize,
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+        id: usize,
+        shared: usize,
+    ) -> isize {
+        unsafe {
+            match message {
+                WM_SETTINGCHANGE => {
+                    // the message may be handled while `pugl` waits for events, without interrupting the wait
+                    let shared = &*(shared as *const Shared);
+                    shared.changed.store(true, Ordering::Relaxed);
+                    shared.waker.wake();
+                }
+                WM_NCDESTROY => {
+                    RemoveWindowSubclass(hwnd, subclass_proc, id);
+                }
+                _ => {}
+            }
+
+            DefSubclassProc(hwnd, message, wparam, lparam)
+        }
+    }
+
+    pub fn color_scheme(_: &World) -> ColorScheme {
+        let sub_key = wide("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
+        let value = wide("AppsUseLightTheme");
+        let mut data = 0u32;
+        let mut size = size_of::<u32>() as u32;
+
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                sub_key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                null_mut(),
+                &mut data as *mut u32 as *mut c_void,
+                &mut size,
+            )
+        };
+
+        match (status, data) {
+            (ERROR_SUCCESS, 0) => ColorScheme::Dark,
+            (ERROR_SUCCESS, _) => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    }
+
+    pub fn color_profile(_: &World, hwnd: usize) -> Option<Vec<u8>> {
+        unsafe {
+            let hdc = GetDC(hwnd as isize);
+            if hdc == 0 {
+                return None;
+            }
+
+            // MAX_PATH, the profiles are in the system color directory
+            let mut name = [0u16; 260];
+            let mut len = name.len() as u32;
+            let found = GetICMProfileW(hdc, &mut len, name.as_mut_ptr()) != 0;
+            ReleaseDC(hwnd as isize, hdc);
+
+            if !found {
+                return None;
+            }
+
+            let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            let path = OsString::from_wide(&name[..len]);
+            std::fs::read(path).ok()
+        }
+    }
+
+    fn wide(string: &str) -> Vec<u16> {
+        string.encode_utf16().chain([0]).collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::ColorScheme;
+    use crate::{World, WorldWaker, objc};
+    use std::{
+        os::raw::c_void,
+        ptr::null,
+        sync::{
+            Mutex, OnceLock, PoisonError,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    /// Number of theme changes reported by the system.
+    static CHANGES: AtomicUsize = AtomicUsize::new(0);
+
+    /// The wakers of the worlds with a watcher, woken up by the notification, by the address of the watcher.
+    static WAKERS: Mutex<Vec<(usize, WorldWaker)>> = Mutex::new(Vec::new());
+
+    /// The layout of an Objective-C block, see the Clang documentation of the block ABI.
+    #[repr(C)]
+    struct Block {
+        isa: *const c_void,
+        flags: i32,
+        reserved: i32,
+        invoke: unsafe extern "C" fn(*const Block, objc::Id),
+        descriptor: *const BlockDescriptor,
+    }
+
+    #[repr(C)]
+    struct BlockDescriptor {
+        reserved: usize,
+        size: usize,
+    }
+
+    unsafe impl Send for Block {}
+    unsafe impl Sync for Block {}
+
+    const BLOCK_IS_GLOBAL: i32 = 1 << 28;
+
+    static DESCRIPTOR: BlockDescriptor = BlockDescriptor {
+        reserved: 0,
+        size: size_of::<Block>(),
+    };
+
+    unsafe extern "C" {
+        static _NSConcreteGlobalBlock: c_void;
+    }
+
+    /// A block without captures, which doesn't have to be copied or released.
+    fn notification_block() -> &'static Block {
+        static BLOCK: OnceLock<Block> = OnceLock::new();
+        BLOCK.get_or_init(|| Block {
+            isa: unsafe { &raw const _NSConcreteGlobalBlock },
+            flags: BLOCK_IS_GLOBAL,
+            reserved: 0,
+            invoke: theme_changed,
+            descriptor: &DESCRIPTOR,
+        })
+    }
+
+    unsafe extern "C" fn theme_changed(_: *const Block, _notification: objc::Id) {
+        CHANGES.fetch_add(1, Ordering::Relaxed);
+
+        // the notification is handled by the run loop, which doesn't interrupt the wait for events of `pugl`
+        for (_, waker) in WAKERS.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+            waker.wake();
+        }
+    }
+
+    /// Observes `AppleInterfaceThemeChangedNotification` for [`World::add_color_scheme_callback`].
+    pub struct Watcher {
+        observer: objc::Id,
+        seen: Box<AtomicUsize>,
+    }
+
+    unsafe impl Send for Watcher {}
+
+    impl Watcher {
+        pub fn new(world: &World) -> Option<Self> {
+            unsafe {
+                let waker = world.waker().ok()?;
+                let center = objc::send_class(c"NSDistributedNotificationCenter", c"defaultCenter");
+                let name = objc::ns_string(c"AppleInterfaceThemeChangedNotification");
+                let block = notification_block() as *const Block as *const c_void;
+                let observer = objc::send_with_4(
+                    center,
+                    c"addObserverForName:object:queue:usingBlock:",
+                    [name as *const c_void, null(), null(), block],
+                );
+                if observer.is_null() {
+                    return None;
+                }
+
+                let watcher = Self {
+                    observer: objc::send(observer, c"retain"),
+                    seen: Box::new(AtomicUsize::new(CHANGES.load(Ordering::Relaxed))),
+                };
+
+                let id = &*watcher.seen as *const AtomicUsize as usize;
+                WAKERS.lock().unwrap().push((id, waker));
+                Some(watcher)
+            }
+        }
+
+        pub fn changed(&mut self) -> bool {
+            let changes = CHANGES.load(Ordering::Relaxed);
+            self.seen.swap(changes, Ordering::Relaxed) != changes
+        }
+    }
+
+    impl Drop for Watcher {
+        fn drop(&mut self) {
+            let id = &*self.seen as *const AtomicUsize as usize;
+            WAKERS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .retain(|&(watcher, _)| watcher != id);
+
+            unsafe {
+                let center = objc::send_class(c"NSDistributedNotificationCenter", c"defaultCenter");
+                objc::send_with(center, c"removeObserver:", self.observer);
+                objc::send(self.observer, c"release");
+            }
+        }
+    }
+
+    pub fn color_scheme(_: &World) -> ColorScheme {
+        unsafe {
+            let defaults = objc::send_class(c"NSUserDefaults", c"standardUserDefaults");
+            let key = objc::ns_string(c"AppleInterfaceStyle");
+            if defaults.is_null() || key.is_null() {
+                return ColorScheme::NoPreference;
+            }
+
+            // the key is only set in dark mode
+            let style = objc::send_with(defaults, c"stringForKey:", key);
+            match objc::to_string(style) {
+                Some(style) if style == "Dark" => ColorScheme::Dark,
+                _ => ColorScheme::Light,
+            }
+        }
+    }
+
+    pub fn color_profile(_: &World, view: usize) -> Option<Vec<u8>> {
+        unsafe {
+            let window = objc::send(view as objc::Id, c"window");
+            let screen = objc::send(window, c"screen");
+            let color_space = objc::send(screen, c"colorSpace");
+            let profile = objc::send(color_space, c"ICCProfileData");
+            if profile.is_null() {
+                return None;
+            }
+
+            let bytes = objc::send(profile, c"bytes") as *const u8;
+            let len = objc::send_integer(profile, c"length").max(0) as usize;
+            if bytes.is_null() {
+                return None;
+            }
+
+            Some(std::slice::from_raw_parts(bytes, len).to_vec())
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::ColorScheme;
+    use crate::World;
+
+    pub enum Watcher {}
+
+    impl Watcher {
+        pub fn new(_: &World) -> Option<Self> {
+            None
+        }
+
+        pub fn changed(&mut self) -> bool {
+            match *self {}
+        }
+    }
+
+    pub fn color_scheme(_: &World) -> ColorScheme {
+        ColorScheme::NoPreference
+    }
+
+    pub fn color_profile(_: &World, _: usize) -> Option<Vec<u8>> {
+        None
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Backend, UnrealizedView};
+
+/// A window size in the style of `winit`, which is either in physical pixels or in logical units.
+///
+/// Logical sizes are multiplied by [`UnrealizedView::system_scale`] when applied to a view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowSize {
+    Physical(u32, u32),
+    Logical(f64, f64),
+}
+
+impl WindowSize {
+    /// Returns the size in physical pixels for the given scale factor.
+    pub fn to_physical(self, scale: f64) -> (u32, u32) {
+        match self {
+            Self::Physical(width, height) => (width, height),
+            Self::Logical(width, height) => (
+                (width * scale).round() as u32,
+                (height * scale).round() as u32,
+            ),
+        }
+    }
+}
+
+impl From<(u32, u32)> for WindowSize {
+    fn from((width, height): (u32, u32)) -> Self {
+        Self::Physical(width, height)
+    }
+}
+
+/// Window attributes in the style of `winit`'s `WindowAttributes`, to ease porting of `winit`-based user interfaces.
+///
+/// The attributes are applied with [`UnrealizedView::with_window_attributes`], attributes that are `None` leave the view unchanged.
+/// `pugl` can't create undecorated or transparent windows, so there are no `decorations` and `transparent` attributes
+/// (a transparent framebuffer is configured by the backend, like `OpenGl::bits_alpha`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WindowAttributes {
+    pub title: Option<String>,
+    pub inner_size: Option<WindowSize>,
+    pub min_inner_size: Option<WindowSize>,
+    pub max_inner_size: Option<WindowSize>,
+    pub position: Option<(i32, i32)>,
+    pub resizable: Option<bool>,
+}
+
+impl WindowAttributes {
+    /// Set the title of the window.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the initial size of the window contents.
+    pub fn with_inner_size(mut self, size: impl Into<WindowSize>) -> Self {
+        self.inner_size = Some(size.into());
+        self
+    }
+
+    /// Set the minimum size of the window contents.
+    pub fn with_min_inner_size(mut self, size: impl Into<WindowSize>) -> Self {
+        self.min_inner_size = Some(size.into());
+        self
+    }
+
+    /// Set the maximum size of the window contents.
+    pub fn with_max_inner_size(mut self, size: impl Into<WindowSize>) -> Self {
+        self.max_inner_size = Some(size.into());
+        self
+    }
+
+    /// Set the initial position of the window in screen coordinates with an upper left origin.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Make the window resizable.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = Some(resizable);
+        self
+    }
+}
+
+impl<B: Backend> UnrealizedView<B> {
+    /// Apply `winit`-style window attributes, see [`WindowAttributes`].
+    pub fn with_window_attributes(self, attributes: &WindowAttributes) -> Self {
+        let scale = self.system_scale();
+        let mut view = self;
+
+        if let Some(title) = &attributes.title {
+            view = view.with_title(title);
+        }
+
+        if let Some(resizable) = attributes.resizable {
+            view = view.with_resizable(resizable);
+        }
+
+        if let Some(size) = attributes.inner_size {
+            let (width, height) = size.to_physical(scale);
+            view = view.with_size(width, height);
+        }
+
+        if let Some(size) = attributes.min_inner_size {
+            let (width, height) = size.to_physical(scale);
+            view = view.with_min_size(width, height);
+        }
+
+        if let Some(size) = attributes.max_inner_size {
+            let (width, height) = size.to_physical(scale);
+            view = view.with_max_size(width, height);
+        }
+
+        if let Some((x, y)) = attributes.position {
+            view = view.with_position(x, y);
+        }
+
+        view
+    }
+}
@@ -5,6 +5,7 @@ use crate::sys;
 /// Available backends are:
 /// - `()` - stub backend, no drawing
 /// - `OpenGl` - OpenGL backend, gated behind the `opengl` feature
+/// - `Cairo` - Cairo backend, gated behind the `cairo` feature
 pub trait Backend: std::fmt::Debug {
     /// The context used for drawing on the view. Can be accessed via `Event::Expose`.
     type DrawContext<'a>: std::fmt::Debug;
@@ -29,6 +30,32 @@ pub trait Backend: std::fmt::Debug {
         view: *mut sys::PuglView,
         _: crate::private::Private,
     ) -> Self::DrawContext<'a>;
+
+    /// Lowers the requirements of the view after realizing it failed, returns `false` if there is nothing left to lower.
+    #[doc(hidden)]
+    unsafe fn degrade(_view: *mut sys::PuglView, _: crate::private::Private) -> bool {
+        false
+    }
+}
+
+/// Creates the setup context of a realize or unrealize event, see [`Backend::setup`].
+pub(crate) unsafe fn setup_context<'a, B: Backend>(
+    view: *mut sys::PuglView,
+) -> B::SetupContext<'a> {
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::trace_span!("pugl_backend_setup", backend = std::any::type_name::<B>()).entered();
+
+    unsafe { B::setup(view, crate::private::Private) }
+}
+
+/// Creates the draw context of an expose event, see [`Backend::draw`].
+pub(crate) unsafe fn draw_context<'a, B: Backend>(view: *mut sys::PuglView) -> B::DrawContext<'a> {
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::trace_span!("pugl_backend_draw", backend = std::any::type_name::<B>()).entered();
+
+    unsafe { B::draw(view, crate::private::Private) }
 }
 
 impl Backend for () {
@@ -56,6 +83,56 @@ impl Backend for () {
     }
 }
 
+/// The graphics backend used by a view, see [`View::backend_kind`](crate::View::backend_kind).
+///
+/// New backends (like Vulkan) may be added in the future.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BackendKind {
+    /// The stub backend of `()`, without drawing
+    Stub,
+    OpenGl,
+    Cairo,
+}
+
+impl BackendKind {
+    /// Identifies the backend installed on the view, or returns `None` if it is not one of the backends of this crate.
+    pub(crate) unsafe fn of(view: *mut sys::PuglView) -> Option<Self> {
+        unsafe {
+            let backend = sys::puglGetBackend(view);
+            if backend.is_null() {
+                return None;
+            }
+
+            if backend == sys::puglStubBackend() {
+                return Some(BackendKind::Stub);
+            }
+
+            #[cfg(feature = "opengl")]
+            if backend == sys::puglGlBackend() {
+                return Some(BackendKind::OpenGl);
+            }
+
+            #[cfg(feature = "cairo")]
+            if backend == sys::puglCairoBackend() {
+                return Some(BackendKind::Cairo);
+            }
+
+            None
+        }
+    }
+}
+
+/// An RGBA image with rows ordered from top to bottom, see `OpenGlContext::capture_pixels` and `CairoContext::capture_pixels`.
+#[cfg(any(feature = "opengl", feature = "cairo"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturedPixels {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height * 4` bytes of RGBA data
+    pub data: Vec<u8>,
+}
+
 #[cfg(feature = "opengl")]
 pub use opengl::*;
 
@@ -80,7 +157,18 @@ mod opengl {
     pub struct OpenGl {
         pub version: OpenGlVersion,
         pub debug: bool,
+        /// Draw into a back buffer that is swapped after the [`Event::Expose`](crate::Event::Expose) handler (the default).
+        ///
+        /// A single-buffered view draws directly into the front buffer, which can lower the latency of scopes and meters,
+        /// but the drawing has to be submitted explicitly with [`OpenGlContext::flush`] at the end of the expose handler, and partially drawn frames may be visible.
+        /// - X11: only without a compositor, otherwise the compositor still presents the window at the next refresh.
+        /// - Windows and MacOS: the windows are always composited, so single buffering only saves a copy and mostly removes the vertical synchronization.
+        ///
+        /// The platform may not have a matching configuration, see [`OpenGlContext::is_double_buffered`].
         pub double_buffer: bool,
+        /// The number of vertical blanks to wait for when swapping the buffers, or `None` for the default of the platform.
+        ///
+        /// Ignored for single-buffered views.
         pub swap_interval: Option<u8>,
         pub bits_red: u8,
         pub bits_green: u8,
@@ -126,6 +214,166 @@ mod opengl {
         }
     }
 
+    impl<'a> OpenGlContext<'a> {
+        /// Returns `true` if the view is double-buffered, which may differ from [`OpenGl::double_buffer`] if the platform has no matching configuration.
+        pub fn is_double_buffered(&self) -> bool {
+            unsafe { sys::puglGetViewHint(self.view, sys::PUGL_DOUBLE_BUFFER) > 0 }
+        }
+
+        /// Submits the commands drawn so far to the display (`glFlush`).
+        ///
+        /// Single-buffered views (see [`OpenGl::double_buffer`]) should call this at the end of the [`Event::Expose`](crate::Event::Expose) handler,
+        /// since the buffers are only swapped for double-buffered views. Returns `false` if `glFlush` is not available.
+        pub fn flush(&self) -> bool {
+            type Flush = unsafe extern "system" fn();
+
+            let flush = self.get_proc_address(c"glFlush");
+            if flush.is_null() {
+                return false;
+            }
+
+            unsafe { std::mem::transmute::<*mut c_void, Flush>(flush)() };
+            true
+        }
+    }
+
+    impl<'a> OpenGlContext<'a> {
+        /// Returns the configuration that the view was actually realized with.
+        ///
+        /// This differs from the requested [`OpenGl`] if the platform adjusted it, or if the requirements were lowered by
+        /// [`UnrealizedView::with_fallback_config`](crate::UnrealizedView::with_fallback_config).
+        pub fn config(&self) -> OpenGl {
+            unsafe {
+                let hint = |hint: sys::PuglViewHint| sys::puglGetViewHint(self.view, hint);
+                let bits = |name| hint(name).clamp(0, u8::MAX as i32) as u8;
+
+                let (major, minor) = (
+                    bits(sys::PUGL_CONTEXT_VERSION_MAJOR),
+                    bits(sys::PUGL_CONTEXT_VERSION_MINOR),
+                );
+                let version = if hint(sys::PUGL_CONTEXT_API) == sys::PUGL_OPENGL_ES_API {
+                    OpenGlVersion::ES(major, minor)
+                } else if hint(sys::PUGL_CONTEXT_PROFILE) == sys::PUGL_OPENGL_CORE_PROFILE {
+                    OpenGlVersion::Core(major, minor)
+                } else {
+                    OpenGlVersion::Compat(major, minor)
+                };
+
+                OpenGl {
+                    version,
+                    debug: hint(sys::PUGL_CONTEXT_DEBUG) > 0,
+                    double_buffer: hint(sys::PUGL_DOUBLE_BUFFER) > 0,
+                    swap_interval: u8::try_from(hint(sys::PUGL_SWAP_INTERVAL)).ok(),
+                    bits_red: bits(sys::PUGL_RED_BITS),
+                    bits_green: bits(sys::PUGL_GREEN_BITS),
+                    bits_blue: bits(sys::PUGL_BLUE_BITS),
+                    bits_alpha: bits(sys::PUGL_ALPHA_BITS),
+                    bits_stencil: bits(sys::PUGL_STENCIL_BITS),
+                    bits_depth: bits(sys::PUGL_DEPTH_BITS),
+                    aa_buffers: u8::try_from(hint(sys::PUGL_SAMPLE_BUFFERS)).ok(),
+                    aa_samples: bits(sys::PUGL_SAMPLES),
+                }
+            }
+        }
+    }
+
+    impl<'a> OpenGlContext<'a> {
+        /// Reads the contents of the current framebuffer, which can be used for golden-image tests.
+        ///
+        /// This should be called at the end of the [`Event::Expose`](crate::Event::Expose) handler, after everything is drawn.
+        /// Returns `None` if `glReadPixels` is not available.
+        pub fn capture_pixels(&self) -> Option<CapturedPixels> {
+            const GL_RGBA: u32 = 0x1908;
+            const GL_UNSIGNED_BYTE: u32 = 0x1401;
+            const GL_PACK_ALIGNMENT: u32 = 0x0D05;
+
+            type ReadPixels = unsafe extern "system" fn(i32, i32, i32, i32, u32, u32, *mut c_void);
+            type PixelStorei = unsafe extern "system" fn(u32, i32);
+
+            unsafe {
+                let read_pixels = self.get_proc_address(c"glReadPixels");
+                let pixel_storei = self.get_proc_address(c"glPixelStorei");
+                if read_pixels.is_null() || pixel_storei.is_null() {
+                    return None;
+                }
+
+                let read_pixels = std::mem::transmute::<*mut c_void, ReadPixels>(read_pixels);
+                let pixel_storei = std::mem::transmute::<*mut c_void, PixelStorei>(pixel_storei);
+
+                let size = sys::puglGetSizeHint(self.view, sys::PUGL_CURRENT_SIZE);
+                let (width, height) = (size.width as u32, size.height as u32);
+                let stride = width as usize * 4;
+                let mut data = vec![0u8; stride * height as usize];
+
+                pixel_storei(GL_PACK_ALIGNMENT, 1);
+                read_pixels(
+                    0,
+                    0,
+                    width as i32,
+                    height as i32,
+                    GL_RGBA,
+                    GL_UNSIGNED_BYTE,
+                    data.as_mut_ptr() as *mut c_void,
+                );
+
+                // OpenGL has a bottom-left origin
+                let rows = data
+                    .chunks_exact(stride.max(1))
+                    .rev()
+                    .flatten()
+                    .copied()
+                    .collect();
+
+                Some(CapturedPixels {
+                    width,
+                    height,
+                    data: rows,
+                })
+            }
+        }
+    }
+
+    #[cfg(feature = "femtovg")]
+    impl<'a> OpenGlContext<'a> {
+        /// Creates a [`femtovg`] canvas that draws into this view.
+        ///
+        /// This should be called in the [`Event::Realize`](crate::Event::Realize) handler, and the canvas should be dropped in
+        /// the [`Event::Unrealize`](crate::Event::Unrealize) handler, while the OpenGL context is still current.
+        /// `femtovg` fills shapes with the stencil buffer, so the view should be created with `bits_stencil: 8`.
+        pub fn create_femtovg_canvas(
+            &self,
+        ) -> Result<femtovg::Canvas<femtovg::renderer::OpenGl>, femtovg::ErrorKind> {
+            let renderer = unsafe {
+                femtovg::renderer::OpenGl::new_from_function_cstr(|name| {
+                    self.get_proc_address(name) as *const c_void
+                })?
+            };
+
+            let mut canvas = femtovg::Canvas::new(renderer)?;
+            self.prepare_femtovg_canvas(&mut canvas);
+            Ok(canvas)
+        }
+
+        /// Updates the viewport and the scale factor of a [`femtovg`] canvas to match the view.
+        ///
+        /// This should be called at the start of every [`Event::Expose`](crate::Event::Expose) handler, before drawing.
+        /// The transform is reset to the identity, so the canvas coordinates are in physical pixels like the coordinates of the events.
+        /// Use `canvas.scale(scale, scale)` with [`View::system_scale`](crate::View::system_scale) to draw in logical units instead.
+        pub fn prepare_femtovg_canvas(
+            &self,
+            canvas: &mut femtovg::Canvas<femtovg::renderer::OpenGl>,
+        ) {
+            unsafe {
+                let size = sys::puglGetSizeHint(self.view, sys::PUGL_CURRENT_SIZE);
+                let scale = sys::puglGetScaleFactor(self.view);
+
+                canvas.set_size(size.width as u32, size.height as u32, scale as f32);
+                canvas.reset_transform();
+                canvas.reset_scissor();
+            }
+        }
+    }
+
     impl<'a> fmt::Debug for OpenGlContext<'a> {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_struct("OpenGlContext")
@@ -186,7 +434,9 @@ mod opengl {
                     sys::puglSetViewHint(view, sys::PUGL_SAMPLE_BUFFERS, aa_buffers as _);
                 }
 
-                if let Some(swap_interval) = self.swap_interval {
+                if let Some(swap_interval) = self.swap_interval
+                    && self.double_buffer
+                {
                     sys::puglSetViewHint(view, sys::PUGL_SWAP_INTERVAL, swap_interval as _);
                 }
             }
@@ -211,5 +461,263 @@ mod opengl {
                 view,
             }
         }
+
+        unsafe fn degrade(view: *mut sys::PuglView, _: crate::private::Private) -> bool {
+            unsafe {
+                let hint = |hint: sys::PuglViewHint| sys::puglGetViewHint(view, hint);
+                let set = |hint: sys::PuglViewHint, value| sys::puglSetViewHint(view, hint, value);
+
+                // multisampling first, since it is the most common reason for a missing pixel format
+                if hint(sys::PUGL_SAMPLES) > 0 || hint(sys::PUGL_SAMPLE_BUFFERS) > 0 {
+                    set(sys::PUGL_SAMPLES, 0);
+                    set(sys::PUGL_SAMPLE_BUFFERS, 0);
+                    return true;
+                }
+
+                if hint(sys::PUGL_CONTEXT_DEBUG) > 0 {
+                    set(sys::PUGL_CONTEXT_DEBUG, 0);
+                    return true;
+                }
+
+                let version = (
+                    hint(sys::PUGL_CONTEXT_VERSION_MAJOR),
+                    hint(sys::PUGL_CONTEXT_VERSION_MINOR),
+                );
+
+                if hint(sys::PUGL_CONTEXT_API) == sys::PUGL_OPENGL_ES_API {
+                    // OpenGL ES 3.0 and later, then 2.0
+                    if version > (2, 0) {
+                        set(sys::PUGL_CONTEXT_VERSION_MAJOR, 2);
+                        set(sys::PUGL_CONTEXT_VERSION_MINOR, 0);
+                        return true;
+                    }
+                } else if version > (3, 2) {
+                    // the highest version supported by MacOS and most old drivers
+                    set(sys::PUGL_CONTEXT_VERSION_MAJOR, 3);
+                    set(sys::PUGL_CONTEXT_VERSION_MINOR, 2);
+                    return true;
+                } else if version > (2, 1) {
+                    // a legacy context, which every driver supports
+                    set(
+                        sys::PUGL_CONTEXT_PROFILE,
+                        sys::PUGL_OPENGL_COMPATIBILITY_PROFILE,
+                    );
+                    set(sys::PUGL_CONTEXT_VERSION_MAJOR, 2);
+                    set(sys::PUGL_CONTEXT_VERSION_MINOR, 1);
+                    return true;
+                }
+
+                false
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cairo")]
+pub use cairo::*;
+
+#[cfg(feature = "cairo")]
+mod cairo {
+    use super::*;
+    use crate::view::{backend_flags, set_backend_flags};
+    use std::{ffi::c_void, fmt, marker::PhantomData};
+
+    unsafe extern "C" {
+        fn cairo_scale(cr: *mut c_void, sx: f64, sy: f64);
+        fn cairo_create(target: *mut c_void) -> *mut c_void;
+        fn cairo_destroy(cr: *mut c_void);
+        fn cairo_get_target(cr: *mut c_void) -> *mut c_void;
+        fn cairo_set_source_surface(cr: *mut c_void, surface: *mut c_void, x: f64, y: f64);
+        fn cairo_paint(cr: *mut c_void);
+        fn cairo_surface_flush(surface: *mut c_void);
+        fn cairo_surface_destroy(surface: *mut c_void);
+        fn cairo_surface_status(surface: *mut c_void) -> i32;
+        fn cairo_image_surface_create(format: i32, width: i32, height: i32) -> *mut c_void;
+        fn cairo_image_surface_get_data(surface: *mut c_void) -> *mut u8;
+        fn cairo_image_surface_get_stride(surface: *mut c_void) -> i32;
+    }
+
+    const LOGICAL_UNITS: u32 = 1;
+    const CAIRO_FORMAT_ARGB32: i32 = 0;
+    const CAIRO_STATUS_SUCCESS: i32 = 0;
+
+    /// Creates an image surface, lets `draw` fill it with the `cairo_t` and reads it back.
+    unsafe fn render_image(
+        width: u32,
+        height: u32,
+        draw: impl FnOnce(*mut c_void),
+    ) -> Option<CapturedPixels> {
+        unsafe {
+            let surface =
+                cairo_image_surface_create(CAIRO_FORMAT_ARGB32, width as i32, height as i32);
+            if cairo_surface_status(surface) != CAIRO_STATUS_SUCCESS {
+                cairo_surface_destroy(surface);
+                return None;
+            }
+
+            let cr = cairo_create(surface);
+            draw(cr);
+            cairo_destroy(cr);
+            cairo_surface_flush(surface);
+
+            let data = cairo_image_surface_get_data(surface);
+            let stride = cairo_image_surface_get_stride(surface) as usize;
+            let pixels = if data.is_null() {
+                None
+            } else {
+                let rows = std::slice::from_raw_parts(data, stride * height as usize);
+                Some(CapturedPixels {
+                    width,
+                    height,
+                    data: unpremultiply(rows, width as usize, stride),
+                })
+            };
+
+            cairo_surface_destroy(surface);
+            pixels
+        }
+    }
+
+    /// Converts premultiplied native-endian ARGB32 rows into straight RGBA.
+    fn unpremultiply(rows: &[u8], width: usize, stride: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(width * 4 * rows.len() / stride.max(1));
+        for row in rows.chunks_exact(stride.max(1)) {
+            for pixel in row[..width * 4].chunks_exact(4) {
+                let argb = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                let [a, r, g, b] = argb.to_be_bytes();
+                let straight = |c: u8| match a {
+                    0 => 0,
+                    _ => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+                };
+                data.extend_from_slice(&[straight(r), straight(g), straight(b), a]);
+            }
+        }
+
+        data
+    }
+
+    impl Cairo {
+        /// Draws into an offscreen image of the given size with the `cairo_t` passed to `draw`, and returns the pixels.
+        ///
+        /// Useful for thumbnails, printing, or tests of the drawing code without a view.
+        /// Returns `None` if the image can't be created.
+        pub fn render_image(
+            width: u32,
+            height: u32,
+            draw: impl FnOnce(*mut c_void),
+        ) -> Option<CapturedPixels> {
+            unsafe { render_image(width, height, draw) }
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    pub struct Cairo {
+        /// Scale the context by [`View::system_scale`](crate::View::system_scale) before every expose,
+        /// so the user interface is drawn in logical units and stays crisp on HiDPI displays.
+        ///
+        /// The coordinates of the events are still in physical pixels, see [`CairoContext::scale`].
+        pub logical_units: bool,
+    }
+
+    /// The drawing context of a [`Cairo`] view, available in [`Event::Expose`](crate::Event::Expose).
+    pub struct CairoContext<'a> {
+        phantom: PhantomData<&'a ()>,
+        view: *mut sys::PuglView,
+        scale: f64,
+    }
+
+    impl<'a> CairoContext<'a> {
+        /// Returns the `cairo_t` to draw with, which is only valid during the expose.
+        pub fn as_raw(&self) -> *mut c_void {
+            unsafe { sys::puglGetContext(self.view) }
+        }
+
+        /// Returns the scale applied to the context, which is the system scale with [`Cairo::logical_units`] and 1.0 otherwise.
+        ///
+        /// Divide the coordinates of the events by the scale to get the coordinates in the units of the context.
+        pub fn scale(&self) -> f64 {
+            self.scale
+        }
+
+        /// Reads the contents of the target surface of the context, which can be used for thumbnails or golden-image tests.
+        ///
+        /// This should be called at the end of the [`Event::Expose`](crate::Event::Expose) handler, after everything is drawn.
+        /// The image has the size of the view in physical pixels. Returns `None` if the surface can't be read.
+        pub fn capture_pixels(&self) -> Option<CapturedPixels> {
+            unsafe {
+                let cr = self.as_raw();
+                if cr.is_null() {
+                    return None;
+                }
+
+                let target = cairo_get_target(cr);
+                cairo_surface_flush(target);
+
+                let size = sys::puglGetSizeHint(self.view, sys::PUGL_CURRENT_SIZE);
+                render_image(size.width as u32, size.height as u32, |image| {
+                    cairo_set_source_surface(image, target, 0.0, 0.0);
+                    cairo_paint(image);
+                })
+            }
+        }
+
+        /// Returns the size of the view in the units of the context.
+        pub fn size(&self) -> (f64, f64) {
+            let size = unsafe { sys::puglGetSizeHint(self.view, sys::PUGL_CURRENT_SIZE) };
+            (
+                size.width as f64 / self.scale,
+                size.height as f64 / self.scale,
+            )
+        }
+    }
+
+    impl<'a> fmt::Debug for CairoContext<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("CairoContext")
+                .field("view", &self.view)
+                .field("scale", &self.scale)
+                .finish()
+        }
+    }
+
+    impl Backend for Cairo {
+        type DrawContext<'a> = CairoContext<'a>;
+        type SetupContext<'a> = ();
+
+        unsafe fn install(self, view: *mut sys::PuglView, _: crate::private::Private) {
+            unsafe {
+                sys::puglSetBackend(view, sys::puglCairoBackend());
+                set_backend_flags(view, if self.logical_units { LOGICAL_UNITS } else { 0 });
+            }
+        }
+
+        unsafe fn setup<'a>(
+            _view: *mut sys::PuglView,
+            _: crate::private::Private,
+        ) -> Self::SetupContext<'a> {
+        }
+
+        unsafe fn draw<'a>(
+            view: *mut sys::PuglView,
+            _: crate::private::Private,
+        ) -> Self::DrawContext<'a> {
+            unsafe {
+                let mut scale = 1.0;
+                if backend_flags(view) & LOGICAL_UNITS != 0 {
+                    // pugl creates a new cairo context for every expose, so the scale is applied once
+                    scale = sys::puglGetScaleFactor(view);
+                    let cr = sys::puglGetContext(view);
+                    if !cr.is_null() {
+                        cairo_scale(cr, scale, scale);
+                    }
+                }
+
+                CairoContext {
+                    phantom: PhantomData,
+                    view,
+                    scale,
+                }
+            }
+        }
     }
 }
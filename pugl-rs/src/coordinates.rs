@@ -0,0 +1,151 @@
+use crate::{Backend, ScreenPoint, View, ViewPoint};
+
+impl<B: Backend> View<B> {
+    /// Converts a point from view coordinates (like the `position` of input events) to screen coordinates with an upper left origin.
+    ///
+    /// This is useful for placing popup windows relative to a widget. The conversion asks the window system where the view actually is,
+    /// so it also works for embedded views and accounts for the frame of the window, unlike adding [`View::position`].
+    /// Falls back to [`View::position`] if the window system can't be asked.
+    pub fn view_to_screen(&self, x: f64, y: f64) -> (f64, f64) {
+        let (origin_x, origin_y) = self.screen_origin();
+        (x + origin_x, y + origin_y)
+    }
+
+    /// Converts a point from screen coordinates with an upper left origin (like the `root_position` of input events) to view coordinates.
+    ///
+    /// This is the inverse of [`View::view_to_screen`].
+    pub fn screen_to_view(&self, x: f64, y: f64) -> (f64, f64) {
+        let (origin_x, origin_y) = self.screen_origin();
+        (x - origin_x, y - origin_y)
+    }
+
+    /// Returns the upper left corner of the view in screen coordinates.
+    fn screen_origin(&self) -> (f64, f64) {
+        platform::screen_origin(self).unwrap_or_else(|| {
+            let (x, y) = self.position();
+            (x as f64, y as f64)
+        })
+    }
+}
+
+impl ViewPoint {
+    /// Converts the point to screen coordinates, see [`View::view_to_screen`].
+    pub fn to_screen<B: Backend>(self, view: &View<B>) -> ScreenPoint {
+        let (x, y) = view.view_to_screen(self.x, self.y);
+        ScreenPoint::new(x, y)
+    }
+}
+
+impl ScreenPoint {
+    /// Converts the point to the coordinates of the view, see [`View::screen_to_view`].
+    pub fn to_view<B: Backend>(self, view: &View<B>) -> ViewPoint {
+        let (x, y) = view.screen_to_view(self.x, self.y);
+        ViewPoint::new(x, y)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use crate::{
+        Backend, View,
+        x11::xlib::{XDefaultRootWindow, XTranslateCoordinates},
+    };
+    use std::os::raw::c_ulong;
+
+    pub fn screen_origin<B: Backend>(view: &View<B>) -> Option<(f64, f64)> {
+        let display = view.world().native().as_raw();
+        let window = view.native().as_raw() as c_ulong;
+        if display.is_null() || window == 0 {
+            return None;
+        }
+
+        unsafe {
+            let (mut x, mut y, mut child) = (0, 0, 0);
+            let root = XDefaultRootWindow(display);
+            if XTranslateCoordinates(display, window, root, 0, 0, &mut x, &mut y, &mut child) == 0 {
+                return None;
+            }
+
+            Some((x as f64, y as f64))
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use crate::{Backend, View};
+
+    #[repr(C)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn ClientToScreen(hwnd: isize, point: *mut Point) -> i32;
+    }
+
+    pub fn screen_origin<B: Backend>(view: &View<B>) -> Option<(f64, f64)> {
+        let hwnd = view.native().as_raw() as isize;
+        if hwnd == 0 {
+            return None;
+        }
+
+        let mut point = Point { x: 0, y: 0 };
+        match unsafe { ClientToScreen(hwnd, &mut point) } {
+            0 => None,
+            _ => Some((point.x as f64, point.y as f64)),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::{Backend, View, objc, sys};
+
+    #[repr(C)]
+    struct CGRect {
+        _origin: [f64; 2],
+        size: [f64; 2],
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGMainDisplayID() -> u32;
+        fn CGDisplayBounds(display: u32) -> CGRect;
+    }
+
+    pub fn screen_origin<B: Backend>(view: &View<B>) -> Option<(f64, f64)> {
+        unsafe {
+            let ns_view = view.native().as_raw() as objc::Id;
+            let window = objc::send(ns_view, c"window");
+            if window.is_null() {
+                return None;
+            }
+
+            // the view of pugl is flipped, so its origin is the upper left corner
+            let origin = objc::send_point_with(
+                ns_view,
+                c"convertPoint:toView:",
+                objc::Point { x: 0.0, y: 0.0 },
+                std::ptr::null_mut(),
+            );
+            let origin = objc::send_point(window, c"convertPointToScreen:", origin);
+
+            // Cocoa screen coordinates have a lower left origin at the bottom of the primary display
+            let primary = CGDisplayBounds(CGMainDisplayID());
+            let scale = sys::puglGetScaleFactor(view.as_pugl_ptr());
+            Some((origin.x * scale, (primary.size[1] - origin.y) * scale))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use crate::{Backend, View};
+
+    pub fn screen_origin<B: Backend>(_: &View<B>) -> Option<(f64, f64)> {
+        None
+    }
+}
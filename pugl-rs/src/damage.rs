@@ -0,0 +1,158 @@
+use crate::Rect;
+use std::mem::take;
+
+/// Above this many separate regions, the damage is collapsed into its bounding box,
+/// since every region is a separate call into the platform (they are still exposed with a single event).
+const MAX_REGIONS: usize = 8;
+
+/// Regions of a view passed to [`View::obscure_region`](crate::View::obscure_region) that are not yet submitted to `pugl`.
+///
+/// Overlapping and adjacent regions are merged, so a widget tree that dirties many small areas results in a few exposes.
+#[derive(Default)]
+pub(crate) struct Damage {
+    regions: Vec<Rect>,
+}
+
+impl Damage {
+    pub fn add(&mut self, mut rect: Rect) {
+        if rect.w == 0 || rect.h == 0 {
+            return;
+        }
+
+        // the merged region can touch regions it didn't touch before, so start over after every merge
+        let mut i = 0;
+        while i < self.regions.len() {
+            if touches(self.regions[i], rect) {
+                rect = union(self.regions.swap_remove(i), rect);
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+
+        self.regions.push(rect);
+
+        if self.regions.len() > MAX_REGIONS {
+            let bounds = self.regions.iter().copied().reduce(union).unwrap_or(rect);
+            self.regions = vec![bounds];
+        }
+    }
+
+    pub fn take(&mut self) -> Vec<Rect> {
+        take(&mut self.regions)
+    }
+}
+
+/// Returns `true` if the rects overlap or share an edge.
+fn touches(a: Rect, b: Rect) -> bool {
+    let (a_right, a_bottom) = (a.x as i64 + a.w as i64, a.y as i64 + a.h as i64);
+    let (b_right, b_bottom) = (b.x as i64 + b.w as i64, b.y as i64 + b.h as i64);
+
+    let overlap_x = a.x as i64 <= b_right && b.x as i64 <= a_right;
+    let overlap_y = a.y as i64 <= b_bottom && b.y as i64 <= a_bottom;
+    let strict_x = (a.x as i64) < b_right && (b.x as i64) < a_right;
+    let strict_y = (a.y as i64) < b_bottom && (b.y as i64) < a_bottom;
+
+    // rects that only share a corner are not merged
+    (strict_x && overlap_y) || (overlap_x && strict_y)
+}
+
+fn union(a: Rect, b: Rect) -> Rect {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    let right = (a.x as i64 + a.w as i64).max(b.x as i64 + b.w as i64);
+    let bottom = (a.y as i64 + a.h as i64).max(b.y as i64 + b.h as i64);
+
+    Rect {
+        x,
+        y,
+        w: (right - x as i64) as u32,
+        h: (bottom - y as i64) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, w: u32, h: u32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    fn damage(rects: &[Rect]) -> Vec<Rect> {
+        let mut damage = Damage::default();
+        for &rect in rects {
+            damage.add(rect);
+        }
+        damage.take()
+    }
+
+    #[test]
+    fn overlapping_regions_are_merged() {
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(5, 5, 10, 10)]),
+            [rect(0, 0, 15, 15)]
+        );
+    }
+
+    #[test]
+    fn adjacent_regions_are_merged() {
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(10, 0, 10, 10)]),
+            [rect(0, 0, 20, 10)]
+        );
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(0, 10, 10, 10)]),
+            [rect(0, 0, 10, 20)]
+        );
+    }
+
+    #[test]
+    fn corners_and_separate_regions_are_kept() {
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(10, 10, 10, 10)]),
+            [rect(0, 0, 10, 10), rect(10, 10, 10, 10)]
+        );
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(20, 0, 10, 10)]),
+            [rect(0, 0, 10, 10), rect(20, 0, 10, 10)]
+        );
+    }
+
+    #[test]
+    fn merged_regions_merge_again() {
+        // the third region bridges the first two
+        assert_eq!(
+            damage(&[rect(0, 0, 10, 10), rect(20, 0, 10, 10), rect(5, 0, 20, 10)]),
+            [rect(0, 0, 30, 10)]
+        );
+    }
+
+    #[test]
+    fn empty_regions_are_ignored() {
+        assert_eq!(damage(&[rect(0, 0, 0, 10), rect(0, 0, 10, 0)]), []);
+    }
+
+    #[test]
+    fn many_regions_collapse_into_their_bounds() {
+        let separate = (0..MAX_REGIONS as i32)
+            .map(|i| rect(i * 20, 0, 10, 10))
+            .collect::<Vec<_>>();
+        assert_eq!(damage(&separate), separate);
+
+        let mut many = separate.clone();
+        many.push(rect(0, 100, 10, 10));
+        assert_eq!(
+            damage(&many),
+            [rect(0, 0, (MAX_REGIONS as u32 - 1) * 20 + 10, 110)]
+        );
+    }
+
+    #[test]
+    fn take_clears_the_damage() {
+        let mut damage = Damage::default();
+        damage.add(rect(0, 0, 10, 10));
+        assert_eq!(damage.take(), [rect(0, 0, 10, 10)]);
+        assert_eq!(damage.take(), []);
+    }
+}
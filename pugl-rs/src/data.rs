@@ -1,9 +1,17 @@
-use crate::{Backend, sys};
-use std::{ffi::CStr, ptr::addr_of, slice::from_raw_parts, str::from_utf8};
+use crate::{Backend, draw_context, setup_context, sys};
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+    ops::{Add, AddAssign, Sub, SubAssign},
+    ptr::addr_of,
+    slice::from_raw_parts,
+    str::from_utf8,
+    time::Duration,
+};
 
 // doc only import
 #[allow(unused_imports)]
-use crate::{View, World};
+use crate::{UnrealizedView, View, World};
 
 bitflags::bitflags! {
     /// Keyboard modifier flags.
@@ -76,6 +84,10 @@ pub enum CrossingMode {
 }
 
 /// An arbitrary rectangle in (physical) pixel coordinates with top-left origin.
+///
+/// With the `mint` feature, it can be converted from/to a `(mint::Point2<i32>, mint::Vector2<u32>)` pair of origin and size.
+/// With the `euclid` feature, it can be converted from/to `euclid::Rect<i32, U>` and `euclid::Box2D<i32, U>`
+/// (negative sizes are clamped to zero, and coordinates that don't fit in `i32` are saturated).
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
 pub struct Rect {
     pub x: i32,
@@ -84,6 +96,266 @@ pub struct Rect {
     pub h: u32,
 }
 
+#[cfg(feature = "mint")]
+impl From<Rect> for (mint::Point2<i32>, mint::Vector2<u32>) {
+    fn from(rect: Rect) -> Self {
+        (
+            mint::Point2 {
+                x: rect.x,
+                y: rect.y,
+            },
+            mint::Vector2 {
+                x: rect.w,
+                y: rect.h,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<(mint::Point2<i32>, mint::Vector2<u32>)> for Rect {
+    fn from((origin, size): (mint::Point2<i32>, mint::Vector2<u32>)) -> Self {
+        Rect {
+            x: origin.x,
+            y: origin.y,
+            w: size.x,
+            h: size.y,
+        }
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<U> From<Rect> for euclid::Rect<i32, U> {
+    fn from(rect: Rect) -> Self {
+        euclid::Rect::new(
+            euclid::Point2D::new(rect.x, rect.y),
+            euclid::Size2D::new(
+                rect.w.min(i32::MAX as u32) as i32,
+                rect.h.min(i32::MAX as u32) as i32,
+            ),
+        )
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<U> From<euclid::Rect<i32, U>> for Rect {
+    fn from(rect: euclid::Rect<i32, U>) -> Self {
+        Rect {
+            x: rect.origin.x,
+            y: rect.origin.y,
+            w: rect.size.width.max(0) as u32,
+            h: rect.size.height.max(0) as u32,
+        }
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<U> From<Rect> for euclid::Box2D<i32, U> {
+    fn from(rect: Rect) -> Self {
+        euclid::Box2D {
+            min: euclid::Point2D::new(rect.x, rect.y),
+            max: euclid::Point2D::new(
+                rect.x.saturating_add_unsigned(rect.w),
+                rect.y.saturating_add_unsigned(rect.h),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<U> From<euclid::Box2D<i32, U>> for Rect {
+    fn from(rect: euclid::Box2D<i32, U>) -> Self {
+        Rect {
+            x: rect.min.x,
+            y: rect.min.y,
+            // the difference of two `i32` always fits in `u32` when it's positive
+            w: (rect.max.x as i64 - rect.min.x as i64).max(0) as u32,
+            h: (rect.max.y as i64 - rect.min.y as i64).max(0) as u32,
+        }
+    }
+}
+
+impl Rect {
+    /// Returns `true` if the point is inside the rectangle, for hit testing.
+    pub fn contains(&self, point: ViewPoint) -> bool {
+        point.x >= self.x as f64
+            && point.y >= self.y as f64
+            && point.x < self.x as f64 + self.w as f64
+            && point.y < self.y as f64 + self.h as f64
+    }
+}
+
+/// A point in view coordinates: (physical) pixels relative to the top-left corner of the view.
+///
+/// Use [`ViewPoint::to_screen`] to convert it to screen coordinates.
+/// With the `mint` feature, it can be converted from/to `mint::Point2<f64>`.
+/// With the `euclid` feature, it can be converted from/to `euclid::Point2D<f64, U>`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ViewPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A point in screen coordinates: (physical) pixels relative to the top-left corner of the screen (the root window).
+///
+/// Use [`ScreenPoint::to_view`] to convert it to view coordinates.
+/// With the `mint` feature, it can be converted from/to `mint::Point2<f64>`.
+/// With the `euclid` feature, it can be converted from/to `euclid::Point2D<f64, U>`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ScreenPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+macro_rules! impl_point {
+    ($point:ident) => {
+        impl $point {
+            pub const fn new(x: f64, y: f64) -> Self {
+                Self { x, y }
+            }
+
+            /// Returns the pixel that contains the point.
+            pub fn to_pixel(self) -> (i32, i32) {
+                (self.x.floor() as i32, self.y.floor() as i32)
+            }
+        }
+
+        #[cfg(feature = "mint")]
+        impl From<$point> for mint::Point2<f64> {
+            fn from(point: $point) -> Self {
+                mint::Point2 {
+                    x: point.x,
+                    y: point.y,
+                }
+            }
+        }
+
+        #[cfg(feature = "mint")]
+        impl From<mint::Point2<f64>> for $point {
+            fn from(point: mint::Point2<f64>) -> Self {
+                $point::new(point.x, point.y)
+            }
+        }
+
+        #[cfg(feature = "euclid")]
+        impl<U> From<$point> for euclid::Point2D<f64, U> {
+            fn from(point: $point) -> Self {
+                euclid::Point2D::new(point.x, point.y)
+            }
+        }
+
+        #[cfg(feature = "euclid")]
+        impl<U> From<euclid::Point2D<f64, U>> for $point {
+            fn from(point: euclid::Point2D<f64, U>) -> Self {
+                $point::new(point.x, point.y)
+            }
+        }
+    };
+}
+
+impl_point!(ViewPoint);
+impl_point!(ScreenPoint);
+
+/// A point in time measured by the world's monotonic clock.
+///
+/// Returned by [`World::time`] and used for event timestamps.
+/// Its absolute value has no meaning: it is only useful to compare against other instants of the same world,
+/// and it is unrelated to [`std::time::Instant`] and wall-clock time.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Default)]
+pub struct WorldInstant(f64);
+
+impl WorldInstant {
+    /// Creates an instant from a raw `pugl` time value in seconds.
+    pub const fn from_secs_f64(secs: f64) -> Self {
+        Self(secs)
+    }
+
+    /// Returns the raw `pugl` time value in seconds.
+    pub const fn as_secs_f64(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`, or `None` if `earlier` is later than `self`.
+    pub fn checked_duration_since(self, earlier: Self) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.0 - earlier.0).ok()
+    }
+
+    /// Returns the amount of time elapsed from `earlier` to `self`, or zero if `earlier` is later than `self`.
+    pub fn duration_since(self, earlier: Self) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+}
+
+impl Add<Duration> for WorldInstant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        Self(self.0 + rhs.as_secs_f64())
+    }
+}
+
+impl AddAssign<Duration> for WorldInstant {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Duration> for WorldInstant {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self {
+        Self(self.0 - rhs.as_secs_f64())
+    }
+}
+
+impl SubAssign<Duration> for WorldInstant {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for WorldInstant {
+    type Output = Duration;
+
+    /// Returns the amount of time elapsed between two instants, saturating to zero.
+    fn sub(self, rhs: Self) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// A status code reported by `pugl`.
+///
+/// The [`Display`](std::fmt::Display) implementation uses the description provided by `pugl`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Status {
+    /// Success
+    Success,
+    /// Non-fatal failure
+    Failure,
+    /// Unknown system error
+    UnknownError,
+    /// Invalid or missing backend
+    BadBackend,
+    /// Invalid view configuration
+    BadConfiguration,
+    /// Invalid parameter
+    BadParameter,
+    /// Backend initialization failed
+    BackendFailed,
+    /// Class registration failed
+    RegistrationFailed,
+    /// System view realization failed
+    RealizeFailed,
+    /// Failed to set pixel format
+    SetFormatFailed,
+    /// Failed to create drawing context
+    CreateContextFailed,
+    /// Unsupported operation
+    Unsupported,
+    /// Failed to allocate memory
+    NoMemory,
+}
+
 /// Mouse cursor icon.
 ///
 /// Used in [`View::set_cursor`].
@@ -227,17 +499,12 @@ pub enum Key {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct EventInput {
     /// Time of the event. Use [`World::time`] to get the current time.
-    pub time: f64,
-
-    /// X coordinate of the event in view coordinates.
-    pub x: f64,
-    /// Y coordinate of the event in view coordinates.
-    pub y: f64,
+    pub time: WorldInstant,
 
-    /// X coordinate of the event in screen coordinates.
-    pub root_x: f64,
-    /// Y coordinate of the event in screen coordinates.
-    pub root_y: f64,
+    /// Position of the event in view coordinates.
+    pub position: ViewPoint,
+    /// Position of the event in screen coordinates.
+    pub root_position: ScreenPoint,
 
     /// Keyboard modifiers active at the time of the event.
     pub mods: Modifiers,
@@ -255,7 +522,37 @@ pub enum Event<'a, B: Backend> {
     /// When a configure event is received, the graphics context is active but not set up for drawing.  
     /// For example, it is valid to adjust the OpenGL viewport or otherwise configure the context,
     /// but not to draw anything.
-    Configure { rect: Rect, style: ViewStyle },
+    ///
+    /// `changed` contains the style flags that changed since the previous configure event of the view
+    /// (all set flags for the first one), so transitions can be handled without tracking the style:
+    /// ```no_run
+    /// # use pugl_rs::*;
+    /// # fn handle(event: Event<()>) {
+    /// if let Event::Configure { style, changed, .. } = event {
+    ///     if changed.contains(ViewStyle::FULLSCREEN) {
+    ///         let entered = style.contains(ViewStyle::FULLSCREEN);
+    ///         // ...
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    Configure {
+        rect: Rect,
+        style: ViewStyle,
+        changed: ViewStyle,
+    },
+
+    /// Parent view resize or move event.
+    ///
+    /// This event is sent to views embedded with [`UnrealizedView::with_parent_view`] whenever the parent view is configured,
+    /// along with the scale factor of the parent view, so the child can follow its size and scale.
+    ParentConfigure { rect: Rect, scale: f64 },
+
+    /// View scale change event.
+    ///
+    /// This event is sent right before an [`Event::Configure`] when the scale factor of the view (see [`View::system_scale`](crate::View::system_scale)) changed,
+    /// for example when the window is moved to a monitor with a different DPI, so the user interface can be laid out and drawn at the new scale.
+    ScaleChange { scale: f64 },
 
     /// View realize event.
     ///
@@ -301,6 +598,10 @@ pub enum Event<'a, B: Backend> {
     ///
     /// When an expose event is received, the graphics context is active, and the view must draw the entire specified region.  
     /// The contents of the region are undefined, there is no preservation of anything drawn previously.
+    ///
+    /// The rectangle is the bounding box of the regions obscured since the last expose.
+    /// Views that need to know when no more exposes follow in the current main loop iteration
+    /// can opt in to [`Event::EndFrame`] with [`UnrealizedView::with_end_frame_events`].
     Expose {
         backend: B::DrawContext<'a>,
         rect: Rect,
@@ -354,12 +655,42 @@ pub enum Event<'a, B: Backend> {
     ///
     /// Alternatively, the raw `keycode` can be used to work directly with physical keys,
     /// but note that this value is not portable and differs between platforms and hardware.
+    ///
+    /// `pugl` passes the text in a fixed 8 byte buffer. Longer input method commits are delivered as several text events on Windows and MacOS,
+    /// but are cut short by `pugl` on X11, in which case `text` contains the characters that fit in the buffer.
     KeyText {
         input: EventInput,
         keycode: u32,
         text: &'a str,
     },
 
+    /// Key press event merged with the text it produced.
+    ///
+    /// This event is only sent if enabled with [`UnrealizedView::with_unified_keyboard_events`],
+    /// in which case it replaces [`Event::KeyPress`] and [`Event::KeyText`].
+    /// `text` is `None` for keys that don't produce text (like the arrow keys).
+    /// Text that is not produced by the last key press (like an input method commit, which has a different keycode) is sent with [`Key::None`].
+    KeyboardInput {
+        input: EventInput,
+        keycode: u32,
+        key: Key,
+        text: Option<&'a str>,
+    },
+
+    /// Dead key event.
+    ///
+    /// This event is sent after the [`Event::KeyPress`] of a dead key (like `´` on many European keyboard layouts), which doesn't produce text itself,
+    /// but starts a sequence that is composed with the next key press (`´` followed by `e` produces `é`).
+    /// `accent` is the spacing version of the accent, so text editors can show it as pending until the composed character arrives as an [`Event::KeyText`],
+    /// or until the next [`Event::KeyPress`] if the sequence is canceled.
+    ///
+    /// On X11, input methods usually consume dead keys before they reach the view, in which case this event is not sent.
+    DeadKey {
+        input: EventInput,
+        keycode: u32,
+        accent: char,
+    },
+
     /// Pointer enter event.
     ///
     /// This event is sent when the pointer enters the view.  
@@ -398,6 +729,7 @@ pub enum Event<'a, B: Backend> {
     /// The scroll distance is expressed in "lines", an arbitrary unit that corresponds to a single tick of a detented mouse wheel.  
     /// For example, `dy` = 1.0 scrolls 1 line up.  
     /// Some systems and devices support finer resolution and/or higher values for fast scrolls, so programs should handle any value gracefully.
+    /// The deltas can be normalized to lines or pixels with [`View::set_scroll_config`].
     Scroll {
         input: EventInput,
         direction: ScrollDirection,
@@ -419,8 +751,338 @@ pub enum Event<'a, B: Backend> {
 
     /// A clipboard paste event.
     ///
-    /// This event is sent if the clipboard contained text data at the time [`View::paste_clipboard`] was called
-    Clipboard { text: &'a str },
+    /// This event is sent if the clipboard contained text data at the time [`View::paste_clipboard`] was called.
+    /// `mime_type` is the type that was negotiated with the clipboard owner (see [`View::paste_clipboard_as`]).
+    Clipboard { mime_type: &'a str, text: &'a str },
+
+    /// A clipboard paste event with non-text data.
+    ///
+    /// This event is sent instead of [`Event::Clipboard`] if the pasted data is not valid UTF-8 (for example, an image,
+    /// or text in a different encoding, see [`Event::clipboard_text_lossy`]).
+    /// If the clipboard owner offered a malformed type, `mime_type` is `application/octet-stream`.
+    ClipboardData { mime_type: &'a str, data: &'a [u8] },
+
+    /// A failed clipboard paste event.
+    ///
+    /// This event is sent instead of [`Event::Clipboard`] if the clipboard has none of the requested types (see [`View::paste_clipboard_as`]),
+    /// or if the data didn't arrive in time (see [`View::set_clipboard_timeout`]), which happens on X11 when the clipboard owner doesn't respond.
+    ClipboardFailed,
+
+    /// End of frame event.
+    ///
+    /// This event is sent once the view received its last [`Event::Expose`] of a main loop iteration,
+    /// so frame-level work (like submitting the accumulated draw commands, or frame statistics) can happen once per frame instead of once per expose.
+    /// Outside of [`World::update`] (for example in the event loop of a plugin host) it is sent after every expose.
+    ///
+    /// This event is only sent if enabled with [`UnrealizedView::with_end_frame_events`].
+    /// The graphics context is not active while it is handled.
+    EndFrame,
+}
+
+/// An owned version of [`Event`], that does not borrow any data and does not depend on the backend.
+///
+/// Backend contexts are not included, so [`Event::Realize`], [`Event::Unrealize`] and [`Event::Expose`] only carry their non-backend data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OwnedEvent {
+    Configure {
+        rect: Rect,
+        style: ViewStyle,
+        changed: ViewStyle,
+    },
+    ParentConfigure {
+        rect: Rect,
+        scale: f64,
+    },
+    ScaleChange {
+        scale: f64,
+    },
+    Realize,
+    Unrealize,
+    EnterLoop,
+    LeaveLoop,
+    Close,
+    Update,
+    Expose {
+        rect: Rect,
+    },
+    FocusIn {
+        mode: CrossingMode,
+    },
+    FocusOut {
+        mode: CrossingMode,
+    },
+    KeyPress {
+        input: EventInput,
+        keycode: u32,
+        key: Key,
+    },
+    KeyRelease {
+        input: EventInput,
+        keycode: u32,
+        key: Key,
+    },
+    KeyText {
+        input: EventInput,
+        keycode: u32,
+        text: String,
+    },
+    KeyboardInput {
+        input: EventInput,
+        keycode: u32,
+        key: Key,
+        text: Option<String>,
+    },
+    DeadKey {
+        input: EventInput,
+        keycode: u32,
+        accent: char,
+    },
+    PointerIn {
+        input: EventInput,
+        mode: CrossingMode,
+    },
+    PointerOut {
+        input: EventInput,
+        mode: CrossingMode,
+    },
+    PointerMotion {
+        input: EventInput,
+    },
+    ButtonPress {
+        input: EventInput,
+        button: MouseButton,
+    },
+    ButtonRelease {
+        input: EventInput,
+        button: MouseButton,
+    },
+    Scroll {
+        input: EventInput,
+        direction: ScrollDirection,
+        dx: f64,
+        dy: f64,
+    },
+    Timer {
+        id: TimerId,
+    },
+    Client {
+        data: [usize; 2],
+    },
+    Clipboard {
+        mime_type: String,
+        text: String,
+    },
+    ClipboardData {
+        mime_type: String,
+        data: Vec<u8>,
+    },
+    ClipboardFailed,
+    EndFrame,
+}
+
+impl<B: Backend> From<&Event<'_, B>> for OwnedEvent {
+    fn from(event: &Event<'_, B>) -> Self {
+        match *event {
+            Event::Configure {
+                rect,
+                style,
+                changed,
+            } => OwnedEvent::Configure {
+                rect,
+                style,
+                changed,
+            },
+            Event::ParentConfigure { rect, scale } => OwnedEvent::ParentConfigure { rect, scale },
+            Event::Realize { .. } => OwnedEvent::Realize,
+            Event::Unrealize { .. } => OwnedEvent::Unrealize,
+            Event::EnterLoop => OwnedEvent::EnterLoop,
+            Event::LeaveLoop => OwnedEvent::LeaveLoop,
+            Event::ScaleChange { scale } => OwnedEvent::ScaleChange { scale },
+            Event::Close => OwnedEvent::Close,
+            Event::Update => OwnedEvent::Update,
+            Event::Expose { rect, .. } => OwnedEvent::Expose { rect },
+            Event::FocusIn { mode } => OwnedEvent::FocusIn { mode },
+            Event::FocusOut { mode } => OwnedEvent::FocusOut { mode },
+            Event::KeyPress {
+                input,
+                keycode,
+                key,
+            } => OwnedEvent::KeyPress {
+                input,
+                keycode,
+                key,
+            },
+            Event::KeyRelease {
+                input,
+                keycode,
+                key,
+            } => OwnedEvent::KeyRelease {
+                input,
+                keycode,
+                key,
+            },
+            Event::KeyText {
+                input,
+                keycode,
+                text,
+            } => OwnedEvent::KeyText {
+                input,
+                keycode,
+                text: text.to_owned(),
+            },
+            Event::KeyboardInput {
+                input,
+                keycode,
+                key,
+                text,
+            } => OwnedEvent::KeyboardInput {
+                input,
+                keycode,
+                key,
+                text: text.map(str::to_owned),
+            },
+            Event::DeadKey {
+                input,
+                keycode,
+                accent,
+            } => OwnedEvent::DeadKey {
+                input,
+                keycode,
+                accent,
+            },
+            Event::PointerIn { input, mode } => OwnedEvent::PointerIn { input, mode },
+            Event::PointerOut { input, mode } => OwnedEvent::PointerOut { input, mode },
+            Event::PointerMotion { input } => OwnedEvent::PointerMotion { input },
+            Event::ButtonPress { input, button } => OwnedEvent::ButtonPress { input, button },
+            Event::ButtonRelease { input, button } => OwnedEvent::ButtonRelease { input, button },
+            Event::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            } => OwnedEvent::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            },
+            Event::Timer { id } => OwnedEvent::Timer { id },
+            Event::Client { data } => OwnedEvent::Client { data },
+            Event::Clipboard { mime_type, text } => OwnedEvent::Clipboard {
+                mime_type: mime_type.to_owned(),
+                text: text.to_owned(),
+            },
+            Event::ClipboardData { mime_type, data } => OwnedEvent::ClipboardData {
+                mime_type: mime_type.to_owned(),
+                data: data.to_owned(),
+            },
+            Event::ClipboardFailed => OwnedEvent::ClipboardFailed,
+            Event::EndFrame => OwnedEvent::EndFrame,
+        }
+    }
+}
+
+impl OwnedEvent {
+    /// Borrows the event as an [`Event`], or returns `None` for events that require a backend context.
+    pub(crate) fn as_event<B: Backend>(&self) -> Option<Event<'_, B>> {
+        Some(match *self {
+            OwnedEvent::Configure {
+                rect,
+                style,
+                changed,
+            } => Event::Configure {
+                rect,
+                style,
+                changed,
+            },
+            OwnedEvent::ParentConfigure { rect, scale } => Event::ParentConfigure { rect, scale },
+            OwnedEvent::Realize | OwnedEvent::Unrealize | OwnedEvent::Expose { .. } => {
+                return None;
+            }
+            OwnedEvent::EnterLoop => Event::EnterLoop,
+            OwnedEvent::LeaveLoop => Event::LeaveLoop,
+            OwnedEvent::ScaleChange { scale } => Event::ScaleChange { scale },
+            OwnedEvent::Close => Event::Close,
+            OwnedEvent::Update => Event::Update,
+            OwnedEvent::FocusIn { mode } => Event::FocusIn { mode },
+            OwnedEvent::FocusOut { mode } => Event::FocusOut { mode },
+            OwnedEvent::KeyPress {
+                input,
+                keycode,
+                key,
+            } => Event::KeyPress {
+                input,
+                keycode,
+                key,
+            },
+            OwnedEvent::KeyRelease {
+                input,
+                keycode,
+                key,
+            } => Event::KeyRelease {
+                input,
+                keycode,
+                key,
+            },
+            OwnedEvent::KeyText {
+                input,
+                keycode,
+                ref text,
+            } => Event::KeyText {
+                input,
+                keycode,
+                text,
+            },
+            OwnedEvent::KeyboardInput {
+                input,
+                keycode,
+                key,
+                ref text,
+            } => Event::KeyboardInput {
+                input,
+                keycode,
+                key,
+                text: text.as_deref(),
+            },
+            OwnedEvent::DeadKey {
+                input,
+                keycode,
+                accent,
+            } => Event::DeadKey {
+                input,
+                keycode,
+                accent,
+            },
+            OwnedEvent::PointerIn { input, mode } => Event::PointerIn { input, mode },
+            OwnedEvent::PointerOut { input, mode } => Event::PointerOut { input, mode },
+            OwnedEvent::PointerMotion { input } => Event::PointerMotion { input },
+            OwnedEvent::ButtonPress { input, button } => Event::ButtonPress { input, button },
+            OwnedEvent::ButtonRelease { input, button } => Event::ButtonRelease { input, button },
+            OwnedEvent::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            } => Event::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            },
+            OwnedEvent::Timer { id } => Event::Timer { id },
+            OwnedEvent::Client { data } => Event::Client { data },
+            OwnedEvent::Clipboard {
+                ref mime_type,
+                ref text,
+            } => Event::Clipboard { mime_type, text },
+            OwnedEvent::ClipboardData {
+                ref mime_type,
+                ref data,
+            } => Event::ClipboardData { mime_type, data },
+            OwnedEvent::ClipboardFailed => Event::ClipboardFailed,
+            OwnedEvent::EndFrame => Event::EndFrame,
+        })
+    }
 }
 
 impl MouseCursor {
@@ -440,6 +1102,68 @@ impl MouseCursor {
     }
 }
 
+impl Status {
+    pub fn from_raw(raw: sys::PuglStatus) -> Self {
+        match raw {
+            sys::PUGL_SUCCESS => Status::Success,
+            sys::PUGL_FAILURE => Status::Failure,
+            sys::PUGL_BAD_BACKEND => Status::BadBackend,
+            sys::PUGL_BAD_CONFIGURATION => Status::BadConfiguration,
+            sys::PUGL_BAD_PARAMETER => Status::BadParameter,
+            sys::PUGL_BACKEND_FAILED => Status::BackendFailed,
+            sys::PUGL_REGISTRATION_FAILED => Status::RegistrationFailed,
+            sys::PUGL_REALIZE_FAILED => Status::RealizeFailed,
+            sys::PUGL_SET_FORMAT_FAILED => Status::SetFormatFailed,
+            sys::PUGL_CREATE_CONTEXT_FAILED => Status::CreateContextFailed,
+            sys::PUGL_UNSUPPORTED => Status::Unsupported,
+            sys::PUGL_NO_MEMORY => Status::NoMemory,
+            _ => Status::UnknownError,
+        }
+    }
+
+    pub fn into_raw(self) -> sys::PuglStatus {
+        match self {
+            Status::Success => sys::PUGL_SUCCESS,
+            Status::Failure => sys::PUGL_FAILURE,
+            Status::UnknownError => sys::PUGL_UNKNOWN_ERROR,
+            Status::BadBackend => sys::PUGL_BAD_BACKEND,
+            Status::BadConfiguration => sys::PUGL_BAD_CONFIGURATION,
+            Status::BadParameter => sys::PUGL_BAD_PARAMETER,
+            Status::BackendFailed => sys::PUGL_BACKEND_FAILED,
+            Status::RegistrationFailed => sys::PUGL_REGISTRATION_FAILED,
+            Status::RealizeFailed => sys::PUGL_REALIZE_FAILED,
+            Status::SetFormatFailed => sys::PUGL_SET_FORMAT_FAILED,
+            Status::CreateContextFailed => sys::PUGL_CREATE_CONTEXT_FAILED,
+            Status::Unsupported => sys::PUGL_UNSUPPORTED,
+            Status::NoMemory => sys::PUGL_NO_MEMORY,
+        }
+    }
+
+    /// Converts the status into a `Result`, treating everything but [`Status::Success`] as an error.
+    pub fn into_result(self) -> Result<(), Status> {
+        match self {
+            Status::Success => Ok(()),
+            status => Err(status),
+        }
+    }
+}
+
+impl std::error::Error for Status {}
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = unsafe { sys::puglStrerror(self.into_raw()) };
+        if message.is_null() {
+            write!(f, "unknown error")
+        } else {
+            write!(
+                f,
+                "{}",
+                unsafe { CStr::from_ptr(message) }.to_string_lossy()
+            )
+        }
+    }
+}
+
 impl ViewType {
     pub fn into_raw(self) -> u32 {
         match self {
@@ -460,6 +1184,16 @@ impl ScrollDirection {
             _ => ScrollDirection::Smooth,
         }
     }
+
+    pub fn into_raw(self) -> sys::PuglScrollDirection {
+        match self {
+            ScrollDirection::Up => sys::PUGL_SCROLL_UP,
+            ScrollDirection::Down => sys::PUGL_SCROLL_DOWN,
+            ScrollDirection::Left => sys::PUGL_SCROLL_LEFT,
+            ScrollDirection::Right => sys::PUGL_SCROLL_RIGHT,
+            ScrollDirection::Smooth => sys::PUGL_SCROLL_SMOOTH,
+        }
+    }
 }
 
 impl CrossingMode {
@@ -470,6 +1204,14 @@ impl CrossingMode {
             _ => CrossingMode::Normal,
         }
     }
+
+    pub fn into_raw(self) -> sys::PuglCrossingMode {
+        match self {
+            CrossingMode::Normal => sys::PUGL_CROSSING_NORMAL,
+            CrossingMode::Grab => sys::PUGL_CROSSING_GRAB,
+            CrossingMode::Ungrab => sys::PUGL_CROSSING_UNGRAB,
+        }
+    }
 }
 
 impl MouseButton {
@@ -483,6 +1225,17 @@ impl MouseButton {
             _ => MouseButton::Other(raw),
         }
     }
+
+    pub fn into_raw(self) -> u32 {
+        match self {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Back => 3,
+            MouseButton::Forward => 4,
+            MouseButton::Other(raw) => raw,
+        }
+    }
 }
 
 impl Key {
@@ -565,204 +1318,475 @@ impl Key {
             },
         }
     }
+
+    pub fn into_raw(self) -> u32 {
+        match self {
+            Key::None => 0,
+            Key::Char(char) => char as u32,
+            Key::AltL => sys::PUGL_KEY_ALT_L,
+            Key::AltR => sys::PUGL_KEY_ALT_R,
+            Key::CtrlL => sys::PUGL_KEY_CTRL_L,
+            Key::CtrlR => sys::PUGL_KEY_CTRL_R,
+            Key::ShiftL => sys::PUGL_KEY_SHIFT_L,
+            Key::ShiftR => sys::PUGL_KEY_SHIFT_R,
+            Key::SuperL => sys::PUGL_KEY_SUPER_L,
+            Key::SuperR => sys::PUGL_KEY_SUPER_R,
+            Key::CapsLock => sys::PUGL_KEY_CAPS_LOCK,
+            Key::NumLock => sys::PUGL_KEY_NUM_LOCK,
+            Key::Pause => sys::PUGL_KEY_PAUSE,
+            Key::PrintScreen => sys::PUGL_KEY_PRINT_SCREEN,
+            Key::ScrollLock => sys::PUGL_KEY_SCROLL_LOCK,
+            Key::PageDown => sys::PUGL_KEY_PAGE_DOWN,
+            Key::PageUp => sys::PUGL_KEY_PAGE_UP,
+            Key::End => sys::PUGL_KEY_END,
+            Key::Menu => sys::PUGL_KEY_MENU,
+            Key::Home => sys::PUGL_KEY_HOME,
+            Key::Insert => sys::PUGL_KEY_INSERT,
+            Key::F1 => sys::PUGL_KEY_F1,
+            Key::F2 => sys::PUGL_KEY_F2,
+            Key::F3 => sys::PUGL_KEY_F3,
+            Key::F4 => sys::PUGL_KEY_F4,
+            Key::F5 => sys::PUGL_KEY_F5,
+            Key::F6 => sys::PUGL_KEY_F6,
+            Key::F7 => sys::PUGL_KEY_F7,
+            Key::F8 => sys::PUGL_KEY_F8,
+            Key::F9 => sys::PUGL_KEY_F9,
+            Key::F10 => sys::PUGL_KEY_F10,
+            Key::F11 => sys::PUGL_KEY_F11,
+            Key::F12 => sys::PUGL_KEY_F12,
+            Key::Down => sys::PUGL_KEY_DOWN,
+            Key::Left => sys::PUGL_KEY_LEFT,
+            Key::Right => sys::PUGL_KEY_RIGHT,
+            Key::Up => sys::PUGL_KEY_UP,
+            Key::Numpad0 => sys::PUGL_KEY_PAD_0,
+            Key::Numpad1 => sys::PUGL_KEY_PAD_1,
+            Key::Numpad2 => sys::PUGL_KEY_PAD_2,
+            Key::Numpad3 => sys::PUGL_KEY_PAD_3,
+            Key::Numpad4 => sys::PUGL_KEY_PAD_4,
+            Key::Numpad5 => sys::PUGL_KEY_PAD_5,
+            Key::Numpad6 => sys::PUGL_KEY_PAD_6,
+            Key::Numpad7 => sys::PUGL_KEY_PAD_7,
+            Key::Numpad8 => sys::PUGL_KEY_PAD_8,
+            Key::Numpad9 => sys::PUGL_KEY_PAD_9,
+            Key::NumpadAdd => sys::PUGL_KEY_PAD_ADD,
+            Key::NumpadSubtract => sys::PUGL_KEY_PAD_SUBTRACT,
+            Key::NumpadMultiply => sys::PUGL_KEY_PAD_MULTIPLY,
+            Key::NumpadDivide => sys::PUGL_KEY_PAD_DIVIDE,
+            Key::NumpadDecimal => sys::PUGL_KEY_PAD_DECIMAL,
+            Key::NumpadEnter => sys::PUGL_KEY_PAD_ENTER,
+            Key::NumpadEqual => sys::PUGL_KEY_PAD_EQUAL,
+            Key::NumpadUp => sys::PUGL_KEY_PAD_UP,
+            Key::NumpadDown => sys::PUGL_KEY_PAD_DOWN,
+            Key::NumpadLeft => sys::PUGL_KEY_PAD_LEFT,
+            Key::NumpadRight => sys::PUGL_KEY_PAD_RIGHT,
+            Key::NumpadHome => sys::PUGL_KEY_PAD_HOME,
+            Key::NumpadEnd => sys::PUGL_KEY_PAD_END,
+            Key::NumpadPageUp => sys::PUGL_KEY_PAD_PAGE_UP,
+            Key::NumpadPageDown => sys::PUGL_KEY_PAD_PAGE_DOWN,
+            Key::NumpadInsert => sys::PUGL_KEY_PAD_INSERT,
+            Key::NumpadDelete => sys::PUGL_KEY_PAD_DELETE,
+            Key::NumpadSeparator => sys::PUGL_KEY_PAD_SEPARATOR,
+            Key::NumpadClear => sys::PUGL_KEY_PAD_CLEAR,
+        }
+    }
+}
+
+impl Key {
+    /// Returns a human-readable name of the key, like `"F5"`, `"Numpad Enter"` or `"A"`, for showing shortcut hints.
+    ///
+    /// The names are stable between versions and don't depend on the platform or the keyboard layout.
+    /// Character keys are named by their uppercase character, except for whitespace and control characters
+    /// (`"Space"`, `"Tab"`, `"Enter"`, `"Backspace"`, `"Escape"` and `"Delete"`).
+    pub fn name(self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Key::None => "None",
+            Key::Char(' ') => "Space",
+            Key::Char('\t') => "Tab",
+            Key::Char('\r' | '\n') => "Enter",
+            Key::Char('\x08') => "Backspace",
+            Key::Char('\x1b') => "Escape",
+            Key::Char('\x7f') => "Delete",
+            Key::Char(char) => return Cow::Owned(char.to_uppercase().collect()),
+            Key::F1 => "F1",
+            Key::F2 => "F2",
+            Key::F3 => "F3",
+            Key::F4 => "F4",
+            Key::F5 => "F5",
+            Key::F6 => "F6",
+            Key::F7 => "F7",
+            Key::F8 => "F8",
+            Key::F9 => "F9",
+            Key::F10 => "F10",
+            Key::F11 => "F11",
+            Key::F12 => "F12",
+            Key::Left => "Left",
+            Key::Up => "Up",
+            Key::Right => "Right",
+            Key::Down => "Down",
+            Key::PageUp => "Page Up",
+            Key::PageDown => "Page Down",
+            Key::Home => "Home",
+            Key::End => "End",
+            Key::Insert => "Insert",
+            Key::ShiftL => "Left Shift",
+            Key::ShiftR => "Right Shift",
+            Key::CtrlL => "Left Ctrl",
+            Key::CtrlR => "Right Ctrl",
+            Key::AltL => "Left Alt",
+            Key::AltR => "Right Alt",
+            Key::SuperL => "Left Super",
+            Key::SuperR => "Right Super",
+            Key::Menu => "Menu",
+            Key::CapsLock => "Caps Lock",
+            Key::ScrollLock => "Scroll Lock",
+            Key::NumLock => "Num Lock",
+            Key::PrintScreen => "Print Screen",
+            Key::Pause => "Pause",
+            Key::Numpad0 => "Numpad 0",
+            Key::Numpad1 => "Numpad 1",
+            Key::Numpad2 => "Numpad 2",
+            Key::Numpad3 => "Numpad 3",
+            Key::Numpad4 => "Numpad 4",
+            Key::Numpad5 => "Numpad 5",
+            Key::Numpad6 => "Numpad 6",
+            Key::Numpad7 => "Numpad 7",
+            Key::Numpad8 => "Numpad 8",
+            Key::Numpad9 => "Numpad 9",
+            Key::NumpadAdd => "Numpad Add",
+            Key::NumpadSubtract => "Numpad Subtract",
+            Key::NumpadMultiply => "Numpad Multiply",
+            Key::NumpadDivide => "Numpad Divide",
+            Key::NumpadDecimal => "Numpad Decimal",
+            Key::NumpadEnter => "Numpad Enter",
+            Key::NumpadEqual => "Numpad Equal",
+            Key::NumpadUp => "Numpad Up",
+            Key::NumpadDown => "Numpad Down",
+            Key::NumpadLeft => "Numpad Left",
+            Key::NumpadRight => "Numpad Right",
+            Key::NumpadHome => "Numpad Home",
+            Key::NumpadEnd => "Numpad End",
+            Key::NumpadPageUp => "Numpad Page Up",
+            Key::NumpadPageDown => "Numpad Page Down",
+            Key::NumpadInsert => "Numpad Insert",
+            Key::NumpadDelete => "Numpad Delete",
+            Key::NumpadSeparator => "Numpad Separator",
+            Key::NumpadClear => "Numpad Clear",
+        })
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+impl Modifiers {
+    /// Returns a human-readable name of the modifiers joined with `+`, like `"Ctrl+Shift"`, for showing shortcut hints.
+    ///
+    /// The modifiers are always listed in the same order (`Ctrl`, `Alt`, `Shift`, `Super`, followed by the lock keys),
+    /// and the name is empty if no modifier is set.
+    pub fn name(self) -> String {
+        const NAMES: [(Modifiers, &str); 7] = [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+            (Modifiers::CAPS_LOCK, "Caps Lock"),
+            (Modifiers::NUM_LOCK, "Num Lock"),
+            (Modifiers::SCROLL_LOCK, "Scroll Lock"),
+        ];
+
+        NAMES
+            .iter()
+            .filter(|(modifier, _)| self.contains(*modifier))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
 }
 
+impl std::fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// The MIME type of pasted data whose type is missing or not valid UTF-8.
+const UNKNOWN_MIME_TYPE: &str = "application/octet-stream";
+
 impl<'a, B: Backend> Event<'a, B> {
+    /// Returns the pasted text of an [`Event::Clipboard`] or an [`Event::ClipboardData`] event.
+    ///
+    /// Invalid UTF-8 sequences in [`Event::ClipboardData`] are replaced with `U+FFFD`, which is useful for text that
+    /// was pasted in a wrong encoding. Returns `None` for other events.
+    pub fn clipboard_text_lossy(&self) -> Option<Cow<'a, str>> {
+        match *self {
+            Event::Clipboard { text, .. } => Some(Cow::Borrowed(text)),
+            Event::ClipboardData { data, .. } => Some(String::from_utf8_lossy(data)),
+            _ => None,
+        }
+    }
+
+    /// Converts an event of `view`. `lookup_text` is the complete text of a `PUGL_TEXT` event, see [`take_lookup_text`].
     pub(crate) unsafe fn process(
         view: *mut sys::PuglView,
         event: *const sys::PuglEvent,
+        lookup_text: Option<&'a str>,
     ) -> Option<Self> {
         unsafe {
             Some(match (*event).type_ {
                 sys::PUGL_REALIZE => Event::Realize {
-                    backend: B::setup(view, crate::private::Private),
+                    backend: setup_context::<B>(view),
                 },
-
                 sys::PUGL_UNREALIZE => Event::Unrealize {
-                    backend: B::setup(view, crate::private::Private),
+                    backend: setup_context::<B>(view),
                 },
+                sys::PUGL_EXPOSE => Event::Expose {
+                    backend: draw_context::<B>(view),
+                    rect: Rect {
+                        x: (*event).expose.x as i32,
+                        y: (*event).expose.y as i32,
+                        w: (*event).expose.width as u32,
+                        h: (*event).expose.height as u32,
+                    },
+                },
+                sys::PUGL_DATA => {
+                    // a malformed offer is still delivered, so the paste is never silently dropped
+                    let type_ = sys::puglGetClipboardType(view, (*event).data.typeIndex);
+                    let mime_type = if type_.is_null() {
+                        UNKNOWN_MIME_TYPE
+                    } else {
+                        CStr::from_ptr(type_).to_str().unwrap_or(UNKNOWN_MIME_TYPE)
+                    };
+
+                    let mut len = 0;
+                    let data = sys::puglGetClipboard(view, (*event).data.typeIndex, &mut len);
+                    let data = if data.is_null() {
+                        &[]
+                    } else {
+                        from_raw_parts(data as *const u8, len)
+                    };
+
+                    match from_utf8(data) {
+                        Ok(text) => Event::Clipboard { mime_type, text },
+                        Err(_) => Event::ClipboardData { mime_type, data },
+                    }
+                }
+
+                sys::PUGL_TEXT => match Self::from_raw(&*event)? {
+                    Event::KeyText {
+                        input,
+                        keycode,
+                        text,
+                    } => Event::KeyText {
+                        input,
+                        keycode,
+                        text: full_text(text, lookup_text),
+                    },
+                    event => event,
+                },
+
+                _ => return Self::from_raw(&*event),
+            })
+        }
+    }
 
+    /// Converts the events that don't depend on the view or the backend.
+    ///
+    /// This is safe for any bit pattern of `event`, since all fields of `PuglEvent` are plain integers and floats.
+    pub(crate) fn from_raw(event: &'a sys::PuglEvent) -> Option<Self> {
+        unsafe {
+            Some(match event.type_ {
                 sys::PUGL_LOOP_ENTER => Event::EnterLoop,
                 sys::PUGL_LOOP_LEAVE => Event::LeaveLoop,
                 sys::PUGL_CONFIGURE => Event::Configure {
-                    style: ViewStyle::from_bits_truncate((*event).configure.style),
+                    style: ViewStyle::from_bits_truncate(event.configure.style),
+                    // filled in by the view, which knows the previous style
+                    changed: ViewStyle::empty(),
                     rect: Rect {
-                        x: (*event).configure.x as i32,
-                        y: (*event).configure.y as i32,
-                        w: (*event).configure.width as u32,
-                        h: (*event).configure.height as u32,
+                        x: event.configure.x as i32,
+                        y: event.configure.y as i32,
+                        w: event.configure.width as u32,
+                        h: event.configure.height as u32,
                     },
                 },
                 sys::PUGL_CLOSE => Event::Close,
                 sys::PUGL_UPDATE => Event::Update,
-                sys::PUGL_EXPOSE => Event::Expose {
-                    backend: B::draw(view, crate::private::Private),
-                    rect: Rect {
-                        x: (*event).expose.x as i32,
-                        y: (*event).expose.y as i32,
-                        w: (*event).expose.width as u32,
-                        h: (*event).expose.height as u32,
-                    },
-                },
                 sys::PUGL_FOCUS_IN => Event::FocusIn {
-                    mode: CrossingMode::from_raw((*event).focus.mode),
+                    mode: CrossingMode::from_raw(event.focus.mode),
                 },
                 sys::PUGL_FOCUS_OUT => Event::FocusOut {
-                    mode: CrossingMode::from_raw((*event).focus.mode),
+                    mode: CrossingMode::from_raw(event.focus.mode),
                 },
                 sys::PUGL_KEY_PRESS => Event::KeyPress {
                     input: EventInput {
-                        time: (*event).key.time,
-                        x: (*event).key.x,
-                        y: (*event).key.y,
-                        root_x: (*event).key.xRoot,
-                        root_y: (*event).key.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).key.state),
-                        hint: ((*event).key.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.key.time),
+                        position: ViewPoint::new(event.key.x, event.key.y),
+                        root_position: ScreenPoint::new(event.key.xRoot, event.key.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.key.state),
+                        hint: (event.key.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    keycode: (*event).key.keycode,
-                    key: Key::from_raw((*event).key.key),
+                    keycode: event.key.keycode,
+                    key: Key::from_raw(event.key.key),
                 },
                 sys::PUGL_KEY_RELEASE => Event::KeyRelease {
                     input: EventInput {
-                        time: (*event).key.time,
-                        x: (*event).key.x,
-                        y: (*event).key.y,
-                        root_x: (*event).key.xRoot,
-                        root_y: (*event).key.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).key.state),
-                        hint: ((*event).key.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.key.time),
+                        position: ViewPoint::new(event.key.x, event.key.y),
+                        root_position: ScreenPoint::new(event.key.xRoot, event.key.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.key.state),
+                        hint: (event.key.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    keycode: (*event).key.keycode,
-                    key: Key::from_raw((*event).key.key),
+                    keycode: event.key.keycode,
+                    key: Key::from_raw(event.key.key),
                 },
                 sys::PUGL_TEXT => Event::KeyText {
                     input: EventInput {
-                        time: (*event).key.time,
-                        x: (*event).key.x,
-                        y: (*event).key.y,
-                        root_x: (*event).key.xRoot,
-                        root_y: (*event).key.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).key.state),
-                        hint: ((*event).key.flags & sys::PUGL_IS_HINT) != 0,
-                    },
-                    keycode: (*event).key.keycode,
-                    text: {
-                        let bytes = &*addr_of!((*event).text.string).cast::<[u8; 8]>();
-                        let len = bytes.iter().position(|&b| b == 0).unwrap_or(8);
-                        from_utf8(&bytes[..len]).ok()?
+                        time: WorldInstant(event.key.time),
+                        position: ViewPoint::new(event.key.x, event.key.y),
+                        root_position: ScreenPoint::new(event.key.xRoot, event.key.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.key.state),
+                        hint: (event.key.flags & sys::PUGL_IS_HINT) != 0,
                     },
+                    keycode: event.key.keycode,
+                    text: text_from_buffer(&*addr_of!(event.text.string).cast::<[u8; 8]>()),
                 },
                 sys::PUGL_POINTER_IN => Event::PointerIn {
                     input: EventInput {
-                        time: (*event).crossing.time,
-                        x: (*event).crossing.x,
-                        y: (*event).crossing.y,
-                        root_x: (*event).crossing.xRoot,
-                        root_y: (*event).crossing.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).crossing.state),
-                        hint: ((*event).crossing.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.crossing.time),
+                        position: ViewPoint::new(event.crossing.x, event.crossing.y),
+                        root_position: ScreenPoint::new(event.crossing.xRoot, event.crossing.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.crossing.state),
+                        hint: (event.crossing.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    mode: CrossingMode::from_raw((*event).crossing.mode),
+                    mode: CrossingMode::from_raw(event.crossing.mode),
                 },
                 sys::PUGL_POINTER_OUT => Event::PointerOut {
                     input: EventInput {
-                        time: (*event).crossing.time,
-                        x: (*event).crossing.x,
-                        y: (*event).crossing.y,
-                        root_x: (*event).crossing.xRoot,
-                        root_y: (*event).crossing.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).crossing.state),
-                        hint: ((*event).crossing.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.crossing.time),
+                        position: ViewPoint::new(event.crossing.x, event.crossing.y),
+                        root_position: ScreenPoint::new(event.crossing.xRoot, event.crossing.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.crossing.state),
+                        hint: (event.crossing.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    mode: CrossingMode::from_raw((*event).crossing.mode),
+                    mode: CrossingMode::from_raw(event.crossing.mode),
                 },
                 sys::PUGL_BUTTON_PRESS => Event::ButtonPress {
                     input: EventInput {
-                        time: (*event).button.time,
-                        x: (*event).button.x,
-                        y: (*event).button.y,
-                        root_x: (*event).button.xRoot,
-                        root_y: (*event).button.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).button.state),
-                        hint: ((*event).button.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.button.time),
+                        position: ViewPoint::new(event.button.x, event.button.y),
+                        root_position: ScreenPoint::new(event.button.xRoot, event.button.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.button.state),
+                        hint: (event.button.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    button: MouseButton::from_raw((*event).button.button),
+                    button: MouseButton::from_raw(event.button.button),
                 },
                 sys::PUGL_BUTTON_RELEASE => Event::ButtonRelease {
                     input: EventInput {
-                        time: (*event).button.time,
-                        x: (*event).button.x,
-                        y: (*event).button.y,
-                        root_x: (*event).button.xRoot,
-                        root_y: (*event).button.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).button.state),
-                        hint: ((*event).button.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.button.time),
+                        position: ViewPoint::new(event.button.x, event.button.y),
+                        root_position: ScreenPoint::new(event.button.xRoot, event.button.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.button.state),
+                        hint: (event.button.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    button: MouseButton::from_raw((*event).button.button),
+                    button: MouseButton::from_raw(event.button.button),
                 },
                 sys::PUGL_MOTION => Event::PointerMotion {
                     input: EventInput {
-                        time: (*event).motion.time,
-                        x: (*event).motion.x,
-                        y: (*event).motion.y,
-                        root_x: (*event).motion.xRoot,
-                        root_y: (*event).motion.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).motion.state),
-                        hint: ((*event).motion.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.motion.time),
+                        position: ViewPoint::new(event.motion.x, event.motion.y),
+                        root_position: ScreenPoint::new(event.motion.xRoot, event.motion.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.motion.state),
+                        hint: (event.motion.flags & sys::PUGL_IS_HINT) != 0,
                     },
                 },
                 sys::PUGL_SCROLL => Event::Scroll {
                     input: EventInput {
-                        time: (*event).scroll.time,
-                        x: (*event).scroll.x,
-                        y: (*event).scroll.y,
-                        root_x: (*event).scroll.xRoot,
-                        root_y: (*event).scroll.yRoot,
-                        mods: Modifiers::from_bits_truncate((*event).scroll.state),
-                        hint: ((*event).scroll.flags & sys::PUGL_IS_HINT) != 0,
+                        time: WorldInstant(event.scroll.time),
+                        position: ViewPoint::new(event.scroll.x, event.scroll.y),
+                        root_position: ScreenPoint::new(event.scroll.xRoot, event.scroll.yRoot),
+                        mods: Modifiers::from_bits_truncate(event.scroll.state),
+                        hint: (event.scroll.flags & sys::PUGL_IS_HINT) != 0,
                     },
-                    dx: (*event).scroll.dx,
-                    dy: (*event).scroll.dy,
-                    direction: ScrollDirection::from_raw((*event).scroll.direction),
+                    dx: event.scroll.dx,
+                    dy: event.scroll.dy,
+                    direction: ScrollDirection::from_raw(event.scroll.direction),
                 },
-
                 sys::PUGL_CLIENT => Event::Client {
-                    data: [(*event).client.data1, (*event).client.data2],
+                    data: [event.client.data1, event.client.data2],
                 },
+                sys::PUGL_TIMER => Event::Timer { id: event.timer.id },
 
-                sys::PUGL_TIMER => Event::Timer {
-                    id: (*event).timer.id,
-                },
+                _ => return None,
+            })
+        }
+    }
+}
 
-                sys::PUGL_DATA_OFFER => {
-                    let num_types = sys::puglGetNumClipboardTypes(view);
-                    for i in 0..num_types {
-                        let type_ = sys::puglGetClipboardType(view, i);
-                        if CStr::from_ptr(type_).to_str() == Ok("text/plain") {
-                            sys::puglAcceptOffer(view, &(*event).offer, i);
-                        }
-                    }
+/// Runs `f` with a nul-terminated copy of `string`, without allocating for short strings (like titles that are updated often).
+///
+/// Nul bytes can't be passed to `pugl`, so they are removed from `string`.
+pub(crate) fn with_c_str<R>(string: &str, f: impl FnOnce(&CStr) -> R) -> R {
+    const STACK_SIZE: usize = 256;
 
-                    return None;
-                }
+    if string.as_bytes().contains(&0) {
+        return with_c_str(&string.replace('\0', ""), f);
+    }
 
-                sys::PUGL_DATA => {
-                    let type_ = sys::puglGetClipboardType(view, (*event).data.typeIndex);
-                    if CStr::from_ptr(type_).to_str() == Ok("text/plain") {
-                        let mut len = 0;
-                        let data = sys::puglGetClipboard(view, (*event).data.typeIndex, &mut len);
-                        if !data.is_null() {
-                            let text = from_utf8(from_raw_parts(data as *const u8, len)).ok()?;
-                            return Some(Event::Clipboard { text });
-                        }
-                    }
+    if string.len() < STACK_SIZE {
+        let mut buffer = [0u8; STACK_SIZE];
+        buffer[..string.len()].copy_from_slice(string.as_bytes());
+        let string = CStr::from_bytes_with_nul(&buffer[..=string.len()])
+            .expect("nul bytes are removed above");
+        f(string)
+    } else {
+        let string = CString::new(string).expect("nul bytes are removed above");
+        f(&string)
+    }
+}
 
-                    return None;
-                }
+/// Returns the text of a `PUGL_TEXT` event.
+///
+/// `pugl` passes the text in an 8 byte buffer, so on X11 the complete text of a longer input method commit
+/// is taken from the last key press that `pugl` looked up instead, if the text of the event is its beginning.
+fn full_text<'a>(text: &'a str, lookup_text: Option<&'a str>) -> &'a str {
+    match lookup_text {
+        Some(full) if !text.is_empty() && full.len() > text.len() && full.starts_with(text) => full,
+        _ => text,
+    }
+}
 
-                _ => return None,
-            })
-        }
+/// Takes the complete text of the last key press that `pugl` looked up, see `puglRsGetLookupText`.
+///
+/// Must be called once for every `PUGL_TEXT` event, so the text of a commit isn't used for the text of a later key press.
+/// The text is copied, since it is replaced when the next key press is looked up.
+pub(crate) fn take_lookup_text() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    unsafe {
+        let mut len = 0;
+        let ptr = sys::puglRsGetLookupText(&mut len);
+        let text = match ptr.is_null() || len == 0 {
+            true => None,
+            false => from_utf8(std::slice::from_raw_parts(ptr as *const u8, len))
+                .ok()
+                .map(str::to_owned),
+        };
+
+        sys::puglRsClearLookupText();
+        text
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// Returns the text of a `PUGL_TEXT` event, up to the first nul byte.
+/// If the buffer was filled up in the middle of a character, the incomplete character is left out instead of dropping the whole event.
+fn text_from_buffer(bytes: &[u8; 8]) -> &str {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    match from_utf8(&bytes[..len]) {
+        Ok(text) => text,
+        Err(error) => from_utf8(&bytes[..error.valid_up_to()]).unwrap_or_default(),
     }
 }
@@ -0,0 +1,794 @@
+use crate::{Backend, NativeView, View};
+use std::{fmt, path::PathBuf};
+
+/// A native dialog for choosing files or folders to open, or a file to save to.
+///
+/// The dialog is modal and blocks until it is closed, so the views are not redrawn in the meantime.
+/// - Windows: the common item dialog (`IFileOpenDialog` and `IFileSaveDialog`), owned by the window of the parent view.
+/// - MacOS: `NSOpenPanel` and `NSSavePanel`, which are application-modal instead of being attached to the parent window as a sheet.
+/// - X11: the file chooser of the XDG desktop portal, or `zenity` or `kdialog` if the portal is not available.
+///   There is no dialog if neither is available.
+/// ```no_run
+/// # use pugl_rs::*;
+/// # fn load(view: &View<()>) -> Result<(), DialogError> {
+/// let sample = FileDialog::new()
+///     .with_parent(view)
+///     .with_title("Load sample")
+///     .with_filter("Audio files", &["wav", "flac"])
+///     .pick_file()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FileDialog {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    parent: Option<NativeView>,
+}
+
+/// File dialog error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogError {
+    /// There is no dialog available (on X11 without the desktop portal, `zenity` and `kdialog`)
+    Unavailable,
+    /// The dialog was shown outside of the main thread on a platform that requires it (MacOS)
+    NotMainThread,
+}
+
+impl std::error::Error for DialogError {}
+impl fmt::Display for DialogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => write!(f, "no file dialog available"),
+            Self::NotMainThread => write!(f, "file dialog must be shown on the main thread"),
+        }
+    }
+}
+
+/// What is chosen in a [`FileDialog`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DialogMode {
+    Open,
+    OpenMultiple,
+    Folder,
+    Save,
+}
+
+impl FileDialog {
+    /// Creates a dialog without a title, a parent or filters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Set the directory that is shown when the dialog opens.
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Set the initial file name of a save dialog.
+    pub fn with_file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(file_name.to_owned());
+        self
+    }
+
+    /// Add a filter that only shows the files with one of the extensions (without the leading dot).
+    ///
+    /// The first filter is selected when the dialog opens.
+    pub fn with_filter(mut self, name: &str, extensions: &[&str]) -> Self {
+        self.filters.push((
+            name.to_owned(),
+            extensions
+                .iter()
+                .map(|&extension| extension.to_owned())
+                .collect(),
+        ));
+        self
+    }
+
+    /// Make the dialog a transient dialog of the window of the view, so it stays on top of it.
+    pub fn with_parent<B: Backend>(mut self, view: &View<B>) -> Self {
+        self.parent = Some(view.native());
+        self
+    }
+
+    /// Shows the dialog for choosing a file to open.
+    ///
+    /// Returns `None` if the dialog was cancelled.
+    pub fn pick_file(&self) -> Result<Option<PathBuf>, DialogError> {
+        Ok(platform::run(self, DialogMode::Open)?.into_iter().next())
+    }
+
+    /// Shows the dialog for choosing any number of files to open.
+    ///
+    /// Returns an empty list if the dialog was cancelled.
+    pub fn pick_files(&self) -> Result<Vec<PathBuf>, DialogError> {
+        platform::run(self, DialogMode::OpenMultiple)
+    }
+
+    /// Shows the dialog for choosing a folder.
+    ///
+    /// Returns `None` if the dialog was cancelled.
+    pub fn pick_folder(&self) -> Result<Option<PathBuf>, DialogError> {
+        Ok(platform::run(self, DialogMode::Folder)?.into_iter().next())
+    }
+
+    /// Shows the dialog for choosing the file to save to, which asks the user to confirm overwriting an existing file.
+    ///
+    /// Returns `None` if the dialog was cancelled.
+    pub fn save_file(&self) -> Result<Option<PathBuf>, DialogError> {
+        Ok(platform::run(self, DialogMode::Save)?.into_iter().next())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{DialogError, DialogMode, FileDialog};
+    use crate::portal;
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf, process::Command};
+
+    pub fn run(dialog: &FileDialog, mode: DialogMode) -> Result<Vec<PathBuf>, DialogError> {
+        file_chooser(dialog, mode)
+            .or_else(|| zenity(dialog, mode))
+            .or_else(|| kdialog(dialog, mode))
+            .ok_or(DialogError::Unavailable)
+    }
+
+    /// Shows the file chooser of the XDG desktop portal, returns `None` if the portal is not available.
+    ///
+    /// Choosing folders needs version 3 of the file chooser, older versions show a dialog for choosing files instead.
+    fn file_chooser(dialog: &FileDialog, mode: DialogMode) -> Option<Vec<PathBuf>> {
+        let (method, default_title) = match mode {
+            DialogMode::Open => ("OpenFile", "Open File"),
+            DialogMode::OpenMultiple => ("OpenFile", "Open Files"),
+            DialogMode::Folder => ("OpenFile", "Open Folder"),
+            DialogMode::Save => ("SaveFile", "Save File"),
+        };
+
+        let parent = dialog
+            .parent
+            .map(|parent| format!("x11:{:x}", parent.as_raw()))
+            .unwrap_or_default();
+        let title = dialog.title.as_deref().unwrap_or(default_title);
+
+        let arguments = |arguments: &mut portal::Writer| {
+            arguments.string(&parent);
+            arguments.string(title);
+        };
+
+        let options = |options: &mut portal::Writer| {
+            if dialog.parent.is_some() {
+                options.entry("modal", "b", |value| value.bool(true));
+            }
+
+            match mode {
+                DialogMode::OpenMultiple => {
+                    options.entry("multiple", "b", |value| value.bool(true));
+                }
+                DialogMode::Folder => {
+                    options.entry("directory", "b", |value| value.bool(true));
+                }
+                DialogMode::Open | DialogMode::Save => {}
+            }
+
+            if mode != DialogMode::Folder && !dialog.filters.is_empty() {
+                options.entry("filters", "a(sa(us))", |value| {
+                    value.array(8, |filters| {
+                        for (name, extensions) in &dialog.filters {
+                            filters.structure(|filter| {
+                                filter.string(name);
+                                filter.array(8, |patterns| {
+                                    for extension in extensions {
+                                        // 0 is a glob pattern, 1 a MIME type
+                                        patterns.structure(|pattern| {
+                                            pattern.u32(0);
+                                            pattern.string(&format!("*.{extension}"));
+                                        });
+                                    }
+                                });
+                            });
+                        }
+                    });
+                });
+            }
+
+            if let Some(directory) = &dialog.directory {
+                options.entry("current_folder", "ay", |value| {
+                    value.array(1, |bytes| {
+                        bytes.bytes(directory.as_os_str().as_bytes());
+                        bytes.byte(0);
+                    });
+                });
+            }
+
+            if mode == DialogMode::Save
+                && let Some(file_name) = &dialog.file_name
+            {
+                options.entry("current_name", "s", |value| value.string(file_name));
+            }
+        };
+
+        let response = portal::request(
+            "org.freedesktop.portal.FileChooser",
+            method,
+            "ssa{sv}",
+            arguments,
+            options,
+        )?;
+
+        if response.code != 0 {
+            return Some(Vec::new());
+        }
+
+        let uris = response.strings("uris").unwrap_or_default();
+        Some(uris.iter().filter_map(|uri| uri_path(uri)).collect())
+    }
+
+    /// Returns the path of a `file://` URI.
+    fn uri_path(uri: &str) -> Option<PathBuf> {
+        let uri = uri.strip_prefix("file://")?;
+
+        // skips the host, which is empty or `localhost` for local files
+        let path = &uri[uri.find('/')?..];
+        Some(PathBuf::from(OsStr::from_bytes(&portal::unescape(path))))
+    }
+
+    /// Returns `None` if the command is not installed, and an empty list if the dialog was cancelled.
+    fn output(mut command: Command) -> Option<Vec<PathBuf>> {
+        let output = command.output().ok()?;
+        if !output.status.success() {
+            return Some(Vec::new());
+        }
+
+        // the paths are printed as they are, which may not be valid UTF-8
+        Some(
+            output
+                .stdout
+                .split(|&byte| byte == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| PathBuf::from(OsStr::from_bytes(line)))
+                .collect(),
+        )
+    }
+
+    /// The start path, which is the initial file name in the initial directory.
+    fn start(dialog: &FileDialog) -> Option<PathBuf> {
+        match (&dialog.directory, &dialog.file_name) {
+            (Some(directory), Some(file_name)) => Some(directory.join(file_name)),
+            (Some(directory), None) => Some(directory.join("")),
+            (None, Some(file_name)) => Some(PathBuf::from(file_name)),
+            (None, None) => None,
+        }
+    }
+
+    fn zenity(dialog: &FileDialog, mode: DialogMode) -> Option<Vec<PathBuf>> {
+        let mut command = Command::new("zenity");
+        command.arg("--file-selection");
+
+        match mode {
+            DialogMode::Open => {}
+            DialogMode::OpenMultiple => {
+                command.args(["--multiple", "--separator=\n"]);
+            }
+            DialogMode::Folder => {
+                command.arg("--directory");
+            }
+            DialogMode::Save => {
+                command.args(["--save", "--confirm-overwrite"]);
+            }
+        }
+
+        if let Some(title) = &dialog.title {
+            command.arg(format!("--title={title}"));
+        }
+
+        if let Some(start) = start(dialog) {
+            command.arg(format!("--filename={}", start.display()));
+        }
+
+        for (name, extensions) in &dialog.filters {
+            let patterns = extensions
+                .iter()
+                .map(|extension| format!("*.{extension}"))
+                .collect::<Vec<_>>();
+            command.arg(format!("--file-filter={name} | {}", patterns.join(" ")));
+        }
+
+        if let Some(parent) = dialog.parent {
+            command.arg(format!("--attach={}", parent.as_raw()));
+        }
+
+        output(command)
+    }
+
+    fn kdialog(dialog: &FileDialog, mode: DialogMode) -> Option<Vec<PathBuf>> {
+        let mut command = Command::new("kdialog");
+
+        if let Some(title) = &dialog.title {
+            command.args(["--title", title]);
+        }
+
+        if let Some(parent) = dialog.parent {
+            command.args(["--attach", &parent.as_raw().to_string()]);
+        }
+
+        match mode {
+            DialogMode::Open => {
+                command.arg("--getopenfilename");
+            }
+            DialogMode::OpenMultiple => {
+                command.args(["--getopenfilename", "--multiple", "--separate-output"]);
+            }
+            DialogMode::Folder => {
+                command.arg("--getexistingdirectory");
+            }
+            DialogMode::Save => {
+                command.arg("--getsavefilename");
+            }
+        }
+
+        let start = start(dialog).unwrap_or_else(|| PathBuf::from("."));
+        command.arg(start);
+
+        if mode != DialogMode::Folder && !dialog.filters.is_empty() {
+            let filters = dialog
+                .filters
+                .iter()
+                .map(|(name, extensions)| {
+                    let patterns = extensions
+                        .iter()
+                        .map(|extension| format!("*.{extension}"))
+                        .collect::<Vec<_>>();
+                    format!("{} ({})|{}", name, patterns.join(" "), patterns.join(" "))
+                })
+                .collect::<Vec<_>>();
+            command.arg(filters.join("\n"));
+        }
+
+        output(command)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{DialogError, DialogMode, FileDialog};
+    use std::{
+        ffi::OsString,
+        os::{
+            raw::c_void,
+            windows::ffi::{OsStrExt, OsStringExt},
+        },
+        path::PathBuf,
+        ptr::null_mut,
+    };
+
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+
+    #[repr(C)]
+    struct FilterSpec {
+        name: *const u16,
+        spec: *const u16,
+    }
+
+    type Object = *mut c_void;
+    type HResult = i32;
+
+    #[link(name = "ole32")]
+    unsafe extern "system" {
+        fn CoInitializeEx(reserved: *mut c_void, flags: u32) -> HResult;
+        fn CoUninitialize();
+        fn CoCreateInstance(
+            class: *const Guid,
+            outer: Object,
+            context: u32,
+            iid: *const Guid,
+            object: *mut Object,
+        ) -> HResult;
+        fn CoTaskMemFree(ptr: *mut c_void);
+    }
+
+    #[link(name = "shell32")]
+    unsafe extern "system" {
+        fn SHCreateItemFromParsingName(
+            path: *const u16,
+            bind: *mut c_void,
+            iid: *const Guid,
+            item: *mut Object,
+        ) -> HResult;
+    }
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetAncestor(hwnd: isize, flags: u32) -> isize;
+    }
+
+    #[repr(C)]
+    struct UnknownVtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(Object) -> u32,
+    }
+
+    /// `IFileDialog` (including `IModalWindow`), followed by the methods of `IFileOpenDialog`.
+    #[repr(C)]
+    struct FileDialogVtbl {
+        base: UnknownVtbl,
+        show: unsafe extern "system" fn(Object, isize) -> HResult,
+        set_file_types: unsafe extern "system" fn(Object, u32, *const FilterSpec) -> HResult,
+        set_file_type_index: usize,
+        get_file_type_index: usize,
+        advise: usize,
+        unadvise: usize,
+        set_options: unsafe extern "system" fn(Object, u32) -> HResult,
+        get_options: unsafe extern "system" fn(Object, *mut u32) -> HResult,
+        set_default_folder: usize,
+        set_folder: unsafe extern "system" fn(Object, Object) -> HResult,
+        get_folder: usize,
+        get_current_selection: usize,
+        set_file_name: unsafe extern "system" fn(Object, *const u16) -> HResult,
+        get_file_name: usize,
+        set_title: unsafe extern "system" fn(Object, *const u16) -> HResult,
+        set_ok_button_label: usize,
+        set_file_name_label: usize,
+        get_result: unsafe extern "system" fn(Object, *mut Object) -> HResult,
+        add_place: usize,
+        set_default_extension: unsafe extern "system" fn(Object, *const u16) -> HResult,
+        close: usize,
+        set_client_guid: usize,
+        clear_client_data: usize,
+        set_filter: usize,
+        /// only valid for `IFileOpenDialog`
+        get_results: unsafe extern "system" fn(Object, *mut Object) -> HResult,
+    }
+
+    #[repr(C)]
+    struct ShellItemVtbl {
+        base: UnknownVtbl,
+        bind_to_handler: usize,
+        get_parent: usize,
+        get_display_name: unsafe extern "system" fn(Object, u32, *mut *mut u16) -> HResult,
+    }
+
+    #[repr(C)]
+    struct ShellItemArrayVtbl {
+        base: UnknownVtbl,
+        bind_to_handler: usize,
+        get_property_store: usize,
+        get_property_description_list: usize,
+        get_attributes: usize,
+        get_count: unsafe extern "system" fn(Object, *mut u32) -> HResult,
+        get_item_at: unsafe extern "system" fn(Object, u32, *mut Object) -> HResult,
+    }
+
+    const CLSID_FILE_OPEN_DIALOG: Guid = Guid(
+        0xDC1C5A9C,
+        0xE88A,
+        0x4DDE,
+        [0xA5, 0xA1, 0x60, 0xF8, 0x2A, 0x20, 0xAE, 0xF7],
+    );
+    const CLSID_FILE_SAVE_DIALOG: Guid = Guid(
+        0xC0B4E2F3,
+        0xBA21,
+        0x4773,
+        [0x8D, 0xBA, 0x33, 0x5E, 0xC9, 0x46, 0xEB, 0x8B],
+    );
+    const IID_FILE_OPEN_DIALOG: Guid = Guid(
+        0xD57C7288,
+        0xD4AD,
+        0x4768,
+        [0xBE, 0x02, 0x9D, 0x96, 0x95, 0x32, 0xD9, 0x60],
+    );
+    const IID_FILE_SAVE_DIALOG: Guid = Guid(
+        0x84BCCD23,
+        0x5FDE,
+        0x4CDB,
+        [0xAE, 0xA4, 0xAF, 0x64, 0xB8, 0x3D, 0x78, 0xAB],
+    );
+    const IID_SHELL_ITEM: Guid = Guid(
+        0x43826D1E,
+        0xE718,
+        0x42EE,
+        [0xBC, 0x55, 0xA1, 0xE2, 0x61, 0xC3, 0x7B, 0xFE],
+    );
+
+    const COINIT_APARTMENTTHREADED: u32 = 0x2;
+    const COINIT_DISABLE_OLE1DDE: u32 = 0x4;
+    const CLSCTX_INPROC_SERVER: u32 = 0x1;
+    const GA_ROOT: u32 = 2;
+    const SIGDN_FILESYSPATH: u32 = 0x80058000;
+
+    const FOS_OVERWRITEPROMPT: u32 = 0x2;
+    const FOS_PICKFOLDERS: u32 = 0x20;
+    const FOS_FORCEFILESYSTEM: u32 = 0x40;
+    const FOS_ALLOWMULTISELECT: u32 = 0x200;
+
+    unsafe fn vtbl<'a, T>(object: Object) -> &'a T {
+        unsafe { &**(object as *mut *const T) }
+    }
+
+    unsafe fn release(object: Object) {
+        unsafe {
+            if !object.is_null() {
+                (vtbl::<UnknownVtbl>(object).release)(object);
+            }
+        }
+    }
+
+    fn wide(string: impl AsRef<std::ffi::OsStr>) -> Vec<u16> {
+        string.as_ref().encode_wide().chain([0]).collect()
+    }
+
+    pub fn run(dialog: &FileDialog, mode: DialogMode) -> Result<Vec<PathBuf>, DialogError> {
+        unsafe {
+            // fails if COM is already initialized in another mode, which is fine for creating the dialog
+            let initialized = CoInitializeEx(
+                null_mut(),
+                COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE,
+            ) >= 0;
+
+            let paths = show(dialog, mode).ok_or(DialogError::Unavailable);
+
+            if initialized {
+                CoUninitialize();
+            }
+
+            paths
+        }
+    }
+
+    unsafe fn show(dialog: &FileDialog, mode: DialogMode) -> Option<Vec<PathBuf>> {
+        unsafe {
+            let (class, iid) = match mode {
+                DialogMode::Save => (&CLSID_FILE_SAVE_DIALOG, &IID_FILE_SAVE_DIALOG),
+                _ => (&CLSID_FILE_OPEN_DIALOG, &IID_FILE_OPEN_DIALOG),
+            };
+
+            let mut object = null_mut();
+            if CoCreateInstance(class, null_mut(), CLSCTX_INPROC_SERVER, iid, &mut object) < 0 {
+                return None;
+            }
+
+            let file_dialog = vtbl::<FileDialogVtbl>(object);
+
+            let mut options = 0;
+            (file_dialog.get_options)(object, &mut options);
+            options |= FOS_FORCEFILESYSTEM
+                | match mode {
+                    DialogMode::Open => 0,
+                    DialogMode::OpenMultiple => FOS_ALLOWMULTISELECT,
+                    DialogMode::Folder => FOS_PICKFOLDERS,
+                    DialogMode::Save => FOS_OVERWRITEPROMPT,
+                };
+            (file_dialog.set_options)(object, options);
+
+            if let Some(title) = &dialog.title {
+                (file_dialog.set_title)(object, wide(title).as_ptr());
+            }
+
+            if let Some(file_name) = &dialog.file_name {
+                (file_dialog.set_file_name)(object, wide(file_name).as_ptr());
+            }
+
+            if let Some(directory) = &dialog.directory {
+                let mut folder = null_mut();
+                if SHCreateItemFromParsingName(
+                    wide(directory).as_ptr(),
+                    null_mut(),
+                    &IID_SHELL_ITEM,
+                    &mut folder,
+                ) >= 0
+                {
+                    (file_dialog.set_folder)(object, folder);
+                    release(folder);
+                }
+            }
+
+            // the strings must outlive the `SetFileTypes` call
+            let filters = dialog
+                .filters
+                .iter()
+                .map(|(name, extensions)| {
+                    let patterns = extensions
+                        .iter()
+                        .map(|extension| format!("*.{extension}"))
+                        .collect::<Vec<_>>();
+                    (wide(name), wide(patterns.join(";")))
+                })
+                .collect::<Vec<_>>();
+            if mode != DialogMode::Folder && !filters.is_empty() {
+                let specs = filters
+                    .iter()
+                    .map(|(name, spec)| FilterSpec {
+                        name: name.as_ptr(),
+                        spec: spec.as_ptr(),
+                    })
+                    .collect::<Vec<_>>();
+                (file_dialog.set_file_types)(object, specs.len() as u32, specs.as_ptr());
+
+                if mode == DialogMode::Save
+                    && let Some(extension) = dialog.filters[0].1.first()
+                {
+                    (file_dialog.set_default_extension)(object, wide(extension).as_ptr());
+                }
+            }
+
+            let owner = match dialog.parent {
+                Some(parent) => GetAncestor(parent.as_raw() as isize, GA_ROOT),
+                None => 0,
+            };
+
+            // fails with `ERROR_CANCELLED` if the dialog is cancelled
+            let paths = if (file_dialog.show)(object, owner) >= 0 {
+                Some(results(object, file_dialog, mode))
+            } else {
+                Some(Vec::new())
+            };
+
+            release(object);
+            paths
+        }
+    }
+
+    unsafe fn results(
+        object: Object,
+        file_dialog: &FileDialogVtbl,
+        mode: DialogMode,
+    ) -> Vec<PathBuf> {
+        unsafe {
+            if mode != DialogMode::OpenMultiple {
+                let mut item = null_mut();
+                if (file_dialog.get_result)(object, &mut item) < 0 {
+                    return Vec::new();
+                }
+
+                let path = item_path(item);
+                release(item);
+                return path.into_iter().collect();
+            }
+
+            let mut items = null_mut();
+            if (file_dialog.get_results)(object, &mut items) < 0 {
+                return Vec::new();
+            }
+
+            let array = vtbl::<ShellItemArrayVtbl>(items);
+            let mut count = 0;
+            (array.get_count)(items, &mut count);
+
+            let mut paths = Vec::new();
+            for index in 0..count {
+                let mut item = null_mut();
+                if (array.get_item_at)(items, index, &mut item) >= 0 {
+                    paths.extend(item_path(item));
+                    release(item);
+                }
+            }
+
+            release(items);
+            paths
+        }
+    }
+
+    unsafe fn item_path(item: Object) -> Option<PathBuf> {
+        unsafe {
+            let mut name = null_mut();
+            if (vtbl::<ShellItemVtbl>(item).get_display_name)(item, SIGDN_FILESYSPATH, &mut name)
+                < 0
+                || name.is_null()
+            {
+                return None;
+            }
+
+            let len = (0..).take_while(|&i| *name.add(i) != 0).count();
+            let path = OsString::from_wide(std::slice::from_raw_parts(name, len));
+            CoTaskMemFree(name as *mut c_void);
+            Some(PathBuf::from(path))
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{DialogError, DialogMode, FileDialog};
+    use crate::{is_main_thread, objc, with_c_str};
+    use std::path::PathBuf;
+
+    const NS_MODAL_RESPONSE_OK: isize = 1;
+
+    pub fn run(dialog: &FileDialog, mode: DialogMode) -> Result<Vec<PathBuf>, DialogError> {
+        // AppKit panels can only be used on the main thread
+        if !is_main_thread() {
+            return Err(DialogError::NotMainThread);
+        }
+
+        unsafe {
+            let panel = match mode {
+                DialogMode::Save => objc::send_class(c"NSSavePanel", c"savePanel"),
+                _ => objc::send_class(c"NSOpenPanel", c"openPanel"),
+            };
+            if panel.is_null() {
+                return Err(DialogError::Unavailable);
+            }
+
+            if mode != DialogMode::Save {
+                let folder = mode == DialogMode::Folder;
+                objc::send_bool(panel, c"setCanChooseFiles:", !folder);
+                objc::send_bool(panel, c"setCanChooseDirectories:", folder);
+                objc::send_bool(
+                    panel,
+                    c"setAllowsMultipleSelection:",
+                    mode == DialogMode::OpenMultiple,
+                );
+            }
+
+            if let Some(title) = &dialog.title {
+                // the title is not shown by recent versions of MacOS, the message is
+                let title = with_c_str(title, |title| objc::ns_string(title));
+                objc::send_with(panel, c"setTitle:", title);
+                objc::send_with(panel, c"setMessage:", title);
+            }
+
+            if let Some(directory) = &dialog.directory {
+                let directory = with_c_str(&directory.to_string_lossy(), |directory| {
+                    objc::ns_string(directory)
+                });
+                let class = objc::send_class(c"NSURL", c"class");
+                let url = objc::send_with(class, c"fileURLWithPath:", directory);
+                objc::send_with(panel, c"setDirectoryURL:", url);
+            }
+
+            if let Some(file_name) = &dialog.file_name {
+                let file_name = with_c_str(file_name, |file_name| objc::ns_string(file_name));
+                objc::send_with(panel, c"setNameFieldStringValue:", file_name);
+            }
+
+            if mode != DialogMode::Folder && !dialog.filters.is_empty() {
+                let types = objc::send_class(c"NSMutableArray", c"array");
+                for extension in dialog.filters.iter().flat_map(|(_, extensions)| extensions) {
+                    let extension = with_c_str(extension, |extension| objc::ns_string(extension));
+                    objc::send_with(types, c"addObject:", extension);
+                }
+
+                objc::send_with(panel, c"setAllowedFileTypes:", types);
+            }
+
+            if objc::send_integer(panel, c"runModal") != NS_MODAL_RESPONSE_OK {
+                return Ok(Vec::new());
+            }
+
+            let urls = if mode == DialogMode::OpenMultiple {
+                let urls = objc::send(panel, c"URLs");
+                (0..objc::send_integer(urls, c"count").max(0) as usize)
+                    .map(|index| objc::send_index(urls, c"objectAtIndex:", index))
+                    .collect()
+            } else {
+                vec![objc::send(panel, c"URL")]
+            };
+
+            Ok(urls
+                .into_iter()
+                .filter_map(|url| objc::to_string(objc::send(url, c"path")))
+                .map(PathBuf::from)
+                .collect())
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::{DialogError, DialogMode, FileDialog};
+    use std::path::PathBuf;
+
+    pub fn run(_: &FileDialog, _: DialogMode) -> Result<Vec<PathBuf>, DialogError> {
+        Err(DialogError::Unavailable)
+    }
+}
@@ -0,0 +1,353 @@
+//! The display link of [`View::start_display_link`].
+
+use crate::{Backend, PresentationTime, View, sys, view::ViewState};
+use platform::DisplayLink;
+use std::sync::{Mutex, PoisonError};
+
+/// Marks the client events posted by the display link, see [`marker`](crate::view::marker).
+pub(crate) static DISPLAY_LINK_EVENT: u8 = 0;
+
+type DisplayLinkCallback<B> = Box<dyn FnMut(&View<B>, PresentationTime) + Send>;
+
+impl<B: Backend> View<B> {
+    /// Call `callback` on the thread running the event loop at every refresh of the display the view is on, returns `false` if it is not supported.
+    ///
+    /// This paces animations by the vertical blank of the display instead of a timer, which drifts against the refreshes and judders.
+    /// The callback gets the time at which a frame drawn now is shown, and usually calls [`View::obscure_view`].
+    /// Refreshes are skipped while the event loop is busy, so they don't pile up.
+    ///
+    /// Replaces the previous callback. The display link is stopped when the view is unrealized.
+    /// - MacOS: a `CVDisplayLink` that follows the view to other displays. Its thread only posts a client event to the view,
+    ///   so the callback runs during [`World::update`](crate::World::update) like the event handler.
+    /// - Other platforms: not supported, use a [`FramePacer`](crate::FramePacer) instead.
+    /// ```no_run
+    /// # use pugl_rs::*;
+    /// # fn example(view: &View<()>) {
+    /// view.start_display_link(|view, _| view.obscure_view());
+    /// # }
+    /// ```
+    pub fn start_display_link(
+        &self,
+        callback: impl FnMut(&View<B>, PresentationTime) + Send + 'static,
+    ) -> bool {
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        unsafe { state.display_link.start(self.view, Box::new(callback)) }
+    }
+
+    /// Stop the display link started with [`View::start_display_link`].
+    pub fn stop_display_link(&self) {
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        drop(state.display_link.release());
+    }
+}
+
+/// The display link of a view and the callback of [`View::start_display_link`].
+pub(crate) struct DisplayLinkState<B: Backend> {
+    link: Mutex<Option<DisplayLink>>,
+    callback: Mutex<Option<DisplayLinkCallback<B>>>,
+}
+
+impl<B: Backend> DisplayLinkState<B> {
+    pub fn new() -> Self {
+        Self {
+            link: Mutex::new(None),
+            callback: Mutex::new(None),
+        }
+    }
+
+    /// Starts the display link if it isn't running yet and replaces the callback, returns `false` if it is not supported.
+    unsafe fn start(&self, view: *mut sys::PuglView, callback: DisplayLinkCallback<B>) -> bool {
+        let mut link = self.link.lock().unwrap();
+        if link.is_none() {
+            *link = unsafe { DisplayLink::start(view) };
+        }
+
+        if link.is_none() {
+            return false;
+        }
+
+        drop(link);
+        let previous = self.callback.lock().unwrap().replace(callback);
+        drop(previous);
+        true
+    }
+
+    /// Stops the display link and returns the callback, so it can be dropped outside of the locks.
+    ///
+    /// The display link is stopped first, so no more refreshes are posted.
+    pub fn release(&self) -> Option<DisplayLinkCallback<B>> {
+        self.link
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        self.callback
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// Calls the callback of [`View::start_display_link`] for a refresh posted by the display link.
+    ///
+    /// The callback is taken out of its lock while it runs, so it can replace or stop the display link.
+    pub fn refresh(&self, view: &View<B>, output_time: usize) {
+        let Some(time) = self
+            .link
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|link| link.refresh(output_time))
+        else {
+            return;
+        };
+
+        let Some(mut callback) = self.callback.lock().unwrap().take() else {
+            return;
+        };
+
+        callback(view, time);
+
+        let mut slot = self.callback.lock().unwrap();
+        if slot.is_none() && self.link.lock().unwrap().is_some() {
+            *slot = Some(callback);
+        }
+    }
+
+    /// Moves the display link to the display the view is on.
+    pub fn update_display(&self, view: *mut sys::PuglView) {
+        if let Some(link) = self.link.lock().unwrap().as_ref() {
+            unsafe { link.update_display(view) };
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::DISPLAY_LINK_EVENT;
+    use crate::{
+        FramePacer, PresentationTime, objc, sys,
+        view::{marker, send_client_event},
+    };
+    use std::{
+        ffi::c_void,
+        sync::atomic::{AtomicBool, Ordering},
+        time::{Duration, Instant},
+    };
+
+    type CVDisplayLinkRef = *mut c_void;
+    type OutputCallback = unsafe extern "C" fn(
+        CVDisplayLinkRef,
+        *const CVTimeStamp,
+        *const CVTimeStamp,
+        u64,
+        *mut u64,
+        *mut c_void,
+    ) -> i32;
+
+    #[repr(C)]
+    struct CVSMPTETime {
+        subframes: i16,
+        subframe_divisor: i16,
+        counter: u32,
+        type_: u32,
+        flags: u32,
+        hours: i16,
+        minutes: i16,
+        seconds: i16,
+        frames: i16,
+    }
+
+    /// `CVTimeStamp`, only the host time is used.
+    #[repr(C)]
+    struct CVTimeStamp {
+        version: u32,
+        video_time_scale: i32,
+        video_time: i64,
+        host_time: u64,
+        rate_scalar: f64,
+        video_refresh_period: i64,
+        smpte_time: CVSMPTETime,
+        flags: u64,
+        reserved: u64,
+    }
+
+    #[repr(C)]
+    struct TimebaseInfo {
+        numer: u32,
+        denom: u32,
+    }
+
+    #[link(name = "CoreVideo", kind = "framework")]
+    unsafe extern "C" {
+        fn CVDisplayLinkCreateWithActiveCGDisplays(link: *mut CVDisplayLinkRef) -> i32;
+        fn CVDisplayLinkSetOutputCallback(
+            link: CVDisplayLinkRef,
+            callback: OutputCallback,
+            context: *mut c_void,
+        ) -> i32;
+        fn CVDisplayLinkSetCurrentCGDisplay(link: CVDisplayLinkRef, display: u32) -> i32;
+        fn CVDisplayLinkStart(link: CVDisplayLinkRef) -> i32;
+        fn CVDisplayLinkStop(link: CVDisplayLinkRef) -> i32;
+        fn CVDisplayLinkRelease(link: CVDisplayLinkRef);
+        fn CVDisplayLinkGetActualOutputVideoRefreshPeriod(link: CVDisplayLinkRef) -> f64;
+    }
+
+    unsafe extern "C" {
+        fn mach_absolute_time() -> u64;
+        fn mach_timebase_info(info: *mut TimebaseInfo) -> i32;
+    }
+
+    /// The state shared with the thread of the display link.
+    struct Shared {
+        view: *mut sys::PuglView,
+        /// `true` from posting a refresh until the event loop handles it
+        pending: AtomicBool,
+    }
+
+    pub struct DisplayLink {
+        link: CVDisplayLinkRef,
+        shared: Box<Shared>,
+    }
+
+    impl DisplayLink {
+        pub unsafe fn start(view: *mut sys::PuglView) -> Option<Self> {
+            unsafe {
+                let mut link = std::ptr::null_mut();
+                if CVDisplayLinkCreateWithActiveCGDisplays(&mut link) != 0 || link.is_null() {
+                    return None;
+                }
+
+                // released by the drop impl if starting fails
+                let display_link = DisplayLink {
+                    link,
+                    shared: Box::new(Shared {
+                        view,
+                        pending: AtomicBool::new(false),
+                    }),
+                };
+
+                let context = &*display_link.shared as *const Shared as *mut c_void;
+                if CVDisplayLinkSetOutputCallback(link, output, context) != 0 {
+                    return None;
+                }
+
+                display_link.update_display(view);
+                if CVDisplayLinkStart(link) != 0 {
+                    return None;
+                }
+
+                Some(display_link)
+            }
+        }
+
+        /// Moves the display link to the display the window of the view is on.
+        pub unsafe fn update_display(&self, view: *mut sys::PuglView) {
+            unsafe {
+                let window = objc::send(sys::puglGetNativeView(view) as objc::Id, c"window");
+                let screen = objc::send(window, c"screen");
+                let description = objc::send(screen, c"deviceDescription");
+                let key = objc::ns_string(c"NSScreenNumber");
+                let number = objc::send_with(description, c"objectForKey:", key);
+                if !number.is_null() {
+                    let display = objc::send_integer(number, c"unsignedIntValue") as u32;
+                    CVDisplayLinkSetCurrentCGDisplay(self.link, display);
+                }
+            }
+        }
+
+        /// Called on the thread of the event loop for a refresh posted by [`output`], allows posting the next one.
+        pub fn refresh(&self, output_time: usize) -> PresentationTime {
+            self.shared.pending.store(false, Ordering::Release);
+
+            unsafe {
+                let mut timebase = TimebaseInfo { numer: 0, denom: 0 };
+                mach_timebase_info(&mut timebase);
+                let to_duration = |ticks: u64| {
+                    Duration::from_nanos(
+                        (ticks as u128 * timebase.numer as u128 / timebase.denom.max(1) as u128)
+                            as u64,
+                    )
+                };
+
+                // the host time is `mach_absolute_time`, which can't be converted to an `Instant` directly
+                let (now, host_now) = (Instant::now(), mach_absolute_time());
+                let output_time = output_time as u64;
+                let time = match output_time.checked_sub(host_now) {
+                    Some(ahead) => now + to_duration(ahead),
+                    None => now
+                        .checked_sub(to_duration(host_now - output_time))
+                        .unwrap_or(now),
+                };
+
+                let period = CVDisplayLinkGetActualOutputVideoRefreshPeriod(self.link);
+                let refresh_interval = match period {
+                    period if period > 0.0 && period.is_finite() => Duration::from_secs_f64(period),
+                    _ => Duration::from_secs(1) / FramePacer::DEFAULT_RATE,
+                };
+
+                PresentationTime {
+                    time,
+                    refresh_interval,
+                    synchronized: true,
+                }
+            }
+        }
+    }
+
+    impl Drop for DisplayLink {
+        fn drop(&mut self) {
+            // stopping waits for a running callback, so `shared` can be freed afterwards
+            unsafe {
+                CVDisplayLinkStop(self.link);
+                CVDisplayLinkRelease(self.link);
+            }
+        }
+    }
+
+    /// Runs on the thread of the display link, and only posts a client event since the view must be used from the event loop.
+    unsafe extern "C" fn output(
+        _link: CVDisplayLinkRef,
+        _now: *const CVTimeStamp,
+        output_time: *const CVTimeStamp,
+        _flags: u64,
+        _flags_out: *mut u64,
+        context: *mut c_void,
+    ) -> i32 {
+        unsafe {
+            let shared = &*(context as *const Shared);
+
+            // posting events is allowed from other threads, see `-[NSWindow postEvent:atStart:]`
+            if !shared.pending.swap(true, Ordering::AcqRel) {
+                let data = [
+                    marker(&DISPLAY_LINK_EVENT),
+                    (*output_time).host_time as usize,
+                ];
+                if send_client_event(shared.view, data).is_err() {
+                    shared.pending.store(false, Ordering::Release);
+                }
+            }
+
+            0
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use crate::{PresentationTime, sys};
+
+    pub enum DisplayLink {}
+
+    impl DisplayLink {
+        pub unsafe fn start(_: *mut sys::PuglView) -> Option<Self> {
+            None
+        }
+
+        pub unsafe fn update_display(&self, _: *mut sys::PuglView) {
+            match *self {}
+        }
+
+        pub fn refresh(&self, _: usize) -> PresentationTime {
+            match *self {}
+        }
+    }
+}
@@ -0,0 +1,131 @@
+/// How Windows scales the windows of the process on high-DPI displays, see [`set_dpi_awareness`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DpiAwareness {
+    /// The windows are drawn at 96 DPI and bitmap-stretched by the system
+    Unaware,
+    /// The windows are drawn at the DPI of the primary monitor, and bitmap-stretched on other monitors
+    System,
+    /// The windows are drawn at the DPI of their monitor, without scaling the non-client area (the title bar)
+    PerMonitor,
+    /// The windows are drawn at the DPI of their monitor, including the non-client area and the child windows
+    PerMonitorV2,
+}
+
+/// Set the DPI awareness of the process on Windows.
+///
+/// [`World::new_program`](crate::World::new_program) makes the process [`DpiAwareness::PerMonitorV2`], unless the awareness was set before
+/// (by this function or by the manifest of the executable), so applications that want a different awareness should call this before creating the world.
+/// [`World::new_module`](crate::World::new_module) leaves the awareness of the process to the host.
+///
+/// Returns `false` if the awareness was already set (it can only be set once per process), or on other platforms.
+pub fn set_dpi_awareness(awareness: DpiAwareness) -> bool {
+    platform::set_dpi_awareness(awareness)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::DpiAwareness;
+    use std::{ffi::c_void, sync::OnceLock};
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn LoadLibraryA(name: *const i8) -> isize;
+        fn GetProcAddress(module: isize, name: *const i8) -> *mut c_void;
+    }
+
+    type SetProcessDpiAwarenessContext = unsafe extern "system" fn(isize) -> i32;
+    type SetProcessDpiAwareness = unsafe extern "system" fn(i32) -> i32;
+    type GetDpiForWindow = unsafe extern "system" fn(isize) -> u32;
+
+    // DPI_AWARENESS_CONTEXT handles
+    const DPI_AWARENESS_CONTEXT_UNAWARE: isize = -1;
+    const DPI_AWARENESS_CONTEXT_SYSTEM_AWARE: isize = -2;
+    const DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE: isize = -3;
+    const DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2: isize = -4;
+
+    /// The functions added in Windows 8.1 and 10, which are loaded dynamically so older versions still work.
+    struct Functions {
+        set_process_dpi_awareness_context: Option<SetProcessDpiAwarenessContext>,
+        set_process_dpi_awareness: Option<SetProcessDpiAwareness>,
+        get_dpi_for_window: Option<GetDpiForWindow>,
+    }
+
+    fn functions() -> &'static Functions {
+        static FUNCTIONS: OnceLock<Functions> = OnceLock::new();
+        FUNCTIONS.get_or_init(|| unsafe {
+            let user32 = LoadLibraryA(c"user32.dll".as_ptr());
+            let shcore = LoadLibraryA(c"shcore.dll".as_ptr());
+            let load = |module: isize, name: &std::ffi::CStr| match module {
+                0 => None,
+                _ => Some(GetProcAddress(module, name.as_ptr())).filter(|f| !f.is_null()),
+            };
+
+            Functions {
+                set_process_dpi_awareness_context: load(user32, c"SetProcessDpiAwarenessContext")
+                    .map(|f| std::mem::transmute::<*mut c_void, SetProcessDpiAwarenessContext>(f)),
+                set_process_dpi_awareness: load(shcore, c"SetProcessDpiAwareness")
+                    .map(|f| std::mem::transmute::<*mut c_void, SetProcessDpiAwareness>(f)),
+                get_dpi_for_window: load(user32, c"GetDpiForWindow")
+                    .map(|f| std::mem::transmute::<*mut c_void, GetDpiForWindow>(f)),
+            }
+        })
+    }
+
+    pub fn set_dpi_awareness(awareness: DpiAwareness) -> bool {
+        let functions = functions();
+        unsafe {
+            if let Some(set_context) = functions.set_process_dpi_awareness_context {
+                let context = match awareness {
+                    DpiAwareness::Unaware => DPI_AWARENESS_CONTEXT_UNAWARE,
+                    DpiAwareness::System => DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
+                    DpiAwareness::PerMonitor => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+                    DpiAwareness::PerMonitorV2 => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+                };
+
+                if set_context(context) != 0 {
+                    return true;
+                }
+
+                // per-monitor v2 needs Windows 10 1703, older versions fail with an invalid parameter
+                if awareness != DpiAwareness::PerMonitorV2 {
+                    return false;
+                }
+            }
+
+            // PROCESS_DPI_AWARENESS, which has no per-monitor v2
+            match functions.set_process_dpi_awareness {
+                Some(set_awareness) => {
+                    let value = match awareness {
+                        DpiAwareness::Unaware => 0,
+                        DpiAwareness::System => 1,
+                        DpiAwareness::PerMonitor | DpiAwareness::PerMonitorV2 => 2,
+                    };
+
+                    set_awareness(value) >= 0
+                }
+                None => false,
+            }
+        }
+    }
+
+    /// Returns the scale of the monitor of a window, or `None` before Windows 10 1607.
+    pub fn window_scale(hwnd: usize) -> Option<f64> {
+        let get_dpi = functions().get_dpi_for_window?;
+        match unsafe { get_dpi(hwnd as isize) } {
+            0 => None,
+            dpi => Some(dpi as f64 / 96.0),
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::DpiAwareness;
+
+    pub fn set_dpi_awareness(_: DpiAwareness) -> bool {
+        false
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use platform::window_scale;
@@ -0,0 +1,19 @@
+//! Entry points for the fuzz targets in `pugl-rs/fuzz`, not part of the public API.
+
+use crate::{Event, OwnedEvent, sys};
+use std::mem::{MaybeUninit, size_of};
+
+/// Interprets `bytes` as a `PuglEvent` (zero-padded or truncated to its size) and converts it like an event received from `pugl`.
+///
+/// Events that depend on the view (realize, unrealize, expose and clipboard data) are not converted.
+pub fn process_event(bytes: &[u8]) -> Option<OwnedEvent> {
+    let mut raw = MaybeUninit::<sys::PuglEvent>::zeroed();
+    let len = bytes.len().min(size_of::<sys::PuglEvent>());
+
+    let event = unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), raw.as_mut_ptr() as *mut u8, len);
+        raw.assume_init()
+    };
+
+    Event::<()>::from_raw(&event).map(|event| OwnedEvent::from(&event))
+}
@@ -0,0 +1,94 @@
+/// The GPU used for rendering on systems with both an integrated and a discrete GPU, see [`set_gpu_preference`].
+///
+/// Plugin user interfaces generally want the integrated GPU to save battery, while games want the discrete GPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GpuPreference {
+    /// Let the system (or the settings of the user) decide, which is usually the integrated GPU
+    #[default]
+    Default,
+    Integrated,
+    Discrete,
+}
+
+/// Set the GPU used by the OpenGL views realized afterwards.
+///
+/// Returns `false` if the preference can't be set at runtime on this platform:
+/// - X11: sets the `DRI_PRIME` environment variable for Mesa, and if the proprietary NVIDIA driver is loaded, the `__NV_PRIME_RENDER_OFFLOAD`
+///   and `__GLX_VENDOR_LIBRARY_NAME` environment variables for it. Must be called before the first OpenGL view is realized, since the driver is only loaded once.
+/// - Windows: the drivers read exported symbols of the executable instead, see [`prefer_discrete_gpu!`](crate::prefer_discrete_gpu).
+///   Plugins can't choose the GPU, since it is decided by the host.
+/// - MacOS: the discrete GPU is used unless the `NSSupportsAutomaticGraphicsSwitching` key in the `Info.plist` of the application is `true`.
+///
+/// # Safety
+/// Modifies the environment of the process, see [`std::env::set_var`]. Must not be called while other threads read or write the environment.
+pub unsafe fn set_gpu_preference(preference: GpuPreference) -> bool {
+    #[cfg(target_os = "linux")]
+    unsafe {
+        match preference {
+            GpuPreference::Default => {
+                std::env::remove_var("DRI_PRIME");
+                std::env::remove_var("__NV_PRIME_RENDER_OFFLOAD");
+                std::env::remove_var("__GLX_VENDOR_LIBRARY_NAME");
+            }
+            GpuPreference::Integrated => {
+                std::env::set_var("DRI_PRIME", "0");
+                std::env::remove_var("__NV_PRIME_RENDER_OFFLOAD");
+                std::env::remove_var("__GLX_VENDOR_LIBRARY_NAME");
+            }
+            GpuPreference::Discrete => {
+                std::env::set_var("DRI_PRIME", "1");
+
+                // without the NVIDIA driver, libglvnd fails to load the vendor library and GLX stops working
+                if std::path::Path::new("/proc/driver/nvidia/version").exists() {
+                    std::env::set_var("__NV_PRIME_RENDER_OFFLOAD", "1");
+                    std::env::set_var("__GLX_VENDOR_LIBRARY_NAME", "nvidia");
+                }
+            }
+        }
+
+        true
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = preference;
+        false
+    }
+}
+
+/// Asks the NVIDIA Optimus and AMD PowerXpress drivers on Windows to use the discrete GPU, by exporting
+/// `NvOptimusEnablement` and `AmdPowerXpressRequestHighPerformance` from the executable.
+///
+/// Must be invoked once at the top level of the binary crate (the drivers ignore the symbols of DLLs, so it has no effect in plugins).
+/// Expands to nothing on other platforms, see [`set_gpu_preference`].
+///
+/// The symbols are exported with a linker directive on MSVC. With the GNU toolchain, they have to be exported by the build script instead:
+/// `cargo:rustc-link-arg-bins=-Wl,--export-all-symbols`.
+/// ```no_run
+/// pugl_rs::prefer_discrete_gpu!();
+///
+/// fn main() {
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! prefer_discrete_gpu {
+    () => {
+        #[cfg(target_os = "windows")]
+        #[allow(non_upper_case_globals)]
+        #[unsafe(no_mangle)]
+        #[used]
+        pub static NvOptimusEnablement: u32 = 1;
+
+        #[cfg(target_os = "windows")]
+        #[allow(non_upper_case_globals)]
+        #[unsafe(no_mangle)]
+        #[used]
+        pub static AmdPowerXpressRequestHighPerformance: u32 = 1;
+
+        #[cfg(all(target_os = "windows", target_env = "msvc"))]
+        #[unsafe(link_section = ".drectve")]
+        #[used]
+        static PUGL_RS_GPU_EXPORTS: [u8; 84] = *b" /EXPORT:NvOptimusEnablement,DATA /EXPORT:AmdPowerXpressRequestHighPerformance,DATA ";
+    };
+}
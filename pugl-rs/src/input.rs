@@ -0,0 +1,663 @@
+use crate::{Modifiers, ScrollDirection, World};
+use std::time::Duration;
+
+/// The key auto-repeat settings of the user, see [`World::key_repeat`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyRepeat {
+    /// The time a key has to be held before it starts repeating
+    pub delay: Duration,
+    /// The time between two repeats
+    pub interval: Duration,
+}
+
+/// The unit of the deltas of [`Event::Scroll`](crate::Event::Scroll), see [`ScrollConfig`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScrollUnit {
+    /// The deltas as reported by the platform (the default): lines for mouse wheels, and lines or pixels for touchpads depending on the platform
+    #[default]
+    Native,
+    /// Lines, where 1.0 is a single tick of a mouse wheel
+    Lines,
+    /// (Physical) pixels
+    Pixels,
+}
+
+/// How the deltas of [`Event::Scroll`](crate::Event::Scroll) are normalized, see [`UnrealizedView::with_scroll_config`](crate::UnrealizedView::with_scroll_config).
+///
+/// Mouse wheels report lines on every platform, while touchpads report pixels on MacOS and fractional lines elsewhere.
+/// Normalizing the deltas to a single unit makes scrollable views behave the same on every platform and device.
+///
+/// Regardless of the configuration, the direction of discrete scroll events always matches the sign of their deltas on every platform:
+/// [`ScrollDirection::Up`] has a positive `dy`, [`ScrollDirection::Down`] a negative `dy`,
+/// [`ScrollDirection::Left`] a negative `dx` and [`ScrollDirection::Right`] a positive `dx` (for example from tilting the wheel).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollConfig {
+    /// The unit the deltas are converted to, [`ScrollUnit::Native`] (the default) leaves them unchanged
+    pub unit: ScrollUnit,
+    /// The height of a line in (physical) pixels, for converting between lines and pixels
+    pub line_height: f64,
+    /// Turn vertical scrolling with <kbd>Shift</kbd> held into horizontal scrolling, like most applications on Windows and Linux do
+    /// (scrolling down scrolls to the right). The [`Modifiers::SHIFT`] modifier is still reported.
+    ///
+    /// MacOS already does this for mouse wheels, so only events without horizontal deltas are turned. Disabled by default.
+    pub shift_to_horizontal: bool,
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        ScrollConfig {
+            unit: ScrollUnit::Native,
+            line_height: 16.0,
+            shift_to_horizontal: false,
+        }
+    }
+}
+
+impl ScrollConfig {
+    /// Applies the configuration to a scroll event, `scale` is the scale factor of the view.
+    pub(crate) fn normalize(
+        &self,
+        direction: ScrollDirection,
+        dx: f64,
+        dy: f64,
+        mods: Modifiers,
+        scale: f64,
+    ) -> (ScrollDirection, f64, f64) {
+        let (direction, mut dx, mut dy) = consistent_direction(direction, dx, dy);
+
+        // precise deltas are in points on MacOS, everything else is in lines
+        let pixels = cfg!(target_os = "macos") && direction == ScrollDirection::Smooth;
+        let line_height = self.line_height.max(1.0);
+
+        match (self.unit, pixels) {
+            (ScrollUnit::Native, _) | (ScrollUnit::Lines, false) => {}
+            (ScrollUnit::Lines, true) => {
+                (dx, dy) = (dx * scale / line_height, dy * scale / line_height);
+            }
+            (ScrollUnit::Pixels, false) => (dx, dy) = (dx * line_height, dy * line_height),
+            (ScrollUnit::Pixels, true) => (dx, dy) = (dx * scale, dy * scale),
+        }
+
+        if self.shift_to_horizontal && mods.contains(Modifiers::SHIFT) && dx == 0.0 && dy != 0.0 {
+            let direction = match direction {
+                ScrollDirection::Up => ScrollDirection::Left,
+                ScrollDirection::Down => ScrollDirection::Right,
+                direction => direction,
+            };
+
+            return (direction, -dy, 0.0);
+        }
+
+        (direction, dx, dy)
+    }
+}
+
+/// Makes the direction of discrete scroll events match their deltas, since the platforms disagree on the deltas of tilted wheels
+/// (some report only the direction, some only the deltas).
+fn consistent_direction(
+    direction: ScrollDirection,
+    dx: f64,
+    dy: f64,
+) -> (ScrollDirection, f64, f64) {
+    if direction == ScrollDirection::Smooth {
+        return (direction, dx, dy);
+    }
+
+    if dx == 0.0 && dy == 0.0 {
+        return match direction {
+            ScrollDirection::Up => (direction, 0.0, 1.0),
+            ScrollDirection::Down => (direction, 0.0, -1.0),
+            ScrollDirection::Left => (direction, -1.0, 0.0),
+            _ => (direction, 1.0, 0.0),
+        };
+    }
+
+    if dy == 0.0 {
+        let direction = if dx < 0.0 {
+            ScrollDirection::Left
+        } else {
+            ScrollDirection::Right
+        };
+        (direction, dx, dy)
+    } else {
+        let direction = if dy > 0.0 {
+            ScrollDirection::Up
+        } else {
+            ScrollDirection::Down
+        };
+        (direction, dx, dy)
+    }
+}
+
+impl World {
+    /// Returns the key auto-repeat settings of the user, or `None` if they are not known.
+    ///
+    /// Applications that implement their own repeat (for example with [`UnrealizedView::with_ignore_key_repeats`](crate::UnrealizedView::with_ignore_key_repeats))
+    /// can use this to match the settings of the system.
+    /// - Windows: the keyboard delay and speed settings (`SPI_GETKEYBOARDDELAY` and `SPI_GETKEYBOARDSPEED`).
+    /// - MacOS: `NSEvent.keyRepeatDelay` and `NSEvent.keyRepeatInterval`.
+    /// - X11: the repeat rate of the core keyboard (`XkbGetAutoRepeatRate`).
+    pub fn key_repeat(&self) -> Option<KeyRepeat> {
+        platform::key_repeat(self).filter(|repeat| !repeat.interval.is_zero())
+    }
+
+    /// Returns the time since the last input of the user in any application, or `None` if it is not known.
+    ///
+    /// - Windows: `GetLastInputInfo`.
+    /// - MacOS: `CGEventSourceSecondsSinceLastEventType` of the login session.
+    /// - X11: always `None`, since it needs the `XScreenSaver` extension which is not linked. Use [`World::user_idle_time`] instead.
+    pub fn system_idle_time(&self) -> Option<Duration> {
+        platform::system_idle_time()
+    }
+}
+
+/// Returns the accent of a dead key, see [`Event::DeadKey`](crate::Event::DeadKey).
+pub(crate) fn dead_key(world: &World, keycode: u32, mods: Modifiers) -> Option<char> {
+    platform::dead_key(world, keycode, mods)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use platform::{unwatch_keys, watch_keys};
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::KeyRepeat;
+    use crate::{
+        Modifiers, World,
+        x11::{
+            event_hook,
+            xlib::{
+                Display, KEY_PRESS, XKB_USE_CORE_KBD, XKeyEvent, XkbGetAutoRepeatRate,
+                XkbKeycodeToKeysym,
+            },
+        },
+    };
+    use std::{
+        os::raw::{c_int, c_uchar, c_void},
+        sync::atomic::{AtomicU16, Ordering},
+        time::Duration,
+    };
+
+    /// The state of the last key press of every keycode, recorded by [`record_key_state`],
+    /// since the state that `pugl` reports doesn't include the keyboard group (the active layout).
+    static KEY_STATES: [AtomicU16; 256] = [const { AtomicU16::new(0) }; 256];
+
+    fn record_key_state(_: Display, event: *const c_void) {
+        let event = unsafe { &*(event as *const XKeyEvent) };
+        if let Some(state) = KEY_STATES.get(event.keycode as usize) {
+            state.store(event.state as u16, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the state of the key presses of the world for [`dead_key`], until [`unwatch_keys`] is called.
+    pub unsafe fn watch_keys(display: Display) {
+        unsafe { event_hook::add(display, KEY_PRESS, record_key_state) }
+    }
+
+    pub unsafe fn unwatch_keys(display: Display) {
+        unsafe { event_hook::remove(display, KEY_PRESS, record_key_state) }
+    }
+
+    pub fn dead_key(world: &World, keycode: u32, mods: Modifiers) -> Option<char> {
+        let display = world.native().as_raw();
+        let keycode = c_uchar::try_from(keycode).ok()?;
+        if display.is_null() {
+            return None;
+        }
+
+        let keysym = unsafe {
+            // the keysym depends on the layout that was active when the key was pressed, which is in the XKB group bits of the state
+            let state = KEY_STATES[keycode as usize].load(Ordering::Relaxed);
+            let group = ((state >> 13) & 0x3) as c_int;
+            let level = mods.contains(Modifiers::SHIFT) as c_int;
+            XkbKeycodeToKeysym(display, keycode, group, level)
+        };
+
+        // the `XK_dead_*` keysyms
+        Some(match keysym {
+            0xfe50 => '`',
+            0xfe51 => '´',
+            0xfe52 => '^',
+            0xfe53 => '~',
+            0xfe54 => '¯',
+            0xfe55 => '˘',
+            0xfe56 => '˙',
+            0xfe57 => '¨',
+            0xfe58 => '˚',
+            0xfe59 => '˝',
+            0xfe5a => 'ˇ',
+            0xfe5b => '¸',
+            0xfe5c => '˛',
+            _ => return None,
+        })
+    }
+
+    pub fn system_idle_time() -> Option<Duration> {
+        None
+    }
+
+    pub fn key_repeat(world: &World) -> Option<KeyRepeat> {
+        let display = world.native().as_raw();
+        if display.is_null() {
+            return None;
+        }
+
+        let (mut delay, mut interval) = (0, 0);
+        match unsafe { XkbGetAutoRepeatRate(display, XKB_USE_CORE_KBD, &mut delay, &mut interval) }
+        {
+            0 => None,
+            _ => Some(KeyRepeat {
+                delay: Duration::from_millis(delay as u64),
+                interval: Duration::from_millis(interval as u64),
+            }),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::KeyRepeat;
+    use crate::{Modifiers, World};
+    use std::{ffi::c_void, time::Duration};
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn SystemParametersInfoW(action: u32, param: u32, value: *mut c_void, ini: u32) -> i32;
+        fn GetLastInputInfo(info: *mut LastInputInfo) -> i32;
+        fn MapVirtualKeyW(code: u32, map_type: u32) -> u32;
+        fn GetKeyboardLayout(thread: u32) -> isize;
+        fn ToUnicodeEx(
+            vk: u32,
+            scan_code: u32,
+            key_state: *const u8,
+            buffer: *mut u16,
+            len: i32,
+            flags: u32,
+            layout: isize,
+        ) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    #[repr(C)]
+    struct LastInputInfo {
+        size: u32,
+        time: u32,
+    }
+
+    const MAPVK_VSC_TO_VK_EX: u32 = 3;
+    const VK_SHIFT: usize = 0x10;
+    const VK_CONTROL: usize = 0x11;
+    const VK_MENU: usize = 0x12;
+    /// Keeps the dead key state of the keyboard for the key that follows (Windows 10 1607 and later)
+    const TOUNICODE_NO_STATE_CHANGE: u32 = 0x4;
+
+    /// The keycode of `pugl` is the scan code.
+    pub fn dead_key(_: &World, keycode: u32, mods: Modifiers) -> Option<char> {
+        unsafe {
+            let vk = MapVirtualKeyW(keycode, MAPVK_VSC_TO_VK_EX);
+            if vk == 0 {
+                return None;
+            }
+
+            // the accent can depend on the modifiers, like the grave accent on `Shift` and the acute accent key of German layouts
+            let mut state = [0u8; 256];
+            for (modifier, vk) in [
+                (Modifiers::SHIFT, VK_SHIFT),
+                (Modifiers::CTRL, VK_CONTROL),
+                (Modifiers::ALT, VK_MENU),
+            ] {
+                if mods.contains(modifier) {
+                    state[vk] = 0x80;
+                }
+            }
+
+            let mut text = [0u16; 4];
+            let len = ToUnicodeEx(
+                vk,
+                keycode,
+                state.as_ptr(),
+                text.as_mut_ptr(),
+                text.len() as i32,
+                TOUNICODE_NO_STATE_CHANGE,
+                GetKeyboardLayout(0),
+            );
+
+            // dead keys return -1 and write their spacing accent
+            if len != -1 {
+                return None;
+            }
+
+            char::from_u32(text[0] as u32)
+        }
+    }
+
+    const SPI_GETKEYBOARDSPEED: u32 = 0x000A;
+    const SPI_GETKEYBOARDDELAY: u32 = 0x0016;
+
+    fn get(action: u32) -> Option<u32> {
+        let mut value = 0u32;
+        let result =
+            unsafe { SystemParametersInfoW(action, 0, &mut value as *mut u32 as *mut c_void, 0) };
+
+        (result != 0).then_some(value)
+    }
+
+    pub fn key_repeat(_: &World) -> Option<KeyRepeat> {
+        // the delay is 0 (250ms) to 3 (1s), the speed is 0 (about 2.5 repeats per second) to 31 (about 30 repeats per second)
+        let delay = get(SPI_GETKEYBOARDDELAY)?.min(3);
+        let speed = get(SPI_GETKEYBOARDSPEED)?.min(31);
+        let rate = 2.5 + speed as f64 * (27.5 / 31.0);
+
+        Some(KeyRepeat {
+            delay: Duration::from_millis(250 * (delay as u64 + 1)),
+            interval: Duration::from_secs_f64(1.0 / rate),
+        })
+    }
+
+    pub fn system_idle_time() -> Option<Duration> {
+        let mut info = LastInputInfo {
+            size: size_of::<LastInputInfo>() as u32,
+            time: 0,
+        };
+
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+
+        // the tick count wraps around after 49 days
+        let idle = unsafe { GetTickCount() }.wrapping_sub(info.time);
+        Some(Duration::from_millis(idle as u64))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::KeyRepeat;
+    use crate::{Modifiers, World, objc};
+    use std::{ffi::c_void, time::Duration};
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    unsafe extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFDataGetBytePtr(data: *const c_void) -> *const u8;
+        fn CFRelease(object: *const c_void);
+    }
+
+    #[link(name = "Carbon", kind = "framework")]
+    unsafe extern "C" {
+        static kTISPropertyUnicodeKeyLayoutData: *const c_void;
+        fn TISCopyCurrentKeyboardLayoutInputSource() -> *const c_void;
+        fn TISGetInputSourceProperty(source: *const c_void, key: *const c_void) -> *const c_void;
+        fn LMGetKbdType() -> u8;
+        fn UCKeyTranslate(
+            layout: *const c_void,
+            key_code: u16,
+            key_action: u16,
+            modifier_key_state: u32,
+            keyboard_type: u32,
+            options: u32,
+            dead_key_state: *mut u32,
+            max_length: usize,
+            actual_length: *mut usize,
+            unicode: *mut u16,
+        ) -> i32;
+    }
+
+    const UC_KEY_ACTION_DOWN: u16 = 0;
+    const VK_SPACE: u16 = 0x31;
+    /// `shiftKey >> 8`
+    const SHIFT_KEY_STATE: u32 = 0x02;
+
+    /// The keycode of `pugl` is the virtual keycode of the event.
+    pub fn dead_key(_: &World, keycode: u32, mods: Modifiers) -> Option<char> {
+        unsafe {
+            let source = TISCopyCurrentKeyboardLayoutInputSource();
+            if source.is_null() {
+                return None;
+            }
+
+            let data = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData);
+            let accent = if data.is_null() {
+                None
+            } else {
+                let layout = CFDataGetBytePtr(data) as *const c_void;
+                let modifiers = if mods.contains(Modifiers::SHIFT) {
+                    SHIFT_KEY_STATE
+                } else {
+                    0
+                };
+
+                let mut dead_key_state = 0;
+                let mut text = [0u16; 4];
+                let mut len = 0;
+                let mut translate = |key_code, modifiers, dead_key_state: &mut u32| {
+                    UCKeyTranslate(
+                        layout,
+                        key_code,
+                        UC_KEY_ACTION_DOWN,
+                        modifiers,
+                        LMGetKbdType() as u32,
+                        0,
+                        dead_key_state,
+                        text.len(),
+                        &mut len,
+                        text.as_mut_ptr(),
+                    )
+                };
+
+                translate(keycode as u16, modifiers, &mut dead_key_state);
+                if dead_key_state == 0 {
+                    None
+                } else {
+                    // a dead key followed by space produces the accent on its own
+                    translate(VK_SPACE, 0, &mut dead_key_state);
+                    char::decode_utf16(text[..len.min(text.len())].iter().copied())
+                        .next()
+                        .and_then(Result::ok)
+                }
+            };
+
+            CFRelease(source);
+            accent
+        }
+    }
+
+    const CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE: i32 = 0;
+    const CG_ANY_INPUT_EVENT_TYPE: u32 = !0;
+
+    pub fn system_idle_time() -> Option<Duration> {
+        let seconds = unsafe {
+            CGEventSourceSecondsSinceLastEventType(
+                CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE,
+                CG_ANY_INPUT_EVENT_TYPE,
+            )
+        };
+
+        Duration::try_from_secs_f64(seconds).ok()
+    }
+
+    pub fn key_repeat(_: &World) -> Option<KeyRepeat> {
+        unsafe {
+            let delay = objc::send_class_double(c"NSEvent", c"keyRepeatDelay")?;
+            let interval = objc::send_class_double(c"NSEvent", c"keyRepeatInterval")?;
+
+            Some(KeyRepeat {
+                delay: Duration::try_from_secs_f64(delay).ok()?,
+                interval: Duration::try_from_secs_f64(interval).ok()?,
+            })
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::KeyRepeat;
+    use crate::{Modifiers, World};
+
+    pub fn key_repeat(_: &World) -> Option<KeyRepeat> {
+        None
+    }
+
+    pub fn dead_key(_: &World, _: u32, _: Modifiers) -> Option<char> {
+        None
+    }
+
+    pub fn system_idle_time() -> Option<std::time::Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(
+        config: ScrollConfig,
+        direction: ScrollDirection,
+        dx: f64,
+        dy: f64,
+        mods: Modifiers,
+    ) -> (ScrollDirection, f64, f64) {
+        config.normalize(direction, dx, dy, mods, 2.0)
+    }
+
+    #[test]
+    fn direction_only_gets_unit_deltas() {
+        use ScrollDirection::*;
+
+        assert_eq!(consistent_direction(Up, 0.0, 0.0), (Up, 0.0, 1.0));
+        assert_eq!(consistent_direction(Down, 0.0, 0.0), (Down, 0.0, -1.0));
+        assert_eq!(consistent_direction(Left, 0.0, 0.0), (Left, -1.0, 0.0));
+        assert_eq!(consistent_direction(Right, 0.0, 0.0), (Right, 1.0, 0.0));
+    }
+
+    #[test]
+    fn direction_follows_the_sign_of_the_deltas() {
+        use ScrollDirection::*;
+
+        // a tilted wheel reported as a vertical direction with horizontal deltas
+        assert_eq!(consistent_direction(Up, -1.0, 0.0), (Left, -1.0, 0.0));
+        assert_eq!(consistent_direction(Up, 1.0, 0.0), (Right, 1.0, 0.0));
+        assert_eq!(consistent_direction(Left, 0.0, 2.0), (Up, 0.0, 2.0));
+        assert_eq!(consistent_direction(Right, 0.0, -2.0), (Down, 0.0, -2.0));
+
+        // the vertical delta wins for diagonal deltas
+        assert_eq!(consistent_direction(Right, 1.0, -1.0), (Down, 1.0, -1.0));
+    }
+
+    #[test]
+    fn smooth_scrolling_is_unchanged() {
+        use ScrollDirection::*;
+
+        assert_eq!(consistent_direction(Smooth, 0.0, 0.0), (Smooth, 0.0, 0.0));
+        assert_eq!(
+            consistent_direction(Smooth, -0.5, 0.25),
+            (Smooth, -0.5, 0.25)
+        );
+    }
+
+    #[test]
+    fn native_unit_keeps_the_deltas() {
+        let config = ScrollConfig::default();
+        assert_eq!(
+            normalize(config, ScrollDirection::Down, 0.0, -3.0, Modifiers::empty()),
+            (ScrollDirection::Down, 0.0, -3.0)
+        );
+    }
+
+    #[test]
+    fn wheel_lines_are_converted_to_pixels() {
+        let config = ScrollConfig {
+            unit: ScrollUnit::Pixels,
+            line_height: 20.0,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            normalize(config, ScrollDirection::Up, 0.0, 0.0, Modifiers::empty()),
+            (ScrollDirection::Up, 0.0, 20.0)
+        );
+        assert_eq!(
+            normalize(config, ScrollDirection::Up, 0.0, 0.5, Modifiers::empty()),
+            (ScrollDirection::Up, 0.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn smooth_deltas_are_converted() {
+        let config = ScrollConfig {
+            unit: ScrollUnit::Lines,
+            line_height: 16.0,
+            ..Default::default()
+        };
+
+        // precise deltas are in points on MacOS, and in lines elsewhere
+        let expected = if cfg!(target_os = "macos") { 0.25 } else { 2.0 };
+        assert_eq!(
+            normalize(
+                config,
+                ScrollDirection::Smooth,
+                0.0,
+                2.0,
+                Modifiers::empty()
+            ),
+            (ScrollDirection::Smooth, 0.0, expected)
+        );
+    }
+
+    #[test]
+    fn shift_turns_vertical_into_horizontal_scrolling() {
+        let config = ScrollConfig {
+            shift_to_horizontal: true,
+            ..Default::default()
+        };
+
+        // scrolling down scrolls to the right
+        assert_eq!(
+            normalize(config, ScrollDirection::Down, 0.0, -1.0, Modifiers::SHIFT),
+            (ScrollDirection::Right, 1.0, 0.0)
+        );
+        assert_eq!(
+            normalize(config, ScrollDirection::Up, 0.0, 1.0, Modifiers::SHIFT),
+            (ScrollDirection::Left, -1.0, 0.0)
+        );
+        assert_eq!(
+            normalize(config, ScrollDirection::Smooth, 0.0, 0.5, Modifiers::SHIFT),
+            (ScrollDirection::Smooth, -0.5, 0.0)
+        );
+    }
+
+    #[test]
+    fn shift_keeps_horizontal_scrolling() {
+        let config = ScrollConfig {
+            shift_to_horizontal: true,
+            ..Default::default()
+        };
+
+        // already turned by the system (MacOS), or a tilted wheel
+        assert_eq!(
+            normalize(config, ScrollDirection::Right, 1.0, 0.0, Modifiers::SHIFT),
+            (ScrollDirection::Right, 1.0, 0.0)
+        );
+
+        // disabled
+        assert_eq!(
+            normalize(
+                ScrollConfig::default(),
+                ScrollDirection::Down,
+                0.0,
+                -1.0,
+                Modifiers::SHIFT
+            ),
+            (ScrollDirection::Down, 0.0, -1.0)
+        );
+    }
+}
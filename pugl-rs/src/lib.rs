@@ -1,16 +1,79 @@
 #![doc = include_str!("../../README.md")]
 
+mod alert;
+mod appearance;
+mod attributes;
 mod backend;
+mod coordinates;
+mod damage;
 mod data;
+#[cfg(feature = "file-dialog")]
+mod dialog;
+mod display_link;
+mod dpi;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+mod gpu;
+mod input;
+mod locale;
+#[cfg(target_os = "macos")]
+mod objc;
+mod pacing;
+mod paste;
+mod plugin;
+#[cfg(all(target_os = "linux", feature = "file-dialog"))]
+mod portal;
+mod record;
+mod shortcut;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(target_os = "windows")]
+mod subclass;
+mod template;
+mod timer;
 mod view;
 mod world;
+#[cfg(target_os = "linux")]
+mod x11;
+#[cfg(target_os = "linux")]
+mod xembed;
 
-use pugl_rs_sys as sys;
+/// Raw `pugl` bindings, see [`View::as_pugl_ptr`].
+pub use pugl_rs_sys as sys;
 
+pub use alert::*;
+pub use appearance::*;
+pub use attributes::*;
 pub use backend::*;
+pub(crate) use damage::*;
 pub use data::*;
+#[cfg(feature = "file-dialog")]
+pub use dialog::*;
+pub use dpi::*;
+pub use gpu::*;
+pub use input::*;
+pub use locale::*;
+pub use pacing::*;
+pub use paste::*;
+pub use plugin::*;
+pub use record::*;
+pub use shortcut::*;
+#[cfg(feature = "async")]
+pub use stream::*;
+pub use template::*;
 pub use view::*;
 pub use world::*;
+#[cfg(target_os = "linux")]
+pub use x11::*;
+
+/// Returns the version and the commit of the vendored `pugl` library, for example `("0.5.5", "66afe80...")`.
+///
+/// Useful for bug reports, since the bindings are tied to the exact `pugl` sources they are built with.
+/// The commit is `"unknown"` if the crate was built without the git metadata of the submodule (like from crates.io).
+pub fn pugl_version() -> (&'static str, &'static str) {
+    (sys::PUGL_VERSION, sys::PUGL_COMMIT)
+}
 
 pub(crate) mod private {
     pub struct Private;
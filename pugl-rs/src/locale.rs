@@ -0,0 +1,109 @@
+/// The direction in which text of a language is written, see [`system_text_direction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Languages that are written from right to left, by their ISO 639 code.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+
+impl TextDirection {
+    /// Returns the direction of the language of a locale like `"ar-EG"` or `"en_US.UTF-8"`.
+    pub fn of_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if RTL_LANGUAGES.contains(&language.as_str()) {
+            TextDirection::RightToLeft
+        } else {
+            TextDirection::LeftToRight
+        }
+    }
+}
+
+/// Returns the locale of the user interface as a BCP 47 language tag, like `"en-US"`, or `None` if it is not known.
+///
+/// - Windows: the locale of the user (`GetUserDefaultLocaleName`).
+/// - MacOS: the current locale (`NSLocale.currentLocale`).
+/// - X11: the `LC_ALL`, `LC_MESSAGES` or `LANG` environment variable, the `C` and `POSIX` locales are reported as `None`.
+pub fn system_locale() -> Option<String> {
+    platform::locale()
+        .map(|locale| normalize_locale(&locale))
+        .filter(|locale| !locale.is_empty())
+}
+
+/// Returns the text direction of the language of the user interface, see [`system_locale`] and [`TextDirection::of_locale`].
+///
+/// Layouts should be mirrored for [`TextDirection::RightToLeft`].
+/// Defaults to [`TextDirection::LeftToRight`] if the locale is not known.
+pub fn system_text_direction() -> TextDirection {
+    system_locale()
+        .map(|locale| TextDirection::of_locale(&locale))
+        .unwrap_or(TextDirection::LeftToRight)
+}
+
+/// Turns a POSIX locale like `en_US.UTF-8@euro` into a language tag like `en-US`.
+fn normalize_locale(locale: &str) -> String {
+    locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-")
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub fn locale() -> Option<String> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|locale| !locale.is_empty())
+            .filter(|locale| locale != "C" && locale != "POSIX" && !locale.starts_with("C."))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+    }
+
+    pub fn locale() -> Option<String> {
+        let mut name = [0u16; LOCALE_NAME_MAX_LENGTH];
+        let len = unsafe { GetUserDefaultLocaleName(name.as_mut_ptr(), name.len() as i32) };
+
+        // the length includes the nul terminator, zero on failure
+        match len {
+            1.. => String::from_utf16(&name[..len as usize - 1]).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::objc;
+
+    pub fn locale() -> Option<String> {
+        unsafe {
+            let locale = objc::send_class(c"NSLocale", c"currentLocale");
+            objc::to_string(objc::send(locale, c"localeIdentifier"))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod platform {
+    pub fn locale() -> Option<String> {
+        None
+    }
+}
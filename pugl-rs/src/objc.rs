@@ -0,0 +1,187 @@
+//! Minimal Objective-C runtime calls for the few Cocoa settings that `pugl` doesn't expose.
+
+use std::{
+    ffi::CStr,
+    mem::transmute,
+    os::raw::{c_char, c_void},
+};
+
+#[link(name = "objc")]
+unsafe extern "C" {
+    fn objc_getClass(name: *const c_char) -> *mut c_void;
+    fn sel_registerName(name: *const c_char) -> *mut c_void;
+    fn objc_msgSend();
+}
+
+pub type Id = *mut c_void;
+
+/// Sends a message without arguments to a class, like `[NSLocale currentLocale]`.
+pub unsafe fn send_class(class: &CStr, selector: &CStr) -> Id {
+    unsafe { send(objc_getClass(class.as_ptr()), selector) }
+}
+
+/// Sends a message without arguments, like `[locale localeIdentifier]`.
+pub unsafe fn send(receiver: Id, selector: &CStr) -> Id {
+    unsafe {
+        if receiver.is_null() {
+            return receiver;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void) -> Id =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(receiver, sel_registerName(selector.as_ptr()))
+    }
+}
+
+/// Sends a message with one pointer argument, like `[defaults stringForKey:key]`.
+pub unsafe fn send_with(receiver: Id, selector: &CStr, argument: *const c_void) -> Id {
+    unsafe {
+        if receiver.is_null() {
+            return receiver;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void, *const c_void) -> Id =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(receiver, sel_registerName(selector.as_ptr()), argument)
+    }
+}
+
+/// Sends a message with four pointer arguments, like `[center addObserverForName:name object:nil queue:nil usingBlock:block]`.
+pub unsafe fn send_with_4(receiver: Id, selector: &CStr, arguments: [*const c_void; 4]) -> Id {
+    unsafe {
+        if receiver.is_null() {
+            return receiver;
+        }
+
+        let send: unsafe extern "C" fn(
+            Id,
+            *mut c_void,
+            *const c_void,
+            *const c_void,
+            *const c_void,
+            *const c_void,
+        ) -> Id = transmute(objc_msgSend as unsafe extern "C" fn());
+        let [a, b, c, d] = arguments;
+        send(receiver, sel_registerName(selector.as_ptr()), a, b, c, d)
+    }
+}
+
+/// Creates an autoreleased `NSString`.
+pub unsafe fn ns_string(string: &CStr) -> Id {
+    unsafe {
+        send_with(
+            objc_getClass(c"NSString".as_ptr()),
+            c"stringWithUTF8String:",
+            string.as_ptr() as *const c_void,
+        )
+    }
+}
+
+/// Copies the contents of an `NSString`, or returns `None` if it is `nil`.
+pub unsafe fn to_string(string: Id) -> Option<String> {
+    unsafe {
+        let utf8 = send(string, c"UTF8String") as *const c_char;
+        if utf8.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// Sends a message with one `BOOL` argument, like `[panel setCanChooseFiles:YES]`.
+pub unsafe fn send_bool(receiver: Id, selector: &CStr, argument: bool) {
+    unsafe {
+        if receiver.is_null() {
+            return;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void, i8) =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(
+            receiver,
+            sel_registerName(selector.as_ptr()),
+            argument as i8,
+        )
+    }
+}
+
+/// Sends a message that returns an integer, like `[panel runModal]` or `[array count]`.
+pub unsafe fn send_integer(receiver: Id, selector: &CStr) -> isize {
+    unsafe {
+        if receiver.is_null() {
+            return 0;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void) -> isize =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(receiver, sel_registerName(selector.as_ptr()))
+    }
+}
+
+/// Sends a message with one integer argument, like `[array objectAtIndex:index]`.
+pub unsafe fn send_index(receiver: Id, selector: &CStr, index: usize) -> Id {
+    unsafe {
+        if receiver.is_null() {
+            return receiver;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void, usize) -> Id =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(receiver, sel_registerName(selector.as_ptr()), index)
+    }
+}
+
+/// Sends a message that returns a `double` to a class, like `[NSEvent keyRepeatDelay]`.
+pub unsafe fn send_class_double(class: &CStr, selector: &CStr) -> Option<f64> {
+    unsafe {
+        let class = objc_getClass(class.as_ptr());
+        if class.is_null() {
+            return None;
+        }
+
+        // `objc_msgSend_fpret` is only needed for `long double` on x86_64
+        let send: unsafe extern "C" fn(Id, *mut c_void) -> f64 =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        Some(send(class, sel_registerName(selector.as_ptr())))
+    }
+}
+
+/// An `NSPoint`, which is returned in registers on every supported architecture.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Sends a message with one point argument that returns a point, like `[window convertPointToScreen:point]`.
+pub unsafe fn send_point(receiver: Id, selector: &CStr, point: Point) -> Point {
+    unsafe {
+        if receiver.is_null() {
+            return point;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void, Point) -> Point =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(receiver, sel_registerName(selector.as_ptr()), point)
+    }
+}
+
+/// Sends a message with a point and a pointer argument that returns a point, like `[view convertPoint:point toView:nil]`.
+pub unsafe fn send_point_with(receiver: Id, selector: &CStr, point: Point, argument: Id) -> Point {
+    unsafe {
+        if receiver.is_null() {
+            return point;
+        }
+
+        let send: unsafe extern "C" fn(Id, *mut c_void, Point, Id) -> Point =
+            transmute(objc_msgSend as unsafe extern "C" fn());
+        send(
+            receiver,
+            sel_registerName(selector.as_ptr()),
+            point,
+            argument,
+        )
+    }
+}
@@ -0,0 +1,439 @@
+use crate::{Backend, ControlFlow, View};
+use std::time::{Duration, Instant};
+
+/// Paces the frames of a continuously animating view at the refresh rate of its display.
+///
+/// Frames are scheduled on a fixed grid (`vblank + n * interval`) instead of "now + interval",
+/// so the schedule doesn't drift when an iteration of the main loop is late, and missed frames are skipped instead of being drawn in a burst.
+/// ```no_run
+/// # use pugl_rs::*;
+/// # let mut world = World::new_program().unwrap();
+/// # let view = world.new_view(()).realize().unwrap();
+/// let mut pacer = FramePacer::new(&view);
+/// world
+///     .run(|_| {
+///         if pacer.poll_frame() {
+///             view.obscure_view();
+///         }
+///         pacer.control_flow()
+///     })
+///     .unwrap();
+/// ```
+/// The grid is aligned with the vertical blanks of the display where the system reports them:
+/// - Windows: the pacer synchronizes itself with the compositor (`DwmGetCompositionTimingInfo`) on every [`FramePacer::poll_frame`].
+/// - X11: the vertical blanks are only available while the OpenGL context is current, so call [`FramePacer::synchronize`]
+///   with [`View::presentation_time`] at the end of the [`Event::Expose`](crate::Event::Expose) handler.
+/// - MacOS: call [`FramePacer::synchronize`] from the callback of [`View::start_display_link`],
+///   or use the display link to drive the frames instead of a pacer.
+///
+/// Otherwise, the grid starts when the pacer is created.
+/// The pacer only decides when to request the next frame, so to avoid tearing, vertical synchronization should be enabled
+/// (like `OpenGl::swap_interval` with the `opengl` feature). See [`View::presentation_time`] for when a frame is shown.
+#[derive(Clone, Debug)]
+pub struct FramePacer {
+    /// A point of the grid, the last known vertical blank if the pacer is synchronized
+    anchor: Instant,
+    interval: Duration,
+    /// The point of the grid of the last frame
+    last: Instant,
+    frame: u64,
+    /// `true` if the pacer synchronizes itself with the display, see [`FramePacer::new`]
+    follow_display: bool,
+}
+
+impl FramePacer {
+    /// The refresh rate used when the refresh rate of the display is not known.
+    pub const DEFAULT_RATE: u32 = 60;
+
+    /// Creates a pacer for the refresh rate of the display the view is on (see [`View::refresh_rate`]).
+    pub fn new<B: Backend>(view: &View<B>) -> Self {
+        let mut pacer = Self::with_rate(view.refresh_rate().unwrap_or(Self::DEFAULT_RATE));
+        pacer.follow_display = true;
+        pacer.synchronize_platform();
+        pacer
+    }
+
+    /// Creates a pacer with the given frame rate in Hz.
+    ///
+    /// The pacer doesn't synchronize itself with the display, since the rate may differ from its refresh rate
+    /// (but it can be synchronized explicitly with [`FramePacer::synchronize`]).
+    ///
+    /// # Panics
+    /// Panics if `rate` is zero.
+    pub fn with_rate(rate: u32) -> Self {
+        assert!(rate > 0, "frame rate must be positive");
+
+        let now = Instant::now();
+        Self {
+            anchor: now,
+            interval: Duration::from_secs(1) / rate,
+            last: now,
+            frame: 0,
+            follow_display: false,
+        }
+    }
+
+    /// Aligns the grid with a vertical blank reported by the system, see [`View::presentation_time`] and [`View::start_display_link`].
+    ///
+    /// The interval is replaced by the refresh interval of the display. Times that are only estimated
+    /// (see [`PresentationTime::synchronized`]) are ignored.
+    pub fn synchronize(&mut self, time: PresentationTime) {
+        if time.synchronized && !time.refresh_interval.is_zero() {
+            self.anchor = time.time;
+            self.interval = time.refresh_interval;
+        }
+    }
+
+    fn synchronize_platform(&mut self) {
+        if self.follow_display
+            && let Some((vblank, interval)) = platform::last_vblank()
+            && !interval.is_zero()
+        {
+            self.synchronize(PresentationTime {
+                time: vblank,
+                refresh_interval: interval,
+                synchronized: true,
+            });
+        }
+    }
+
+    /// Returns the first point of the grid after `time`.
+    fn grid_after(&self, time: Instant) -> Instant {
+        let interval = self.interval.as_nanos().max(1);
+        if time < self.anchor {
+            let before = ((self.anchor - time).as_nanos() - 1) / interval;
+            self.anchor - Duration::from_nanos((before * interval) as u64)
+        } else {
+            let after = (time - self.anchor).as_nanos() / interval + 1;
+            self.anchor + Duration::from_nanos((after * interval) as u64)
+        }
+    }
+
+    /// Returns the time between two frames.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns the number of the last frame returned by [`FramePacer::poll_frame`].
+    ///
+    /// Skipped frames are counted, so this is the number of intervals since the pacer was created.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the time at which the next frame is due.
+    pub fn next_frame(&self) -> Instant {
+        self.grid_after(self.last)
+    }
+
+    /// Returns `true` if a new frame is due, in which case the view should be redrawn.
+    ///
+    /// If several frames were missed, they are skipped and only one frame is reported.
+    pub fn poll_frame(&mut self) -> bool {
+        self.synchronize_platform();
+
+        let now = Instant::now();
+        let next = self.next_frame();
+        if now < next {
+            return false;
+        }
+
+        self.last = self.grid_after(now) - self.interval;
+        let missed = (self.last - next).as_nanos() / self.interval.as_nanos().max(1);
+        self.frame += missed as u64 + 1;
+        true
+    }
+
+    /// Returns a [`ControlFlow`] that waits until the next frame is due, for [`World::run`](crate::World::run).
+    pub fn control_flow(&self) -> ControlFlow {
+        ControlFlow::WaitUntil(self.next_frame())
+    }
+}
+
+/// How long the exposes of a view are kept for [`FrameStats`].
+const FRAME_STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Render statistics of a view over the last second, see [`View::frame_stats`].
+///
+/// Useful for an FPS or diagnostics overlay. The frame time is the time spent in the event handler for [`Event::Expose`](crate::Event::Expose),
+/// which doesn't include the time the system or the driver spends presenting the frame.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct FrameStats {
+    /// Number of exposes in the last second
+    pub frames_per_second: u32,
+    /// Average frame time
+    pub average_frame_time: Duration,
+    /// 95th percentile of the frame time, the frame time of the slowest frames ignoring outliers
+    pub p95_frame_time: Duration,
+    /// Longest frame time
+    pub max_frame_time: Duration,
+    /// Number of frames that took longer than one refresh interval of the display (see [`View::refresh_rate`]),
+    /// which likely missed the vertical blank
+    pub dropped_frames: u32,
+    /// Number of exposes since the view was realized (or since [`View::reset_frame_stats`])
+    pub total_frames: u64,
+}
+
+/// The exposes of a view, recorded by the event handler of the view.
+#[derive(Default)]
+pub(crate) struct FrameHistory {
+    /// Start and duration of the exposes in the last second
+    frames: std::collections::VecDeque<(Instant, Duration)>,
+    total: u64,
+}
+
+impl FrameHistory {
+    pub fn record(&mut self, start: Instant, duration: Duration) {
+        self.frames.push_back((start, duration));
+        self.total += 1;
+        self.prune(start);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&(start, _)) = self.frames.front()
+            && now.saturating_duration_since(start) > FRAME_STATS_WINDOW
+        {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn stats(&mut self, refresh_interval: Duration) -> FrameStats {
+        self.prune(Instant::now());
+
+        let mut durations = self
+            .frames
+            .iter()
+            .map(|&(_, duration)| duration)
+            .collect::<Vec<_>>();
+        durations.sort_unstable();
+
+        let count = durations.len();
+        let total = durations.iter().sum::<Duration>();
+
+        FrameStats {
+            frames_per_second: count as u32,
+            average_frame_time: total.checked_div(count as u32).unwrap_or_default(),
+            p95_frame_time: match count {
+                0 => Duration::ZERO,
+                _ => durations[(count * 95).div_ceil(100) - 1],
+            },
+            max_frame_time: durations.last().copied().unwrap_or_default(),
+            dropped_frames: durations
+                .iter()
+                .filter(|&&duration| duration > refresh_interval)
+                .count() as u32,
+            total_frames: self.total,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The estimated time at which a frame becomes visible, see [`View::presentation_time`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PresentationTime {
+    /// The estimated time at which a frame finished now is shown on the display
+    pub time: Instant,
+    /// The time between two refreshes of the display
+    pub refresh_interval: Duration,
+    /// `true` if `time` is the next vertical blank reported by the system,
+    /// `false` if it is only estimated as one refresh interval from now
+    pub synchronized: bool,
+}
+
+impl<B: Backend> View<B> {
+    /// Returns the estimated time at which the frame that is being drawn is shown on the display.
+    ///
+    /// This should be called at the end of the [`Event::Expose`](crate::Event::Expose) handler, so audio-visual synchronization
+    /// (like drawing the playhead) can compensate for the display latency. It assumes that vertical synchronization is enabled,
+    /// and doesn't include the latency of the display itself.
+    /// - Windows: the last vertical blank of the compositor (`DwmGetCompositionTimingInfo`).
+    /// - X11: the last vertical blank of the drawable (`GLX_OML_sync_control`), only with the `opengl` feature while the context is current.
+    /// - MacOS: not synchronized, since the timestamps of `CVDisplayLink` are only available from its own thread (see [`View::start_display_link`]).
+    ///
+    /// Otherwise, the time is estimated as one refresh interval from now (see [`PresentationTime::synchronized`]).
+    pub fn presentation_time(&self) -> PresentationTime {
+        let now = Instant::now();
+        let rate = self.refresh_rate().unwrap_or(FramePacer::DEFAULT_RATE);
+
+        match platform::last_vblank() {
+            Some((vblank, interval)) if !interval.is_zero() => {
+                let since = now.saturating_duration_since(vblank).as_nanos();
+                let refreshes = since.div_ceil(interval.as_nanos()).max(1);
+                PresentationTime {
+                    time: vblank + Duration::from_nanos((interval.as_nanos() * refreshes) as u64),
+                    refresh_interval: interval,
+                    synchronized: true,
+                }
+            }
+            _ => {
+                let interval = Duration::from_secs(1) / rate;
+                PresentationTime {
+                    time: now + interval,
+                    refresh_interval: interval,
+                    synchronized: false,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "opengl"))]
+mod platform {
+    use std::{
+        ffi::c_void,
+        os::raw::{c_char, c_int, c_long, c_ulong},
+        sync::OnceLock,
+        time::{Duration, Instant},
+    };
+
+    type GetSyncValues =
+        unsafe extern "C" fn(*mut c_void, c_ulong, *mut i64, *mut i64, *mut i64) -> c_int;
+    type GetMscRate = unsafe extern "C" fn(*mut c_void, c_ulong, *mut i32, *mut i32) -> c_int;
+
+    #[repr(C)]
+    struct Timespec {
+        sec: c_long,
+        nsec: c_long,
+    }
+
+    const CLOCK_MONOTONIC: c_int = 1;
+
+    unsafe extern "C" {
+        fn glXGetCurrentDisplay() -> *mut c_void;
+        fn glXGetCurrentDrawable() -> c_ulong;
+        fn glXGetProcAddressARB(name: *const c_char) -> Option<unsafe extern "C" fn()>;
+        fn clock_gettime(clock: c_int, time: *mut Timespec) -> c_int;
+    }
+
+    struct Functions {
+        get_sync_values: GetSyncValues,
+        get_msc_rate: GetMscRate,
+    }
+
+    fn functions() -> Option<&'static Functions> {
+        static FUNCTIONS: OnceLock<Option<Functions>> = OnceLock::new();
+        FUNCTIONS
+            .get_or_init(|| unsafe {
+                let get_sync_values = glXGetProcAddressARB(c"glXGetSyncValuesOML".as_ptr())?;
+                let get_msc_rate = glXGetProcAddressARB(c"glXGetMscRateOML".as_ptr())?;
+                Some(Functions {
+                    get_sync_values: std::mem::transmute::<unsafe extern "C" fn(), GetSyncValues>(
+                        get_sync_values,
+                    ),
+                    get_msc_rate: std::mem::transmute::<unsafe extern "C" fn(), GetMscRate>(
+                        get_msc_rate,
+                    ),
+                })
+            })
+            .as_ref()
+    }
+
+    pub fn last_vblank() -> Option<(Instant, Duration)> {
+        unsafe {
+            let display = glXGetCurrentDisplay();
+            let drawable = glXGetCurrentDrawable();
+            if display.is_null() || drawable == 0 {
+                return None;
+            }
+
+            let functions = functions()?;
+            let (mut ust, mut msc, mut sbc) = (0, 0, 0);
+            if (functions.get_sync_values)(display, drawable, &mut ust, &mut msc, &mut sbc) == 0 {
+                return None;
+            }
+
+            let (mut numerator, mut denominator) = (0, 0);
+            if (functions.get_msc_rate)(display, drawable, &mut numerator, &mut denominator) == 0
+                || numerator <= 0
+                || denominator <= 0
+            {
+                return None;
+            }
+
+            // the UST is in microseconds of the monotonic clock on Mesa and the proprietary drivers
+            let mut now = Timespec { sec: 0, nsec: 0 };
+            if ust <= 0 || clock_gettime(CLOCK_MONOTONIC, &mut now) != 0 {
+                return None;
+            }
+
+            let now_us = (now.sec as i128 * 1_000_000 + now.nsec as i128 / 1_000) as i64;
+            let age = Duration::from_micros(now_us.saturating_sub(ust).max(0) as u64);
+            let interval = Duration::from_secs(denominator as u64) / numerator as u32;
+            Some((Instant::now().checked_sub(age)?, interval))
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::time::{Duration, Instant};
+
+    /// `DWM_TIMING_INFO`, which is packed. Only the fields up to the last vertical blank are used.
+    #[repr(C, packed)]
+    struct DwmTimingInfo {
+        size: u32,
+        rate_refresh: [u32; 2],
+        qpc_refresh_period: u64,
+        rate_compose: [u32; 2],
+        qpc_vblank: u64,
+        rest: [u8; 256],
+    }
+
+    #[link(name = "dwmapi")]
+    unsafe extern "system" {
+        fn DwmGetCompositionTimingInfo(hwnd: isize, info: *mut DwmTimingInfo) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn QueryPerformanceCounter(count: *mut i64) -> i32;
+        fn QueryPerformanceFrequency(frequency: *mut i64) -> i32;
+    }
+
+    pub fn last_vblank() -> Option<(Instant, Duration)> {
+        unsafe {
+            let mut info = DwmTimingInfo {
+                size: size_of::<DwmTimingInfo>() as u32,
+                rate_refresh: [0; 2],
+                qpc_refresh_period: 0,
+                rate_compose: [0; 2],
+                qpc_vblank: 0,
+                rest: [0; 256],
+            };
+
+            // the window must be null since Windows 8.1
+            if DwmGetCompositionTimingInfo(0, &mut info) < 0 {
+                return None;
+            }
+
+            let (mut now, mut frequency) = (0, 0);
+            if QueryPerformanceCounter(&mut now) == 0
+                || QueryPerformanceFrequency(&mut frequency) == 0
+                || frequency <= 0
+            {
+                return None;
+            }
+
+            let to_duration = |ticks: u64| {
+                Duration::from_nanos((ticks as u128 * 1_000_000_000 / frequency as u128) as u64)
+            };
+
+            let vblank = info.qpc_vblank;
+            let period = info.qpc_refresh_period;
+            let age = to_duration((now as u64).saturating_sub(vblank));
+            Some((Instant::now().checked_sub(age)?, to_duration(period)))
+        }
+    }
+}
+
+#[cfg(not(any(all(target_os = "linux", feature = "opengl"), target_os = "windows")))]
+mod platform {
+    use std::time::{Duration, Instant};
+
+    pub fn last_vblank() -> Option<(Instant, Duration)> {
+        None
+    }
+}
@@ -0,0 +1,85 @@
+use crate::Event;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+// doc only import
+#[allow(unused_imports)]
+use crate::View;
+
+/// The contents of the clipboard, see [`View::paste_clipboard_async`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClipboardContents {
+    /// The type that was negotiated with the clipboard owner (see [`View::paste_clipboard_as`])
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl ClipboardContents {
+    /// Returns the data as text, or `None` if it is not valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.data).ok()
+    }
+
+    /// Returns the contents of an [`Event::Clipboard`] or an [`Event::ClipboardData`] event.
+    pub(crate) fn from_event<B: crate::Backend>(event: &Event<B>) -> Option<Self> {
+        match *event {
+            Event::Clipboard { mime_type, text } => Some(ClipboardContents {
+                mime_type: mime_type.to_owned(),
+                data: text.as_bytes().to_vec(),
+            }),
+            Event::ClipboardData { mime_type, data } => Some(ClipboardContents {
+                mime_type: mime_type.to_owned(),
+                data: data.to_vec(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A future that resolves with the contents of the clipboard, see [`View::paste_clipboard_async`].
+///
+/// Resolves with `None` if the clipboard has none of the requested types, or if the view is unrealized before the data arrives.
+pub struct ClipboardPaste(pub(crate) Arc<Mutex<PasteSlot>>);
+
+#[derive(Default)]
+pub(crate) struct PasteSlot {
+    result: Option<Option<ClipboardContents>>,
+    waker: Option<Waker>,
+}
+
+impl PasteSlot {
+    pub fn complete(&mut self, contents: Option<ClipboardContents>) {
+        if self.result.is_none() {
+            self.result = Some(contents);
+        }
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for ClipboardPaste {
+    type Output = Option<ClipboardContents>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.0.lock().unwrap();
+        match slot.result.take() {
+            Some(contents) => Poll::Ready(contents),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ClipboardPaste {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClipboardPaste").finish_non_exhaustive()
+    }
+}
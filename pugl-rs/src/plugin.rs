@@ -0,0 +1,163 @@
+use crate::{
+    Backend, Event, NativeView, UnrealizedView, UpdateStats, View, ViewError, ViewParent, World,
+    WorldError, WorldInner, sys,
+};
+use std::{
+    sync::{Arc, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The idle calls of the editors of a world that are closer than this are treated as one tick of the host, see [`PluginEditor::idle`].
+const IDLE_TICK: Duration = Duration::from_millis(4);
+
+/// Glue for using a view as the editor of an audio plugin (CLAP, VST3, ...).
+///
+/// Plugin hosts own both the parent window and the event loop, so the editor:
+/// - embeds the view into the window provided by the host, see [`PluginEditor::attach`]
+/// - negotiates the size with the host, see [`PluginEditor::preferred_size`], [`PluginEditor::adjust_size`] and [`PluginEditor::set_size`]
+/// - processes the events from the idle (or timer) callback of the host, see [`PluginEditor::idle`],
+///   or from a timer of its own if the host doesn't provide one, see [`PluginEditor::start_idle_timer`]
+///
+/// All sizes are in physical pixels, like everywhere else in `pugl-rs`.
+/// ```no_run
+/// # use pugl_rs::*;
+/// # let parent = unsafe { NativeView::from_raw(0) };
+/// // a world per editor, see `PluginEditor::idle` for sharing a world between the editors of a module
+/// let world = World::new_module().unwrap();
+/// let view = world.new_view(()).with_size(640, 480).with_resizable(true);
+/// let mut editor = PluginEditor::attach(view, parent).unwrap();
+/// editor.view().show().unwrap();
+///
+/// // in the host idle or timer callback
+/// editor.idle().unwrap();
+///
+/// // when the host asks to resize the editor
+/// editor.set_size(800, 600);
+/// ```
+pub struct PluginEditor<B: Backend> {
+    // the views are dropped before the world
+    idle_timer: Option<View<()>>,
+    view: View<B>,
+    world: World,
+}
+
+impl<B: Backend> PluginEditor<B> {
+    /// Embeds the view into the parent window provided by the host and realizes it.
+    ///
+    /// The view is not shown, call [`View::show`] when the host asks to show the editor.
+    pub fn attach(view: UnrealizedView<B>, parent: NativeView) -> Result<Self, ViewError> {
+        let view = view.with_parent(ViewParent::Embedding(parent)).realize()?;
+        let world = WorldInner::to_world(&view.world);
+        Ok(Self {
+            idle_timer: None,
+            view,
+            world,
+        })
+    }
+
+    /// Returns the editor view.
+    pub fn view(&self) -> &View<B> {
+        &self.view
+    }
+
+    /// Returns the world of the editor view.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Returns the size the editor wants to have, which should be reported to the host.
+    pub fn preferred_size(&self) -> (u32, u32) {
+        self.view.size()
+    }
+
+    /// Returns `true` if the host is allowed to resize the editor, see [`UnrealizedView::with_resizable`].
+    pub fn is_resizable(&self) -> bool {
+        unsafe { sys::puglGetViewHint(self.view.view, sys::PUGL_RESIZABLE) != 0 }
+    }
+
+    /// Returns the closest size to the one requested by the host that the editor accepts.
+    ///
+    /// The size is clamped to the minimum and maximum size of the view. If the view is not resizable, the current size is returned.
+    pub fn adjust_size(&self, width: u32, height: u32) -> (u32, u32) {
+        if !self.is_resizable() {
+            return self.view.size();
+        }
+
+        unsafe {
+            let min = sys::puglGetSizeHint(self.view.view, sys::PUGL_MIN_SIZE);
+            let max = sys::puglGetSizeHint(self.view.view, sys::PUGL_MAX_SIZE);
+
+            let clamp = |value: u32, min: u16, max: u16| {
+                let value = value.max(min as u32);
+                if max == 0 {
+                    value
+                } else {
+                    value.min(max as u32)
+                }
+            };
+
+            (
+                clamp(width, min.width, max.width),
+                clamp(height, min.height, max.height),
+            )
+        }
+    }
+
+    /// Resizes the editor on a request of the host, after adjusting the size with [`PluginEditor::adjust_size`].
+    ///
+    /// Returns `false` if the host requested a size that the editor doesn't accept, or the view could not be resized.
+    pub fn set_size(&self, width: u32, height: u32) -> bool {
+        let size = self.adjust_size(width, height);
+        self.view.set_size(size.0, size.1).is_ok() && size == (width, height)
+    }
+
+    /// Processes the pending events without blocking.
+    ///
+    /// This should be called from the idle (or timer) callback of the host, since the plugin can't run its own event loop.
+    /// A rate around the refresh rate of the display (like 60 Hz) keeps the editor responsive.
+    ///
+    /// The world may be shared by several editors (see [`World::module_shared`]), whose idle callbacks are called one after another by the host.
+    /// The world is only polled by the first of them, the calls within a few milliseconds of it return empty [`UpdateStats`].
+    pub fn idle(&mut self) -> Result<UpdateStats, WorldError> {
+        idle_world(&self.view.world)
+    }
+
+    /// Calls [`PluginEditor::idle`] from a timer, for hosts that dispatch the events of the editor but don't have an idle callback for it.
+    ///
+    /// The timer belongs to a hidden view, whose timer events are dispatched by the event loop of the host like the events of the editor view,
+    /// so this is only useful on Windows and MacOS, where the windows of a process share the event loop. The timer is stopped when the editor is dropped.
+    pub fn start_idle_timer(&mut self, interval: Duration) -> Result<(), ViewError> {
+        let timer = self
+            .world
+            .new_view(())
+            .with_size(1, 1)
+            .with_event_handler(|view, event| {
+                if let Event::Timer { .. } = event {
+                    // there is nobody to report an error to, the next tick tries again
+                    let _ = idle_world(&view.world);
+                }
+            })
+            .realize()?;
+
+        timer.start_timer(0, interval)?;
+        self.idle_timer = Some(timer);
+        Ok(())
+    }
+}
+
+/// Polls the world, unless an editor of the world already polled it in this tick of the host.
+fn idle_world(world: &Arc<WorldInner>) -> Result<UpdateStats, WorldError> {
+    let now = Instant::now();
+
+    let mut last_idle = world
+        .last_idle
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if last_idle.is_some_and(|last_idle| now.duration_since(last_idle) < IDLE_TICK) {
+        return Ok(UpdateStats::default());
+    }
+    *last_idle = Some(now);
+    drop(last_idle);
+
+    WorldInner::to_world(world).poll()
+}
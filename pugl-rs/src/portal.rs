@@ -0,0 +1,634 @@
+use std::{
+    env,
+    ffi::OsStr,
+    io::{Read, Write},
+    os::{
+        linux::net::SocketAddrExt,
+        unix::{
+            ffi::OsStrExt,
+            net::{SocketAddr, UnixStream},
+        },
+    },
+};
+
+const PORTAL_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+const SIGNAL: u8 = 4;
+
+const ENDIANNESS: u8 = if cfg!(target_endian = "little") {
+    b'l'
+} else {
+    b'B'
+};
+
+unsafe extern "C" {
+    fn getuid() -> u32;
+}
+
+/// The response of a portal request, see [`request`].
+pub struct Response {
+    /// 0 if the request succeeded, 1 if it was cancelled by the user and 2 if it ended in another way
+    pub code: u32,
+    /// `ua{sv}`, the code followed by the results
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// Returns a string array result (`as`), like the `uris` of the file chooser.
+    pub fn strings(&self, key: &str) -> Option<Vec<String>> {
+        let mut reader = Reader::new(&self.body);
+        reader.u32()?;
+
+        let len = reader.u32()? as usize;
+        reader.align(8)?;
+        let end = reader.pos + len;
+
+        while reader.pos < end {
+            reader.align(8)?;
+            let name = reader.string()?;
+            let signature = reader.signature()?;
+            if name != key || signature != b"as" {
+                reader.skip(signature)?;
+                continue;
+            }
+
+            let len = reader.u32()? as usize;
+            let end = reader.pos + len;
+            let mut strings = Vec::new();
+            while reader.pos < end {
+                strings.push(reader.string()?.to_owned());
+            }
+
+            return Some(strings);
+        }
+
+        None
+    }
+}
+
+/// Calls a method of the XDG desktop portal that starts a request, and blocks until the response of the request arrives.
+///
+/// `arguments` writes the arguments before the options (`a{sv}`), which are written by `options` after the `handle_token` option.
+/// `signature` is the signature of all arguments, including the options.
+///
+/// Returns `None` if there is no session bus, if the portal or the interface is not available, or if the connection failed.
+///
+/// The portal is called over a new connection to the session bus, which is closed afterwards, so there is no state to keep.
+/// Only the subset of D-Bus that is needed for this is implemented, so there is no dependency on `libdbus`.
+pub fn request(
+    interface: &str,
+    method: &str,
+    signature: &str,
+    arguments: impl FnOnce(&mut Writer),
+    options: impl FnOnce(&mut Writer),
+) -> Option<Response> {
+    const TOKEN: &str = "pugl_rs";
+
+    let mut connection = Connection::session()?;
+
+    // the path of the request is known in advance, so the response can't be missed
+    let sender = connection
+        .unique_name
+        .trim_start_matches(':')
+        .replace('.', "_");
+    let mut handle = format!("{PORTAL_PATH}/request/{sender}/{TOKEN}");
+    connection.add_match(&handle)?;
+
+    let mut body = Writer::default();
+    arguments(&mut body);
+    body.array(8, |body| {
+        body.entry("handle_token", "s", |body| body.string(TOKEN));
+        options(body);
+    });
+
+    let reply = connection.call(
+        PORTAL_NAME,
+        PORTAL_PATH,
+        interface,
+        method,
+        signature,
+        &body.0,
+    )?;
+
+    // older versions of the portal ignore the token
+    let returned = Reader::new(&reply.body).string()?;
+    if returned != handle {
+        handle = returned.to_owned();
+        connection.add_match(&handle)?;
+    }
+
+    let response = connection.response(&handle)?;
+    let code = Reader::new(&response.body).u32()?;
+
+    Some(Response {
+        code,
+        body: response.body,
+    })
+}
+
+/// Decodes the `%XX` escapes of URIs and D-Bus addresses.
+pub fn unescape(string: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(string.len());
+    let mut rest = string.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(escaped) if byte == b'%' => {
+                bytes.push(escaped);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    bytes
+}
+
+/// A message received from the bus, with the header fields that are used here.
+struct Message {
+    kind: u8,
+    reply_serial: Option<u32>,
+    path: Option<String>,
+    member: Option<String>,
+    body: Vec<u8>,
+}
+
+/// A blocking connection to the session bus.
+struct Connection {
+    stream: UnixStream,
+    serial: u32,
+    unique_name: String,
+    /// The responses that arrived while waiting for a method reply
+    responses: Vec<Message>,
+}
+
+impl Connection {
+    /// Connects and authenticates to the session bus, returns `None` if there is no session bus.
+    fn session() -> Option<Self> {
+        let mut stream = connect()?;
+
+        // the credentials are passed by the socket, the uid is only repeated in hex ASCII
+        let uid = unsafe { getuid() }
+            .to_string()
+            .bytes()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        stream
+            .write_all(format!("\0AUTH EXTERNAL {uid}\r\n").as_bytes())
+            .ok()?;
+        if !read_line(&mut stream)?.starts_with("OK ") {
+            return None;
+        }
+        stream.write_all(b"BEGIN\r\n").ok()?;
+
+        let mut connection = Self {
+            stream,
+            serial: 0,
+            unique_name: String::new(),
+            responses: Vec::new(),
+        };
+
+        let reply = connection.call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "Hello",
+            "",
+            &[],
+        )?;
+        connection.unique_name = Reader::new(&reply.body).string()?.to_owned();
+
+        Some(connection)
+    }
+
+    /// Subscribes to the response of the request with the given path.
+    fn add_match(&mut self, path: &str) -> Option<()> {
+        let rule = format!(
+            "type='signal',interface='org.freedesktop.portal.Request',member='Response',path='{path}'"
+        );
+
+        let mut body = Writer::default();
+        body.string(&rule);
+
+        self.call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "AddMatch",
+            "s",
+            &body.0,
+        )
+        .map(drop)
+    }
+
+    /// Calls a method and waits for its reply, returns `None` if the method failed.
+    fn call(
+        &mut self,
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> Option<Message> {
+        self.serial += 1;
+
+        let mut message = Writer::default();
+        message.byte(ENDIANNESS);
+        message.byte(METHOD_CALL);
+        message.byte(0);
+        message.byte(1);
+        message.u32(body.len() as u32);
+        message.u32(self.serial);
+        message.array(8, |fields| {
+            fields.field(1, "o", path);
+            fields.field(6, "s", destination);
+            fields.field(2, "s", interface);
+            fields.field(3, "s", member);
+            if !signature.is_empty() {
+                fields.structure(|field| {
+                    field.byte(8);
+                    field.signature("g");
+                    field.signature(signature);
+                });
+            }
+        });
+        message.align(8);
+        message.bytes(body);
+
+        self.stream.write_all(&message.0).ok()?;
+
+        loop {
+            let message = self.receive()?;
+            match message.kind {
+                METHOD_RETURN | ERROR if message.reply_serial == Some(self.serial) => {
+                    return (message.kind == METHOD_RETURN).then_some(message);
+                }
+                SIGNAL if message.member.as_deref() == Some("Response") => {
+                    self.responses.push(message);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Waits for the response of the request with the given path.
+    fn response(&mut self, path: &str) -> Option<Message> {
+        if let Some(index) = self
+            .responses
+            .iter()
+            .position(|message| message.path.as_deref() == Some(path))
+        {
+            return Some(self.responses.swap_remove(index));
+        }
+
+        loop {
+            let message = self.receive()?;
+            if message.kind == SIGNAL
+                && message.member.as_deref() == Some("Response")
+                && message.path.as_deref() == Some(path)
+            {
+                return Some(message);
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Option<Message> {
+        let mut fixed = [0u8; 16];
+        self.stream.read_exact(&mut fixed).ok()?;
+
+        // the bus passes messages on in the byte order of the sender, which is the native one in practice
+        if fixed[0] != ENDIANNESS {
+            return None;
+        }
+
+        let body_len = u32::from_ne_bytes(fixed[4..8].try_into().unwrap()) as usize;
+        let fields_len = u32::from_ne_bytes(fixed[12..16].try_into().unwrap()) as usize;
+        let body_start = 16 + fields_len.next_multiple_of(8);
+
+        let mut data = fixed.to_vec();
+        data.resize(body_start + body_len, 0);
+        self.stream.read_exact(&mut data[16..]).ok()?;
+
+        let mut message = Message {
+            kind: fixed[1],
+            reply_serial: None,
+            path: None,
+            member: None,
+            body: data[body_start..].to_vec(),
+        };
+
+        // the offsets of the reader are relative to the start of the message, like the alignment
+        let mut fields = Reader {
+            data: &data[..16 + fields_len],
+            pos: 16,
+        };
+        while fields.pos < fields.data.len() {
+            fields.align(8)?;
+            let code = fields.byte()?;
+            let signature = fields.signature()?;
+            match (code, signature) {
+                (1, b"o") => message.path = Some(fields.string()?.to_owned()),
+                (3, b"s") => message.member = Some(fields.string()?.to_owned()),
+                (5, b"u") => message.reply_serial = Some(fields.u32()?),
+                _ => fields.skip(signature)?,
+            }
+        }
+
+        Some(message)
+    }
+}
+
+/// Connects to the first address of the session bus that can be connected to.
+fn connect() -> Option<UnixStream> {
+    let Ok(addresses) = env::var("DBUS_SESSION_BUS_ADDRESS") else {
+        let runtime_dir = env::var_os("XDG_RUNTIME_DIR")?;
+        return UnixStream::connect(std::path::Path::new(&runtime_dir).join("bus")).ok();
+    };
+
+    for address in addresses.split(';') {
+        let Some(parameters) = address.strip_prefix("unix:") else {
+            continue;
+        };
+
+        for parameter in parameters.split(',') {
+            let stream = if let Some(path) = parameter.strip_prefix("path=") {
+                UnixStream::connect(OsStr::from_bytes(&unescape(path)))
+            } else if let Some(name) = parameter.strip_prefix("abstract=") {
+                SocketAddr::from_abstract_name(unescape(name))
+                    .and_then(|address| UnixStream::connect_addr(&address))
+            } else {
+                continue;
+            };
+
+            if let Ok(stream) = stream {
+                return Some(stream);
+            }
+        }
+    }
+
+    None
+}
+
+/// Reads a line of the authentication protocol, which is read byte by byte so nothing after it is consumed.
+fn read_line(stream: &mut UnixStream) -> Option<String> {
+    let mut line = Vec::new();
+    let mut byte = [0u8];
+
+    while !line.ends_with(b"\r\n") {
+        stream.read_exact(&mut byte).ok()?;
+        line.push(byte[0]);
+    }
+
+    String::from_utf8(line).ok()
+}
+
+/// Marshals values in the D-Bus wire format, in the native byte order.
+///
+/// Values are aligned relative to the start of the buffer, which is also the start of the message or its body.
+#[derive(Default)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    fn align(&mut self, alignment: usize) {
+        let len = self.0.len().next_multiple_of(alignment);
+        self.0.resize(len, 0);
+    }
+
+    pub fn byte(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub fn bytes(&mut self, value: &[u8]) {
+        self.0.extend_from_slice(value);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.align(4);
+        self.0.extend_from_slice(&value.to_ne_bytes());
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.u32(value as u32);
+    }
+
+    /// Writes a string or an object path. Strings can't contain nul bytes, so they are removed.
+    pub fn string(&mut self, value: &str) {
+        let value = value.replace('\0', "");
+        self.u32(value.len() as u32);
+        self.0.extend_from_slice(value.as_bytes());
+        self.0.push(0);
+    }
+
+    pub fn signature(&mut self, value: &str) {
+        self.0.push(value.len() as u8);
+        self.0.extend_from_slice(value.as_bytes());
+        self.0.push(0);
+    }
+
+    /// Writes an array, `alignment` is the alignment of the elements (8 for structs and dict entries).
+    pub fn array(&mut self, alignment: usize, elements: impl FnOnce(&mut Self)) {
+        self.u32(0);
+        let len_offset = self.0.len() - 4;
+
+        // the padding before the first element is not a part of the length
+        self.align(alignment);
+        let start = self.0.len();
+        elements(self);
+
+        let len = (self.0.len() - start) as u32;
+        self.0[len_offset..len_offset + 4].copy_from_slice(&len.to_ne_bytes());
+    }
+
+    pub fn structure(&mut self, fields: impl FnOnce(&mut Self)) {
+        self.align(8);
+        fields(self);
+    }
+
+    /// Writes an entry of an `a{sv}` dictionary, `signature` is the signature of the value.
+    pub fn entry(&mut self, key: &str, signature: &str, value: impl FnOnce(&mut Self)) {
+        self.structure(|entry| {
+            entry.string(key);
+            entry.signature(signature);
+            value(entry);
+        });
+    }
+
+    /// Writes a string header field.
+    fn field(&mut self, code: u8, signature: &str, value: &str) {
+        self.structure(|field| {
+            field.byte(code);
+            field.signature(signature);
+            field.string(value);
+        });
+    }
+}
+
+/// Unmarshals values in the D-Bus wire format, in the native byte order.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn align(&mut self, alignment: usize) -> Option<()> {
+        self.pos = self.pos.next_multiple_of(alignment);
+        (self.pos <= self.data.len()).then_some(())
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.align(4)?;
+        Some(u32::from_ne_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+        let string = self.take(len)?;
+        self.take(1)?;
+        std::str::from_utf8(string).ok()
+    }
+
+    fn signature(&mut self) -> Option<&'a [u8]> {
+        let len = self.byte()? as usize;
+        let signature = self.take(len)?;
+        self.take(1)?;
+        Some(signature)
+    }
+
+    /// Skips a value of the given single complete type.
+    fn skip(&mut self, signature: &[u8]) -> Option<()> {
+        match *signature.first()? {
+            b'y' => self.take(1).map(drop),
+            b'n' | b'q' => self.align(2).and_then(|_| self.take(2)).map(drop),
+            b'b' | b'i' | b'u' | b'h' => self.align(4).and_then(|_| self.take(4)).map(drop),
+            b'x' | b't' | b'd' => self.align(8).and_then(|_| self.take(8)).map(drop),
+            b's' | b'o' => self.string().map(drop),
+            b'g' => self.signature().map(drop),
+            b'v' => {
+                let signature = self.signature()?;
+                self.skip(signature)
+            }
+            b'a' => {
+                let len = self.u32()? as usize;
+                self.align(alignment(*signature.get(1)?))?;
+                self.take(len).map(drop)
+            }
+            b'(' | b'{' => {
+                self.align(8)?;
+                let mut fields = signature.get(1..signature.len() - 1)?;
+                while !fields.is_empty() {
+                    let len = type_len(fields)?;
+                    self.skip(&fields[..len])?;
+                    fields = &fields[len..];
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the alignment of the type with the given type code.
+fn alignment(code: u8) -> usize {
+    match code {
+        b'y' | b'g' | b'v' => 1,
+        b'n' | b'q' => 2,
+        b'x' | b't' | b'd' | b'(' | b'{' => 8,
+        _ => 4,
+    }
+}
+
+/// Returns the length of the first single complete type of the signature.
+fn type_len(signature: &[u8]) -> Option<usize> {
+    match *signature.first()? {
+        b'a' => Some(1 + type_len(&signature[1..])?),
+        b'(' | b'{' => {
+            let mut depth = 0;
+            for (index, &code) in signature.iter().enumerate() {
+                match code {
+                    b'(' | b'{' => depth += 1,
+                    b')' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(index + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+        _ => Some(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_strings() {
+        // the results of a file chooser, with a value before the URIs that has to be skipped
+        let mut body = Writer::default();
+        body.u32(0);
+        body.array(8, |results| {
+            results.entry("choices", "a(ss)", |value| {
+                value.array(8, |choices| {
+                    choices.structure(|choice| {
+                        choice.string("encoding");
+                        choice.string("utf8");
+                    });
+                });
+            });
+            results.entry("uris", "as", |value| {
+                value.array(4, |uris| {
+                    uris.string("file:///tmp/a.wav");
+                    uris.string("file:///tmp/b%20c.wav");
+                });
+            });
+        });
+
+        let response = Response {
+            code: 0,
+            body: body.0,
+        };
+        assert_eq!(
+            response.strings("uris").unwrap(),
+            ["file:///tmp/a.wav", "file:///tmp/b%20c.wav"]
+        );
+        assert_eq!(response.strings("current_filter"), None);
+    }
+
+    #[test]
+    fn unescape_percent() {
+        assert_eq!(unescape("/tmp/b%20c%2Fd"), b"/tmp/b c/d");
+        assert_eq!(unescape("100%"), b"100%");
+        assert_eq!(unescape("%zz"), b"%zz");
+    }
+}
@@ -0,0 +1,500 @@
+use crate::{
+    Backend, CrossingMode, EventInput, Key, Modifiers, MouseButton, OwnedEvent, Rect, ScreenPoint,
+    ScrollDirection, View, ViewPoint, ViewStyle, WorldInstant,
+};
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    str::SplitWhitespace,
+    time::Duration,
+};
+
+// doc only import
+#[allow(unused_imports)]
+use crate::{Event, World};
+
+/// Records the events of a view, so they can be saved and replayed later with [`EventPlayer`].
+///
+/// Events are recorded together with their time, which can be obtained with [`World::time`] in the event handler
+/// (or in a hook set with [`World::set_global_event_hook`]):
+/// ```no_run
+/// # use pugl_rs::*;
+/// # use std::sync::{Arc, Mutex};
+/// # let world = World::new_program().unwrap();
+/// let recorder = Arc::new(Mutex::new(EventRecorder::new()));
+/// let view = world.new_view(()).with_event_handler({
+///     let recorder = recorder.clone();
+///     move |view, event| {
+///         recorder.lock().unwrap().record(view.world().time(), &OwnedEvent::from(&event));
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventRecorder {
+    start: Option<WorldInstant>,
+    events: Vec<(Duration, OwnedEvent)>,
+}
+
+impl EventRecorder {
+    /// Creates an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event that happened at `time`. The time of the first recorded event is the start of the recording.
+    pub fn record(&mut self, time: WorldInstant, event: &OwnedEvent) {
+        let start = *self.start.get_or_insert(time);
+        self.events.push((time - start, event.clone()));
+    }
+
+    /// Finishes the recording.
+    pub fn into_recording(self) -> EventRecording {
+        EventRecording {
+            events: self.events,
+        }
+    }
+}
+
+/// A sequence of recorded events, see [`EventRecorder`] and [`EventPlayer`].
+///
+/// Recordings are stored in a line-based text format, with one event per line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventRecording {
+    events: Vec<(Duration, OwnedEvent)>,
+}
+
+impl EventRecording {
+    /// Returns the recorded events, with their time relative to the start of the recording.
+    pub fn events(&self) -> &[(Duration, OwnedEvent)] {
+        &self.events
+    }
+
+    /// Returns the duration of the recording.
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |(time, _)| *time)
+    }
+
+    /// Writes the recording to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a recording from a file written with [`EventRecording::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the recording to a writer.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        for (time, event) in &self.events {
+            writeln!(writer, "{} {}", time.as_secs_f64(), format_event(event))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a recording written with [`EventRecording::write_to`].
+    pub fn read_from(reader: impl BufRead) -> io::Result<Self> {
+        let mut events = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid event on line {}", index + 1),
+                )
+            };
+
+            let mut tokens = line.split_whitespace();
+            let time = tokens
+                .next()
+                .and_then(|time| time.parse().ok())
+                .and_then(|time| Duration::try_from_secs_f64(time).ok())
+                .ok_or_else(invalid)?;
+            let event = parse_event(&mut tokens).ok_or_else(invalid)?;
+            events.push((time, event));
+        }
+
+        Ok(Self { events })
+    }
+}
+
+/// Replays an [`EventRecording`] into a view.
+///
+/// The player doesn't have its own timer: [`EventPlayer::play`] should be called regularly
+/// (for example from [`Event::Update`] or a timer event), and injects the events that are due with [`View::inject_event`].
+#[derive(Clone, Debug)]
+pub struct EventPlayer {
+    recording: EventRecording,
+    speed: f64,
+    start: Option<WorldInstant>,
+    next: usize,
+}
+
+impl EventPlayer {
+    /// Creates a player for a recording.
+    ///
+    /// `speed` scales the playback speed, `1.0` replays the events at their original pace and `2.0` twice as fast.
+    /// A speed of `f64::INFINITY` replays all events at once.
+    pub fn new(recording: EventRecording, speed: f64) -> Self {
+        Self {
+            recording,
+            speed,
+            start: None,
+            next: 0,
+        }
+    }
+
+    /// Injects the events that are due into the view. The playback starts on the first call.
+    ///
+    /// Returns `true` once all events have been replayed.
+    pub fn play<B: Backend>(&mut self, view: &View<B>) -> bool {
+        let now = view.world().time();
+        let elapsed = (now - *self.start.get_or_insert(now)).as_secs_f64() * self.speed;
+
+        while let Some((time, event)) = self.recording.events.get(self.next) {
+            if time.as_secs_f64() > elapsed {
+                break;
+            }
+
+            view.inject_event(event.clone());
+            self.next += 1;
+        }
+
+        self.is_finished()
+    }
+
+    /// Returns `true` once all events have been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// Restarts the playback from the beginning.
+    pub fn restart(&mut self) {
+        self.start = None;
+        self.next = 0;
+    }
+}
+
+fn format_event(event: &OwnedEvent) -> String {
+    let mut line = String::new();
+    let _ = match event {
+        OwnedEvent::Configure { rect, style, .. } => {
+            write!(line, "configure {} {}", format_rect(rect), style.bits())
+        }
+        OwnedEvent::ParentConfigure { rect, scale } => {
+            write!(line, "parent-configure {} {}", format_rect(rect), scale)
+        }
+        OwnedEvent::Realize => write!(line, "realize"),
+        OwnedEvent::Unrealize => write!(line, "unrealize"),
+        OwnedEvent::EnterLoop => write!(line, "enter-loop"),
+        OwnedEvent::LeaveLoop => write!(line, "leave-loop"),
+        OwnedEvent::ScaleChange { scale } => write!(line, "scale-change {}", scale),
+        OwnedEvent::Close => write!(line, "close"),
+        OwnedEvent::Update => write!(line, "update"),
+        OwnedEvent::Expose { rect } => write!(line, "expose {}", format_rect(rect)),
+        OwnedEvent::FocusIn { mode } => write!(line, "focus-in {}", mode.into_raw()),
+        OwnedEvent::FocusOut { mode } => write!(line, "focus-out {}", mode.into_raw()),
+        OwnedEvent::KeyPress {
+            input,
+            keycode,
+            key,
+        } => write!(
+            line,
+            "key-press {} {} {}",
+            format_input(input),
+            keycode,
+            key.into_raw()
+        ),
+        OwnedEvent::KeyRelease {
+            input,
+            keycode,
+            key,
+        } => write!(
+            line,
+            "key-release {} {} {}",
+            format_input(input),
+            keycode,
+            key.into_raw()
+        ),
+        OwnedEvent::KeyText {
+            input,
+            keycode,
+            text,
+        } => write!(
+            line,
+            "key-text {} {} {}",
+            format_input(input),
+            keycode,
+            format_bytes(text.as_bytes())
+        ),
+        OwnedEvent::KeyboardInput {
+            input,
+            keycode,
+            key,
+            text,
+        } => write!(
+            line,
+            "keyboard-input {} {} {} {} {}",
+            format_input(input),
+            keycode,
+            key.into_raw(),
+            text.is_some() as u8,
+            format_bytes(text.as_deref().unwrap_or_default().as_bytes())
+        ),
+        OwnedEvent::DeadKey {
+            input,
+            keycode,
+            accent,
+        } => write!(
+            line,
+            "dead-key {} {} {}",
+            format_input(input),
+            keycode,
+            *accent as u32
+        ),
+        OwnedEvent::PointerIn { input, mode } => {
+            write!(
+                line,
+                "pointer-in {} {}",
+                format_input(input),
+                mode.into_raw()
+            )
+        }
+        OwnedEvent::PointerOut { input, mode } => {
+            write!(
+                line,
+                "pointer-out {} {}",
+                format_input(input),
+                mode.into_raw()
+            )
+        }
+        OwnedEvent::PointerMotion { input } => {
+            write!(line, "pointer-motion {}", format_input(input))
+        }
+        OwnedEvent::ButtonPress { input, button } => write!(
+            line,
+            "button-press {} {}",
+            format_input(input),
+            button.into_raw()
+        ),
+        OwnedEvent::ButtonRelease { input, button } => write!(
+            line,
+            "button-release {} {}",
+            format_input(input),
+            button.into_raw()
+        ),
+        OwnedEvent::Scroll {
+            input,
+            direction,
+            dx,
+            dy,
+        } => write!(
+            line,
+            "scroll {} {} {} {}",
+            format_input(input),
+            direction.into_raw(),
+            dx,
+            dy
+        ),
+        OwnedEvent::Timer { id } => write!(line, "timer {}", id),
+        OwnedEvent::Client { data } => write!(line, "client {} {}", data[0], data[1]),
+        OwnedEvent::Clipboard { mime_type, text } => write!(
+            line,
+            "clipboard {} {}",
+            format_bytes(mime_type.as_bytes()),
+            format_bytes(text.as_bytes())
+        ),
+        OwnedEvent::ClipboardData { mime_type, data } => write!(
+            line,
+            "clipboard-data {} {}",
+            format_bytes(mime_type.as_bytes()),
+            format_bytes(data)
+        ),
+        OwnedEvent::ClipboardFailed => write!(line, "clipboard-failed"),
+        OwnedEvent::EndFrame => write!(line, "end-frame"),
+    };
+
+    line
+}
+
+fn format_rect(rect: &Rect) -> String {
+    format!("{} {} {} {}", rect.x, rect.y, rect.w, rect.h)
+}
+
+fn format_input(input: &EventInput) -> String {
+    format!(
+        "{} {} {} {} {} {} {}",
+        input.time.as_secs_f64(),
+        input.position.x,
+        input.position.y,
+        input.root_position.x,
+        input.root_position.y,
+        input.mods.bits(),
+        input.hint as u8
+    )
+}
+
+/// Strings are stored as hex, so they can contain whitespace and newlines. Empty strings are stored as `-`.
+fn format_bytes(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "-".to_string();
+    }
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_event(tokens: &mut SplitWhitespace) -> Option<OwnedEvent> {
+    let event = match tokens.next()? {
+        "configure" => OwnedEvent::Configure {
+            rect: parse_rect(tokens)?,
+            style: ViewStyle::from_bits_retain(parse(tokens)?),
+            // recomputed when the event is injected
+            changed: ViewStyle::empty(),
+        },
+        "parent-configure" => OwnedEvent::ParentConfigure {
+            rect: parse_rect(tokens)?,
+            scale: parse(tokens)?,
+        },
+        "realize" => OwnedEvent::Realize,
+        "unrealize" => OwnedEvent::Unrealize,
+        "enter-loop" => OwnedEvent::EnterLoop,
+        "leave-loop" => OwnedEvent::LeaveLoop,
+        "scale-change" => OwnedEvent::ScaleChange {
+            scale: parse(tokens)?,
+        },
+        "close" => OwnedEvent::Close,
+        "update" => OwnedEvent::Update,
+        "expose" => OwnedEvent::Expose {
+            rect: parse_rect(tokens)?,
+        },
+        "focus-in" => OwnedEvent::FocusIn {
+            mode: CrossingMode::from_raw(parse(tokens)?),
+        },
+        "focus-out" => OwnedEvent::FocusOut {
+            mode: CrossingMode::from_raw(parse(tokens)?),
+        },
+        "key-press" => OwnedEvent::KeyPress {
+            input: parse_input(tokens)?,
+            keycode: parse(tokens)?,
+            key: Key::from_raw(parse(tokens)?),
+        },
+        "key-release" => OwnedEvent::KeyRelease {
+            input: parse_input(tokens)?,
+            keycode: parse(tokens)?,
+            key: Key::from_raw(parse(tokens)?),
+        },
+        "key-text" => OwnedEvent::KeyText {
+            input: parse_input(tokens)?,
+            keycode: parse(tokens)?,
+            text: String::from_utf8(parse_bytes(tokens)?).ok()?,
+        },
+        "dead-key" => OwnedEvent::DeadKey {
+            input: parse_input(tokens)?,
+            keycode: parse(tokens)?,
+            accent: char::from_u32(parse(tokens)?)?,
+        },
+        "keyboard-input" => OwnedEvent::KeyboardInput {
+            input: parse_input(tokens)?,
+            keycode: parse(tokens)?,
+            key: Key::from_raw(parse(tokens)?),
+            text: {
+                let has_text = parse::<u8>(tokens)? != 0;
+                let text = String::from_utf8(parse_bytes(tokens)?).ok()?;
+                has_text.then_some(text)
+            },
+        },
+        "pointer-in" => OwnedEvent::PointerIn {
+            input: parse_input(tokens)?,
+            mode: CrossingMode::from_raw(parse(tokens)?),
+        },
+        "pointer-out" => OwnedEvent::PointerOut {
+            input: parse_input(tokens)?,
+            mode: CrossingMode::from_raw(parse(tokens)?),
+        },
+        "pointer-motion" => OwnedEvent::PointerMotion {
+            input: parse_input(tokens)?,
+        },
+        "button-press" => OwnedEvent::ButtonPress {
+            input: parse_input(tokens)?,
+            button: MouseButton::from_raw(parse(tokens)?),
+        },
+        "button-release" => OwnedEvent::ButtonRelease {
+            input: parse_input(tokens)?,
+            button: MouseButton::from_raw(parse(tokens)?),
+        },
+        "scroll" => OwnedEvent::Scroll {
+            input: parse_input(tokens)?,
+            direction: ScrollDirection::from_raw(parse(tokens)?),
+            dx: parse(tokens)?,
+            dy: parse(tokens)?,
+        },
+        "timer" => OwnedEvent::Timer { id: parse(tokens)? },
+        "client" => OwnedEvent::Client {
+            data: [parse(tokens)?, parse(tokens)?],
+        },
+        "clipboard" => OwnedEvent::Clipboard {
+            mime_type: String::from_utf8(parse_bytes(tokens)?).ok()?,
+            text: String::from_utf8(parse_bytes(tokens)?).ok()?,
+        },
+        "clipboard-data" => OwnedEvent::ClipboardData {
+            mime_type: String::from_utf8(parse_bytes(tokens)?).ok()?,
+            data: parse_bytes(tokens)?,
+        },
+        "clipboard-failed" => OwnedEvent::ClipboardFailed,
+        "end-frame" => OwnedEvent::EndFrame,
+        _ => return None,
+    };
+
+    match tokens.next() {
+        None => Some(event),
+        Some(_) => None,
+    }
+}
+
+fn parse<T: std::str::FromStr>(tokens: &mut SplitWhitespace) -> Option<T> {
+    tokens.next()?.parse().ok()
+}
+
+fn parse_rect(tokens: &mut SplitWhitespace) -> Option<Rect> {
+    Some(Rect {
+        x: parse(tokens)?,
+        y: parse(tokens)?,
+        w: parse(tokens)?,
+        h: parse(tokens)?,
+    })
+}
+
+fn parse_input(tokens: &mut SplitWhitespace) -> Option<EventInput> {
+    Some(EventInput {
+        time: WorldInstant::from_secs_f64(parse(tokens)?),
+        position: ViewPoint::new(parse(tokens)?, parse(tokens)?),
+        root_position: ScreenPoint::new(parse(tokens)?, parse(tokens)?),
+        mods: Modifiers::from_bits_retain(parse(tokens)?),
+        hint: parse::<u8>(tokens)? != 0,
+    })
+}
+
+fn parse_bytes(tokens: &mut SplitWhitespace) -> Option<Vec<u8>> {
+    let hex = tokens.next()?;
+    if hex == "-" {
+        return Some(Vec::new());
+    }
+
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,620 @@
+use crate::{Backend, Event, Key, Modifiers};
+use std::{fmt, str::FromStr};
+
+/// The modifiers that are part of a shortcut, lock keys are ignored when matching.
+const SHORTCUT_MODIFIERS: Modifiers = Modifiers::CTRL
+    .union(Modifiers::ALT)
+    .union(Modifiers::SHIFT)
+    .union(Modifiers::SUPER);
+
+/// A keyboard shortcut (accelerator), a key pressed while holding a set of modifiers.
+///
+/// Shortcuts can be parsed from strings like `"Primary+Shift+S"` or `"Ctrl+F5"`, see [`Shortcut::from_str`],
+/// and matched against [`Event::KeyPress`] with [`Shortcut::matches`].
+/// ```no_run
+/// # use pugl_rs::*;
+/// # let world = World::new_program().unwrap();
+/// let save_as: Shortcut = "Primary+Shift+S".parse().unwrap();
+/// let view = world.new_view(()).with_event_handler(move |view, event| {
+///     if save_as.matches(&event) {
+///         // ...
+///     }
+/// });
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Shortcut {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+/// Shortcut parsing error, see [`Shortcut::from_str`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParseShortcutError {
+    /// The string doesn't contain a key
+    MissingKey,
+    /// The modifier is not known
+    UnknownModifier(String),
+    /// The key is not known
+    UnknownKey(String),
+}
+
+impl Modifiers {
+    /// The modifier used for the shortcuts of common commands (like copy or save):
+    /// [`Modifiers::SUPER`] (Command) on MacOS, and [`Modifiers::CTRL`] on other platforms.
+    pub const PRIMARY: Modifiers = if cfg!(target_os = "macos") {
+        Modifiers::SUPER
+    } else {
+        Modifiers::CTRL
+    };
+}
+
+impl Shortcut {
+    /// Creates a shortcut for the key and the modifiers.
+    pub fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self {
+            modifiers: modifiers & SHORTCUT_MODIFIERS,
+            key: normalize_key(key),
+        }
+    }
+
+    /// Returns `true` if the key and the modifiers are the ones of the shortcut.
+    ///
+    /// Lock keys (like caps lock) are ignored, and character keys are compared case-insensitively.
+    pub fn matches_key(&self, key: Key, modifiers: Modifiers) -> bool {
+        normalize_key(self.key) == normalize_key(key)
+            && self.modifiers & SHORTCUT_MODIFIERS == modifiers & SHORTCUT_MODIFIERS
+    }
+
+    /// Returns `true` if the event is a [`Event::KeyPress`] of the shortcut, see [`Shortcut::matches_key`].
+    pub fn matches<B: Backend>(&self, event: &Event<B>) -> bool {
+        match event {
+            Event::KeyPress { input, key, .. } => self.matches_key(*key, input.mods),
+            _ => false,
+        }
+    }
+
+    /// Returns the portable name of the shortcut, like `"Ctrl+Shift+S"`, which can be parsed back on every platform.
+    ///
+    /// Unlike the [`Display`](fmt::Display) implementation, this never uses the MacOS glyphs.
+    pub fn name(&self) -> String {
+        let modifiers = self.modifiers & SHORTCUT_MODIFIERS;
+        if modifiers.is_empty() {
+            self.key.name().into_owned()
+        } else {
+            format!("{}+{}", modifiers.name(), self.key.name())
+        }
+    }
+}
+
+impl From<Key> for Shortcut {
+    fn from(key: Key) -> Self {
+        Self::new(Modifiers::empty(), key)
+    }
+}
+
+/// Formats the shortcut in the style of the platform: `"⌃⌥⇧⌘S"` on MacOS and `"Ctrl+Alt+Shift+Super+S"` on other platforms.
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if cfg!(target_os = "macos") {
+            for (modifier, glyph) in GLYPHS {
+                if self.modifiers.contains(modifier) {
+                    f.write_str(glyph)?;
+                }
+            }
+
+            f.write_str(&self.key.name())
+        } else {
+            f.write_str(&self.name())
+        }
+    }
+}
+
+/// Parses a shortcut from modifier and key names joined with `+`, like `"Primary+Shift+S"`.
+///
+/// - The names are case-insensitive, and the key names are the ones returned by [`Key::name`] (and a single character for character keys).
+/// - The modifiers are `Ctrl` (or `Control`), `Alt` (or `Option`), `Shift`, `Super` (or `Cmd`, `Command`, `Meta`, `Win`)
+///   and `Primary`, which is [`Modifiers::PRIMARY`].
+/// - The MacOS glyphs (`⌃⌥⇧⌘`) are accepted as modifier prefixes, so the output of the [`Display`](fmt::Display) implementation can be parsed back.
+impl FromStr for Shortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut string = string.trim();
+        let mut modifiers = Modifiers::empty();
+
+        while let Some((modifier, glyph)) = GLYPHS
+            .iter()
+            .find(|(_, glyph)| string.starts_with(glyph) && string.len() > glyph.len())
+        {
+            modifiers |= *modifier;
+            string = &string[glyph.len()..];
+        }
+
+        // the `+` key itself, like in "Ctrl++"
+        let (names, key) = match string.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(rest), "+")
+            }
+            _ => match string.rsplit_once('+') {
+                Some((names, key)) => (names, key),
+                None => ("", string),
+            },
+        };
+
+        for name in names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= parse_modifier(name.trim())
+                .ok_or_else(|| ParseShortcutError::UnknownModifier(name.trim().to_owned()))?;
+        }
+
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(ParseShortcutError::MissingKey);
+        }
+
+        let key =
+            Key::from_name(key).ok_or_else(|| ParseShortcutError::UnknownKey(key.to_owned()))?;
+        Ok(Self::new(modifiers, key))
+    }
+}
+
+impl Key {
+    /// Returns the key with the given name, see [`Key::name`].
+    ///
+    /// The name is case-insensitive, and any single character is accepted as a character key.
+    /// A few common abbreviations (like `Esc`, `Del` or `PgUp`) are also accepted.
+    pub fn from_name(name: &str) -> Option<Key> {
+        let mut chars = name.chars();
+        if let (Some(char), None) = (chars.next(), chars.next()) {
+            return Some(normalize_key(Key::Char(char)));
+        }
+
+        let alias = match name.to_ascii_lowercase().as_str() {
+            "esc" => Some(Key::Char('\x1b')),
+            "return" => Some(Key::Char('\r')),
+            "del" => Some(Key::Char('\x7f')),
+            "backspace" => Some(Key::Char('\x08')),
+            "pgup" => Some(Key::PageUp),
+            "pgdn" => Some(Key::PageDown),
+            "ins" => Some(Key::Insert),
+            "plus" => Some(Key::Char('+')),
+            _ => None,
+        };
+
+        alias.or_else(|| {
+            NAMED_KEYS
+                .iter()
+                .copied()
+                .find(|key| key.name().eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+impl std::error::Error for ParseShortcutError {}
+impl fmt::Display for ParseShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseShortcutError::MissingKey => write!(f, "shortcut has no key"),
+            ParseShortcutError::UnknownModifier(name) => write!(f, "unknown modifier: {name}"),
+            ParseShortcutError::UnknownKey(name) => write!(f, "unknown key: {name}"),
+        }
+    }
+}
+
+const GLYPHS: [(Modifiers, &str); 4] = [
+    (Modifiers::CTRL, "⌃"),
+    (Modifiers::ALT, "⌥"),
+    (Modifiers::SHIFT, "⇧"),
+    (Modifiers::SUPER, "⌘"),
+];
+
+/// Every key that has a name other than its character.
+const NAMED_KEYS: &[Key] = &[
+    Key::Char(' '),
+    Key::Char('\t'),
+    Key::Char('\r'),
+    Key::Char('\x08'),
+    Key::Char('\x1b'),
+    Key::Char('\x7f'),
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::Left,
+    Key::Up,
+    Key::Right,
+    Key::Down,
+    Key::PageUp,
+    Key::PageDown,
+    Key::Home,
+    Key::End,
+    Key::Insert,
+    Key::ShiftL,
+    Key::ShiftR,
+    Key::CtrlL,
+    Key::CtrlR,
+    Key::AltL,
+    Key::AltR,
+    Key::SuperL,
+    Key::SuperR,
+    Key::Menu,
+    Key::CapsLock,
+    Key::ScrollLock,
+    Key::NumLock,
+    Key::PrintScreen,
+    Key::Pause,
+    Key::Numpad0,
+    Key::Numpad1,
+    Key::Numpad2,
+    Key::Numpad3,
+    Key::Numpad4,
+    Key::Numpad5,
+    Key::Numpad6,
+    Key::Numpad7,
+    Key::Numpad8,
+    Key::Numpad9,
+    Key::NumpadAdd,
+    Key::NumpadSubtract,
+    Key::NumpadMultiply,
+    Key::NumpadDivide,
+    Key::NumpadDecimal,
+    Key::NumpadEnter,
+    Key::NumpadEqual,
+    Key::NumpadUp,
+    Key::NumpadDown,
+    Key::NumpadLeft,
+    Key::NumpadRight,
+    Key::NumpadHome,
+    Key::NumpadEnd,
+    Key::NumpadPageUp,
+    Key::NumpadPageDown,
+    Key::NumpadInsert,
+    Key::NumpadDelete,
+    Key::NumpadSeparator,
+    Key::NumpadClear,
+];
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Modifiers::CTRL,
+        "alt" | "option" => Modifiers::ALT,
+        "shift" => Modifiers::SHIFT,
+        "super" | "cmd" | "command" | "meta" | "win" => Modifiers::SUPER,
+        "primary" => Modifiers::PRIMARY,
+        _ => return None,
+    })
+}
+
+/// Character keys are stored lowercase, and both line endings are the enter key.
+fn normalize_key(key: Key) -> Key {
+    match key {
+        Key::Char('\n') => Key::Char('\r'),
+        Key::Char(char) => Key::Char(char.to_lowercase().next().unwrap_or(char)),
+        key => key,
+    }
+}
+
+/// Identifies a shortcut registered in a [`ShortcutRegistry`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct ShortcutId(u64);
+
+/// Shortcut registration error, returned when an enabled shortcut of a [`ShortcutRegistry`] already uses the key combination.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShortcutConflict {
+    /// The shortcut that is already registered
+    pub existing: ShortcutId,
+}
+
+/// A set of keyboard shortcuts mapped to actions, so that key bindings don't have to live in a giant `match` in the event handler.
+///
+/// The actions can be anything, like an enum of commands or boxed callbacks. Usually a registry is kept per view,
+/// and [`Event::KeyPress`] events are looked up with [`ShortcutRegistry::handle`] (or [`ShortcutRegistry::handle_mut`]).
+/// ```no_run
+/// # use pugl_rs::*;
+/// # let world = World::new_program().unwrap();
+/// enum Command { Save, Undo }
+///
+/// let mut shortcuts = ShortcutRegistry::new();
+/// shortcuts.register("Primary+S".parse().unwrap(), Command::Save).unwrap();
+/// shortcuts.register("Primary+Z".parse().unwrap(), Command::Undo).unwrap();
+///
+/// let view = world.new_view(()).with_event_handler(move |view, event| {
+///     match shortcuts.handle(&event) {
+///         Some(Command::Save) => { /* ... */ }
+///         Some(Command::Undo) => { /* ... */ }
+///         None => { /* not a shortcut */ }
+///     }
+/// });
+/// ```
+/// Two enabled shortcuts can't use the same key combination, but a disabled shortcut can,
+/// which allows switching between bindings (for example depending on the focused widget) with [`ShortcutRegistry::set_enabled`].
+#[derive(Clone, Debug)]
+pub struct ShortcutRegistry<T> {
+    entries: Vec<ShortcutEntry<T>>,
+    next_id: u64,
+}
+
+#[derive(Clone, Debug)]
+struct ShortcutEntry<T> {
+    id: ShortcutId,
+    shortcut: Shortcut,
+    action: T,
+    enabled: bool,
+}
+
+impl<T> ShortcutRegistry<T> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Registers an enabled shortcut.
+    ///
+    /// Returns an error if an enabled shortcut already uses the same key combination.
+    pub fn register(
+        &mut self,
+        shortcut: Shortcut,
+        action: T,
+    ) -> Result<ShortcutId, ShortcutConflict> {
+        let shortcut = Shortcut::new(shortcut.modifiers, shortcut.key);
+        if let Some(existing) = self.find(&shortcut) {
+            return Err(ShortcutConflict {
+                existing: existing.id,
+            });
+        }
+
+        Ok(self.insert(shortcut, action, true))
+    }
+
+    /// Registers a disabled shortcut, which can be enabled later with [`ShortcutRegistry::set_enabled`].
+    ///
+    /// Disabled shortcuts don't conflict with other shortcuts, so this can't fail.
+    pub fn register_disabled(&mut self, shortcut: Shortcut, action: T) -> ShortcutId {
+        let shortcut = Shortcut::new(shortcut.modifiers, shortcut.key);
+        self.insert(shortcut, action, false)
+    }
+
+    /// Adds a shortcut without checking for conflicts.
+    fn insert(&mut self, shortcut: Shortcut, action: T, enabled: bool) -> ShortcutId {
+        let id = ShortcutId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(ShortcutEntry {
+            id,
+            shortcut,
+            action,
+            enabled,
+        });
+
+        id
+    }
+
+    /// Removes a shortcut and returns its action, or `None` if it is not registered.
+    pub fn unregister(&mut self, id: ShortcutId) -> Option<T> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index).action)
+    }
+
+    /// Enables or disables a shortcut.
+    ///
+    /// Returns `false` if the shortcut is not registered, or if it can't be enabled because another enabled shortcut uses the same key combination.
+    pub fn set_enabled(&mut self, id: ShortcutId, enabled: bool) -> bool {
+        let Some(index) = self.entries.iter().position(|entry| entry.id == id) else {
+            return false;
+        };
+
+        if enabled
+            && let Some(existing) = self.find(&self.entries[index].shortcut)
+            && existing.id != id
+        {
+            return false;
+        }
+
+        self.entries[index].enabled = enabled;
+        true
+    }
+
+    /// Returns `true` if the shortcut is registered and enabled.
+    pub fn is_enabled(&self, id: ShortcutId) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.id == id && entry.enabled)
+    }
+
+    /// Returns the enabled shortcut that uses the same key combination, see [`ShortcutConflict`].
+    pub fn conflict(&self, shortcut: Shortcut) -> Option<ShortcutId> {
+        self.find(&Shortcut::new(shortcut.modifiers, shortcut.key))
+            .map(|entry| entry.id)
+    }
+
+    /// Returns the shortcut and the action of a registered shortcut.
+    pub fn get(&self, id: ShortcutId) -> Option<(Shortcut, &T)> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| (entry.shortcut, &entry.action))
+    }
+
+    /// Returns the action of the enabled shortcut for the key and the modifiers.
+    pub fn lookup(&self, key: Key, modifiers: Modifiers) -> Option<&T> {
+        self.entries
+            .iter()
+            .find(|entry| entry.enabled && entry.shortcut.matches_key(key, modifiers))
+            .map(|entry| &entry.action)
+    }
+
+    /// Returns the action of the enabled shortcut for the key and the modifiers.
+    pub fn lookup_mut(&mut self, key: Key, modifiers: Modifiers) -> Option<&mut T> {
+        self.entries
+            .iter_mut()
+            .find(|entry| entry.enabled && entry.shortcut.matches_key(key, modifiers))
+            .map(|entry| &mut entry.action)
+    }
+
+    /// Returns the action of the enabled shortcut if the event is a [`Event::KeyPress`] of it.
+    pub fn handle<B: Backend>(&self, event: &Event<B>) -> Option<&T> {
+        match event {
+            Event::KeyPress { input, key, .. } => self.lookup(*key, input.mods),
+            _ => None,
+        }
+    }
+
+    /// Returns the action of the enabled shortcut if the event is a [`Event::KeyPress`] of it, for actions like `Box<dyn FnMut()>`.
+    pub fn handle_mut<B: Backend>(&mut self, event: &Event<B>) -> Option<&mut T> {
+        match event {
+            Event::KeyPress { input, key, .. } => self.lookup_mut(*key, input.mods),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the registered shortcuts, for example for listing the key bindings in a preferences dialog.
+    pub fn iter(&self) -> impl Iterator<Item = (ShortcutId, Shortcut, &T)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.id, entry.shortcut, &entry.action))
+    }
+
+    fn find(&self, shortcut: &Shortcut) -> Option<&ShortcutEntry<T>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.enabled && entry.shortcut == *shortcut)
+    }
+}
+
+impl<T> Default for ShortcutRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::error::Error for ShortcutConflict {}
+impl fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the key combination is already used by another shortcut")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(string: &str) -> Result<Shortcut, ParseShortcutError> {
+        string.parse()
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for string in [
+            "Ctrl+Shift+S",
+            "Alt+F5",
+            "Super+Enter",
+            "Ctrl+Alt+Shift+Super+Left",
+            "Space",
+        ] {
+            let shortcut = parse(string).unwrap();
+            assert_eq!(shortcut.name(), string);
+            assert_eq!(parse(&shortcut.name()), Ok(shortcut));
+            assert_eq!(parse(&shortcut.to_string()), Ok(shortcut));
+        }
+    }
+
+    #[test]
+    fn glyphs_are_parsed() {
+        assert_eq!(
+            parse("⌃⌥⇧⌘S"),
+            Ok(Shortcut::new(
+                Modifiers::CTRL | Modifiers::ALT | Modifiers::SHIFT | Modifiers::SUPER,
+                Key::Char('s')
+            ))
+        );
+        assert_eq!(
+            parse("⌘+"),
+            Ok(Shortcut::new(Modifiers::SUPER, Key::Char('+')))
+        );
+        // a glyph on its own is the key
+        assert_eq!(
+            parse("⌘"),
+            Ok(Shortcut::new(Modifiers::empty(), Key::Char('⌘')))
+        );
+    }
+
+    #[test]
+    fn plus_key() {
+        let ctrl_plus = Shortcut::new(Modifiers::CTRL, Key::Char('+'));
+        assert_eq!(parse("Ctrl++"), Ok(ctrl_plus));
+        assert_eq!(parse("Ctrl+Plus"), Ok(ctrl_plus));
+        assert_eq!(parse("+"), Ok(Shortcut::from(Key::Char('+'))));
+        assert_eq!(parse(&ctrl_plus.name()), Ok(ctrl_plus));
+        assert_eq!(
+            parse("Ctrl+Shift++"),
+            Ok(Shortcut::new(
+                Modifiers::CTRL | Modifiers::SHIFT,
+                Key::Char('+')
+            ))
+        );
+    }
+
+    #[test]
+    fn primary_is_the_platform_modifier() {
+        let shortcut = parse("Primary+S").unwrap();
+        assert_eq!(shortcut.modifiers, Modifiers::PRIMARY);
+        if cfg!(target_os = "macos") {
+            assert_eq!(shortcut.name(), "Super+S");
+        } else {
+            assert_eq!(shortcut.name(), "Ctrl+S");
+        }
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        assert_eq!(parse("ctrl+shift+s"), parse("Ctrl+Shift+S"));
+        assert_eq!(
+            parse("CMD+pgup"),
+            Ok(Shortcut::new(Modifiers::SUPER, Key::PageUp))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse(""), Err(ParseShortcutError::MissingKey));
+        assert_eq!(parse("Ctrl+ "), Err(ParseShortcutError::MissingKey));
+        assert_eq!(
+            parse("Hyper+S"),
+            Err(ParseShortcutError::UnknownModifier("Hyper".to_owned()))
+        );
+        assert_eq!(
+            parse("Ctrl+Foo"),
+            Err(ParseShortcutError::UnknownKey("Foo".to_owned()))
+        );
+    }
+
+    #[test]
+    fn registry_conflicts() {
+        let save = parse("Ctrl+S").unwrap();
+        let mut registry = ShortcutRegistry::new();
+        let first = registry.register(save, 1).unwrap();
+        assert_eq!(
+            registry.register(parse("ctrl+s").unwrap(), 2),
+            Err(ShortcutConflict { existing: first })
+        );
+
+        // disabled shortcuts can share the key combination, but not be enabled at the same time
+        let second = registry.register_disabled(save, 2);
+        assert!(!registry.set_enabled(second, true));
+        assert!(registry.set_enabled(first, false));
+        assert!(registry.set_enabled(second, true));
+        assert_eq!(
+            registry.lookup(Key::Char('S'), Modifiers::CTRL | Modifiers::CAPS_LOCK),
+            Some(&2)
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::OwnedEvent;
+use futures_core::Stream;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+// doc only import
+#[allow(unused_imports)]
+use crate::{View, World};
+
+/// A stream of events received by a view.
+///
+/// See [`View::events`] for more info.
+pub struct EventStream(pub(crate) Arc<Mutex<EventQueue>>);
+
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    events: VecDeque<OwnedEvent>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: OwnedEvent) {
+        self.events.push_back(event);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Stream for EventStream {
+    type Item = OwnedEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut queue = self.0.lock().unwrap();
+        if let Some(event) = queue.events.pop_front() {
+            Poll::Ready(Some(event))
+        } else if queue.closed {
+            Poll::Ready(None)
+        } else {
+            queue.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl std::fmt::Debug for EventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventStream").finish_non_exhaustive()
+    }
+}
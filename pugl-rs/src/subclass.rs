@@ -0,0 +1,184 @@
+//! Window subclassing for [`View::set_message_hook`](crate::View::set_message_hook).
+//!
+//! `SetWindowSubclass` chains the window procedure of `pugl` instead of replacing it, and keeps working if `pugl` or the host subclass the window too.
+
+use crate::{Backend, MouseCursor, WindowMessage, sys, view::ViewState};
+use std::{ffi::c_void, ptr::null_mut, sync::PoisonError};
+
+type SubclassProc = unsafe extern "system" fn(isize, u32, usize, isize, usize, usize) -> isize;
+
+#[link(name = "comctl32")]
+unsafe extern "system" {
+    fn SetWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize, data: usize) -> i32;
+    fn RemoveWindowSubclass(hwnd: isize, proc: SubclassProc, id: usize) -> i32;
+    fn DefSubclassProc(hwnd: isize, message: u32, wparam: usize, lparam: isize) -> isize;
+}
+
+#[link(name = "user32")]
+unsafe extern "system" {
+    fn LoadImageW(
+        instance: isize,
+        name: *const u16,
+        kind: u32,
+        width: i32,
+        height: i32,
+        flags: u32,
+    ) -> isize;
+    fn LoadCursorW(instance: isize, name: *const u16) -> isize;
+    fn DestroyCursor(cursor: isize) -> i32;
+    fn GetCursor() -> isize;
+    fn SetCursor(cursor: isize) -> isize;
+}
+
+#[link(name = "advapi32")]
+unsafe extern "system" {
+    fn RegGetValueW(
+        key: isize,
+        sub_key: *const u16,
+        value: *const u16,
+        flags: u32,
+        kind: *mut u32,
+        data: *mut c_void,
+        size: *mut u32,
+    ) -> i32;
+}
+
+/// The subclass is identified by its procedure and this id, which only has to match between installing and removing it.
+const SUBCLASS_ID: usize = 1;
+const WM_NCDESTROY: u32 = 0x0082;
+const WM_SETCURSOR: u32 = 0x0020;
+const HTCLIENT: isize = 1;
+const IMAGE_CURSOR: u32 = 2;
+const HKEY_CURRENT_USER: isize = 0x8000_0001u32 as i32 as isize;
+const RRF_RT_REG_DWORD: u32 = 0x10;
+const ERROR_SUCCESS: i32 = 0;
+
+/// A system cursor loaded at the size of [`View::cursor_size`](crate::View::cursor_size).
+///
+/// `pugl` loads the system cursors with `LoadCursorW`, which always returns them at the size of the primary monitor,
+/// so the subclass sets this one instead while the pointer is in the client area.
+pub struct Cursor {
+    handle: isize,
+    id: u16,
+}
+
+impl Cursor {
+    pub fn load(cursor: MouseCursor, size: u32) -> Option<Self> {
+        // the IDC_* resources of the system
+        let id = match cursor {
+            MouseCursor::Arrow => 32512,
+            MouseCursor::Caret => 32513,
+            MouseCursor::Crosshair => 32515,
+            MouseCursor::Hand => 32649,
+            MouseCursor::NotAllowed => 32648,
+            MouseCursor::Scroll => 32646,
+            MouseCursor::ResizeWE => 32644,
+            MouseCursor::ResizeNS => 32645,
+            MouseCursor::ResizeNWSE => 32642,
+            MouseCursor::ResizeNESW => 32643,
+        };
+
+        // not shared, shared cursors are cached at the size they were first loaded with
+        let size = size as i32;
+        let handle =
+            unsafe { LoadImageW(0, id as usize as *const u16, IMAGE_CURSOR, size, size, 0) };
+        (handle != 0).then_some(Self { handle, id })
+    }
+
+    /// Shows the cursor right away if the pointer currently shows the unscaled one set by `pugl`,
+    /// instead of waiting for the pointer to move.
+    pub fn show_if_current(&self) {
+        unsafe {
+            if GetCursor() == LoadCursorW(0, self.id as usize as *const u16) {
+                SetCursor(self.handle);
+            }
+        }
+    }
+}
+
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        unsafe {
+            // the cursor can't be destroyed while it's shown
+            if GetCursor() == self.handle {
+                SetCursor(LoadCursorW(0, self.id as usize as *const u16));
+            }
+            DestroyCursor(self.handle);
+        }
+    }
+}
+
+/// The cursor size set in the accessibility settings, in pixels at 100% scale.
+pub fn base_cursor_size() -> u32 {
+    let sub_key: Vec<u16> = "Control Panel\\Cursors\0".encode_utf16().collect();
+    let value: Vec<u16> = "CursorBaseSize\0".encode_utf16().collect();
+    let mut data = 0u32;
+    let mut size = size_of::<u32>() as u32;
+
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            sub_key.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            null_mut(),
+            &mut data as *mut u32 as *mut c_void,
+            &mut size,
+        )
+    };
+
+    match (status, data) {
+        (ERROR_SUCCESS, 1..) => data,
+        _ => 32,
+    }
+}
+
+/// Subclasses the window, returns `false` if it failed. Installing the subclass again only updates the view.
+pub unsafe fn install<B: Backend>(hwnd: isize, view: *mut sys::PuglView) -> bool {
+    unsafe { SetWindowSubclass(hwnd, subclass_proc::<B>, SUBCLASS_ID, view as usize) != 0 }
+}
+
+pub unsafe fn remove<B: Backend>(hwnd: isize) {
+    unsafe {
+        RemoveWindowSubclass(hwnd, subclass_proc::<B>, SUBCLASS_ID);
+    }
+}
+
+unsafe extern "system" fn subclass_proc<B: Backend>(
+    hwnd: isize,
+    message: u32,
+    wparam: usize,
+    lparam: isize,
+    _id: usize,
+    view: usize,
+) -> isize {
+    unsafe {
+        // the subclass must be removed before the window is gone, `pugl` frees the view after that
+        if message == WM_NCDESTROY {
+            remove::<B>(hwnd);
+            return DefSubclassProc(hwnd, message, wparam, lparam);
+        }
+
+        let state = ViewState::<B>::from_raw(view as *mut sys::PuglView);
+        let message = WindowMessage {
+            hwnd,
+            message,
+            wparam,
+            lparam,
+        };
+
+        if let Some(result) = state.call_message_hook(message) {
+            return result;
+        }
+
+        if message.message == WM_SETCURSOR && lparam & 0xffff == HTCLIENT {
+            let cursor = state.cursor.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(cursor) = &*cursor {
+                SetCursor(cursor.handle);
+                return 1;
+            }
+        }
+
+        DefSubclassProc(hwnd, message.message, wparam, lparam)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{Backend, Event, UnrealizedView, View, ViewError, World};
+
+type Configure<B> = Box<dyn Fn(UnrealizedView<B>) -> UnrealizedView<B>>;
+type HandlerFactory<B> = Box<dyn FnMut(WindowId) -> Box<dyn FnMut(&View<B>, Event<B>) + Send>>;
+
+/// Identifies a window spawned by a [`ViewTemplate`], unique among the windows of the template.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct WindowId(pub u64);
+
+/// A view configuration for spawning any number of similar windows, like in document-per-window applications.
+///
+/// Every spawned window gets its own event handler, created by the factory passed to [`ViewTemplate::new`] with the id of the window.
+/// ```no_run
+/// # use pugl_rs::*;
+/// # let world = World::new_program().unwrap();
+/// let mut documents = ViewTemplate::new((), |id| {
+///     move |view: &View<()>, event: Event<()>| {
+///         // ...
+///     }
+/// })
+/// .with_config(|view| view.with_size(800, 600).with_resizable(true));
+///
+/// let (first, first_view) = documents.spawn(&world).unwrap();
+/// let (second, second_view) = documents.spawn(&world).unwrap();
+/// ```
+pub struct ViewTemplate<B: Backend + Clone> {
+    backend: B,
+    configure: Configure<B>,
+    factory: HandlerFactory<B>,
+    next_id: u64,
+}
+
+impl<B: Backend + Clone + 'static> ViewTemplate<B> {
+    /// Creates a template for views of the backend, with event handlers created by `factory`.
+    pub fn new<H>(backend: B, mut factory: impl FnMut(WindowId) -> H + 'static) -> Self
+    where
+        H: FnMut(&View<B>, Event<B>) + Send + 'static,
+    {
+        Self {
+            backend,
+            configure: Box::new(|view| view),
+            factory: Box::new(move |id| Box::new(factory(id))),
+            next_id: 0,
+        }
+    }
+
+    /// Set the configuration applied to every spawned view (like the size or the title), before the event handler is set.
+    pub fn with_config(
+        mut self,
+        configure: impl Fn(UnrealizedView<B>) -> UnrealizedView<B> + 'static,
+    ) -> Self {
+        self.configure = Box::new(configure);
+        self
+    }
+
+    /// Creates a configured view with its event handler, without realizing it.
+    pub fn spawn_unrealized(&mut self, world: &World) -> (WindowId, UnrealizedView<B>) {
+        let id = WindowId(self.next_id);
+        self.next_id += 1;
+
+        let view = (self.configure)(world.new_view(self.backend.clone()));
+        (id, view.with_event_handler((self.factory)(id)))
+    }
+
+    /// Creates, realizes and shows a configured view with its event handler.
+    ///
+    /// The id is used even if the view fails to realize, so ids are never reused.
+    pub fn spawn(&mut self, world: &World) -> Result<(WindowId, View<B>), ViewError> {
+        let (id, view) = self.spawn_unrealized(world);
+        let view = view.realize()?;
+        // a view that fails to show is still usable, and can be shown again later
+        let _ = view.show();
+        Ok((id, view))
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{Backend, Status, TimerId, View, sys, view::ViewState};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+impl<B: Backend> View<B> {
+    /// Activate a repeating timer event that is scheduled on a fixed grid (`start + n * interval`), like [`FramePacer`](crate::FramePacer).
+    ///
+    /// Unlike [`View::start_timer`], the next event is scheduled from the start of the timer instead of from when the previous event was handled,
+    /// so long-running animation or metering timers don't fall behind. If the event loop is late by more than one interval,
+    /// the missed events are skipped and only one [`Event::Timer`](crate::Event::Timer) is sent. The resolution is the same as for [`View::start_timer`].
+    ///
+    /// If the given timer already exists, it is replaced.
+    pub fn start_fixed_rate_timer(&self, id: TimerId, interval: Duration) -> Result<(), Status> {
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        unsafe {
+            state
+                .timers
+                .schedule(self.view, id, Instant::now(), Some(interval), interval)
+        }
+    }
+
+    /// Activate a one-shot timer that sends one [`Event::Timer`](crate::Event::Timer) at the deadline, and then stops.
+    ///
+    /// If the deadline has already passed, the event is sent as soon as possible.
+    /// If the given timer already exists, it is replaced.
+    pub fn start_deadline_timer(&self, id: TimerId, deadline: Instant) -> Result<(), Status> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        unsafe {
+            state
+                .timers
+                .schedule(self.view, id, deadline, None, timeout)
+        }
+    }
+}
+
+/// Timers started with [`View::start_fixed_rate_timer`] or [`View::start_deadline_timer`], which are rescheduled by `pugl-rs` after every event.
+#[derive(Default)]
+pub(crate) struct Timers(Mutex<Vec<ScheduledTimer>>);
+
+struct ScheduledTimer {
+    id: TimerId,
+    /// The start of the grid, or the deadline of a one-shot timer
+    start: Instant,
+    /// `None` for a one-shot timer
+    interval: Option<Duration>,
+    /// The number of the last tick that was delivered
+    tick: u64,
+}
+
+impl Timers {
+    /// Starts the `pugl` timer of a scheduled timer, replacing a scheduled timer with the same id.
+    pub unsafe fn schedule(
+        &self,
+        view: *mut sys::PuglView,
+        id: TimerId,
+        start: Instant,
+        interval: Option<Duration>,
+        timeout: Duration,
+    ) -> Result<(), Status> {
+        self.unschedule(id);
+
+        unsafe {
+            Status::from_raw(sys::puglStartTimer(view, id, timer_timeout(timeout)))
+                .into_result()?;
+        }
+
+        self.0.lock().unwrap().push(ScheduledTimer {
+            id,
+            start,
+            interval,
+            tick: 0,
+        });
+        Ok(())
+    }
+
+    /// Forgets a scheduled timer, so it is not rescheduled anymore.
+    pub fn unschedule(&self, id: TimerId) {
+        self.0.lock().unwrap().retain(|timer| timer.id != id);
+    }
+
+    /// Restarts the `pugl` timer of a scheduled timer for its next tick.
+    ///
+    /// Returns `false` if the timer fired early, in which case the event is not delivered.
+    pub unsafe fn reschedule(&self, view: *mut sys::PuglView, id: TimerId) -> bool {
+        let mut timers = self.0.lock().unwrap();
+        let Some(index) = timers.iter().position(|timer| timer.id == id) else {
+            return true;
+        };
+
+        let now = Instant::now();
+        let timer = &mut timers[index];
+        let (deliver, next) = match timer.interval {
+            None if now < timer.start => (false, Some(timer.start)),
+            None => (true, None),
+            Some(interval) => {
+                let elapsed = now.duration_since(timer.start).as_nanos();
+                let tick = (elapsed / interval.as_nanos().max(1)) as u64;
+                let deliver = tick > timer.tick;
+                timer.tick = timer.tick.max(tick);
+
+                let next = interval.as_nanos() * (timer.tick as u128 + 1);
+                (
+                    deliver,
+                    Some(timer.start + Duration::from_nanos(next as u64)),
+                )
+            }
+        };
+
+        unsafe {
+            match next {
+                Some(next) => {
+                    let timeout = timer_timeout(next.saturating_duration_since(now));
+                    sys::puglStartTimer(view, id, timeout);
+                }
+                None => {
+                    timers.remove(index);
+                    sys::puglStopTimer(view, id);
+                }
+            }
+        }
+
+        deliver
+    }
+}
+
+/// The timeout passed to `puglStartTimer`, at least 1ms so an overdue timer doesn't spin.
+fn timer_timeout(timeout: Duration) -> f64 {
+    timeout.max(Duration::from_millis(1)).as_secs_f64()
+}
@@ -1,15 +1,29 @@
+#[cfg(target_os = "windows")]
+use crate::subclass;
+#[cfg(target_os = "linux")]
+use crate::xembed;
 use crate::{
-    Backend, Event, MouseCursor, Rect, TimerId, ViewStyle, ViewType, World, WorldInner, sys,
+    Backend, BackendKind, ClipboardContents, ClipboardPaste, Damage, Event, EventInput,
+    FrameHistory, FramePacer, FrameStats, Key, MouseCursor, OwnedEvent, PasteSlot, Rect,
+    ScrollConfig, Status, TimerId, ViewStyle, ViewType, World, WorldInner, dead_key,
+    display_link::{DISPLAY_LINK_EVENT, DisplayLinkState},
+    is_main_thread, sys, take_lookup_text,
+    timer::Timers,
+    with_c_str,
 };
 use std::{
-    ffi::CString,
+    collections::VecDeque,
+    ffi::CStr,
     fmt,
     marker::PhantomData,
-    mem::ManuallyDrop,
-    panic::{AssertUnwindSafe, catch_unwind},
+    mem::{ManuallyDrop, replace, take},
+    panic::{AssertUnwindSafe, catch_unwind, resume_unwind},
     ptr::null_mut,
-    sync::{Arc, Mutex},
-    time::Duration,
+    sync::{
+        Arc, Mutex, PoisonError, Weak,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 /// A view that is not yet realized.
@@ -26,6 +40,30 @@ pub struct View<B: Backend> {
     pub(crate) phantom: PhantomData<B>,
 }
 
+/// A message of the window of a view, see [`View::set_message_hook`].
+///
+/// The fields are the arguments of the window procedure, see the documentation of `WNDPROC`.
+#[cfg(target_os = "windows")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WindowMessage {
+    pub hwnd: isize,
+    pub message: u32,
+    pub wparam: usize,
+    pub lparam: isize,
+}
+
+/// What happens when a view receives [`Event::Close`] (for example when the user clicks the close button), see [`UnrealizedView::with_close_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ClosePolicy {
+    /// Nothing happens, the event handler decides what to do (the default)
+    #[default]
+    Ignore,
+    /// The view is hidden, and can be shown again
+    Hide,
+    /// The view is unrealized, which drops its event handlers. The [`View`] can only be dropped afterwards
+    Unrealize,
+}
+
 /// Represents a parent window for a view.
 ///
 /// A view can either have a parent (for embedding) or a transient parent (for top-level windows like dialogs), but not both.
@@ -56,16 +94,230 @@ impl NativeView {
     pub unsafe fn from_raw(ptr: usize) -> Self {
         Self { ptr }
     }
+
+    /// Constructs a `NativeView` from a [`raw_window_handle::RawWindowHandle`], as handed out by plugin hosts and windowing libraries.
+    ///
+    /// Returns `None` if the handle is not supported on the current platform (supported handles are `Xlib` and `Xcb` on X11,
+    /// `Win32` on Windows and `AppKit` on MacOS).
+    ///
+    /// # Safety
+    /// The handle must refer to a valid window.
+    #[cfg(feature = "raw-window-handle")]
+    pub unsafe fn from_raw_window_handle(
+        handle: raw_window_handle::RawWindowHandle,
+    ) -> Option<Self> {
+        use raw_window_handle::RawWindowHandle;
+
+        let ptr = match handle {
+            #[cfg(target_os = "linux")]
+            RawWindowHandle::Xlib(handle) => handle.window as usize,
+            #[cfg(target_os = "linux")]
+            RawWindowHandle::Xcb(handle) => handle.window.get() as usize,
+            #[cfg(target_os = "windows")]
+            RawWindowHandle::Win32(handle) => handle.hwnd.get() as usize,
+            #[cfg(target_os = "macos")]
+            RawWindowHandle::AppKit(handle) => handle.ns_view.as_ptr() as usize,
+            _ => return None,
+        };
+
+        Some(Self { ptr })
+    }
 }
 
 unsafe impl Send for NativeView {}
 unsafe impl Sync for NativeView {}
 
-// TODO: verify that these are correct
-// pugl docs have no thread safety info
+/// A non-owning handle to a view, that does not depend on the backend.
+///
+/// The handle does not keep the view alive: once the view is dropped, all operations on the handle fail.
+/// Proxies can be obtained with [`View::proxy`] or [`World::views`].
+///
+/// Proxies can be sent to other threads, but `pugl` can only be used from the thread that runs the event loop.
+/// Requests (like [`ViewProxy::obscure_view`] and [`ViewProxy::send_event`]) made from other threads are deferred to the next [`World::update`],
+/// and queries (like [`ViewProxy::size`]) return nothing there.
+#[derive(Clone)]
+pub struct ViewProxy {
+    world: Weak<WorldInner>,
+    id: u64,
+    view: *mut sys::PuglView,
+}
+
+unsafe impl Send for ViewProxy {}
+unsafe impl Sync for ViewProxy {}
+
+impl ViewProxy {
+    pub(crate) fn new(world: &Arc<WorldInner>, id: u64, view: *mut sys::PuglView) -> Self {
+        Self {
+            world: Arc::downgrade(world),
+            id,
+            view,
+        }
+    }
+
+    /// Runs `f` with the view pointer if the view is still alive.
+    /// The view can't be dropped while `f` is running.
+    fn with_view<R>(&self, f: impl FnOnce(*mut sys::PuglView) -> R) -> Option<R> {
+        let world = self.world.upgrade()?;
+        let views = world.views.lock().unwrap();
+        // the address of a dropped view can be reused by a new view, so the id is compared
+        if views.iter().any(|&(id, _)| id == self.id) {
+            Some(f(self.view))
+        } else {
+            None
+        }
+    }
+
+    /// Queue a closure to run on the main loop thread during the next [`World::update`] call, see [`World::spawn`].
+    ///
+    /// The closure is only called if the view still exists by then.
+    /// Returns `false` if the world no longer exists.
+    pub fn defer(&self, f: impl FnOnce(&ViewProxy) + Send + 'static) -> bool {
+        let Some(world) = self.world.upgrade() else {
+            return false;
+        };
+
+        let proxy = self.clone();
+        world.defer(Box::new(move |_| {
+            if proxy.is_alive() {
+                f(&proxy);
+            }
+        }));
+        true
+    }
+
+    /// Returns `true` if the world still exists and the current thread runs its event loop.
+    fn on_loop_thread(&self) -> bool {
+        self.world
+            .upgrade()
+            .is_some_and(|world| world.is_owner_thread())
+    }
+
+    /// Returns `true` if the view still exists.
+    pub fn is_alive(&self) -> bool {
+        self.with_view(|_| ()).is_some()
+    }
+
+    /// Returns the native window handle, if the view still exists.
+    ///
+    /// Returns `None` when called from a thread other than the one running the event loop.
+    pub fn native(&self) -> Option<NativeView> {
+        if !self.on_loop_thread() {
+            return None;
+        }
+
+        self.with_view(|view| unsafe {
+            NativeView {
+                ptr: sys::puglGetNativeView(view),
+            }
+        })
+    }
+
+    /// Returns the current size of the view in (physical) pixels, if the view still exists.
+    ///
+    /// Returns `None` when called from a thread other than the one running the event loop.
+    pub fn size(&self) -> Option<(u32, u32)> {
+        if !self.on_loop_thread() {
+            return None;
+        }
+
+        self.with_view(|view| unsafe {
+            let size = sys::puglGetSizeHint(view, sys::PUGL_CURRENT_SIZE);
+            (size.width as u32, size.height as u32)
+        })
+    }
+
+    /// Return true if the view still exists and is currently visible.
+    ///
+    /// Returns `false` when called from a thread other than the one running the event loop.
+    pub fn is_visible(&self) -> bool {
+        if !self.on_loop_thread() {
+            return false;
+        }
+
+        self.with_view(|view| unsafe { sys::puglGetVisible(view) })
+            .unwrap_or(false)
+    }
+
+    /// Request a redisplay for the entire view. See [`View::obscure_view`].
+    ///
+    /// When called from a thread other than the one running the event loop, the request is deferred to the next [`World::update`] call.
+    pub fn obscure_view(&self) -> bool {
+        if !self.on_loop_thread() {
+            return self.defer(|proxy| {
+                proxy.obscure_view();
+            });
+        }
+
+        self.with_view(|view| unsafe { sys::puglObscureView(view) == sys::PUGL_SUCCESS })
+            .unwrap_or(false)
+    }
+
+    /// Send a client event to the view. See [`View::send_client_event`].
+    ///
+    /// When called from a thread other than the one running the event loop, the event is sent during the next [`World::update`] call.
+    pub fn send_client_event(&self, data: [usize; 2]) -> bool {
+        if !self.on_loop_thread() {
+            return self.defer(move |proxy| {
+                proxy.send_client_event(data);
+            });
+        }
+
+        self.with_view(|view| unsafe { send_client_event(view, data).is_ok() })
+            .unwrap_or(false)
+    }
+
+    /// Send an event to the view via the window system. See [`View::send_event`].
+    ///
+    /// When called from a thread other than the one running the event loop, the event is sent during the next [`World::update`] call.
+    pub fn send_event(&self, event: OwnedEvent) -> bool {
+        if !self.on_loop_thread() {
+            return self.defer(move |proxy| {
+                proxy.send_event(event);
+            });
+        }
+
+        self.with_view(|view| unsafe { send_event(view, event).is_ok() })
+            .unwrap_or(false)
+    }
+
+    /// Send a close event to the view. See [`View::send_close_event`].
+    ///
+    /// When called from a thread other than the one running the event loop, the event is sent during the next [`World::update`] call.
+    pub fn send_close_event(&self) -> bool {
+        if !self.on_loop_thread() {
+            return self.defer(|proxy| {
+                proxy.send_close_event();
+            });
+        }
+
+        self.with_view(|view| unsafe { send_close_event(view).is_ok() })
+            .unwrap_or(false)
+    }
+}
+
+impl PartialEq for ViewProxy {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && Weak::ptr_eq(&self.world, &other.world)
+    }
+}
+
+impl Eq for ViewProxy {}
+
+impl fmt::Debug for ViewProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ViewProxy")
+            .field("native", &self.native())
+            .finish()
+    }
+}
+
+// pugl has no internal locking, so views can be moved between threads but not shared between them,
+// see the thread safety section of the `World` docs
 unsafe impl<B: Backend> Send for View<B> {}
+#[cfg(feature = "unsafe-sync")]
 unsafe impl<B: Backend> Sync for View<B> {}
 unsafe impl<B: Backend> Send for UnrealizedView<B> {}
+#[cfg(feature = "unsafe-sync")]
 unsafe impl<B: Backend> Sync for UnrealizedView<B> {}
 
 impl<B: Backend> UnrealizedView<B> {
@@ -74,8 +326,13 @@ impl<B: Backend> UnrealizedView<B> {
             let view = sys::puglNewView(world.raw);
             assert!(!view.is_null(), "failed to allocate view");
             sys::puglSetEventFunc(view, Some(event_handler::<B>));
-            sys::puglSetHandle(view, null_mut());
+            sys::puglSetHandle(
+                view,
+                Box::into_raw(ViewState::<B>::new(view, &world)) as *mut _,
+            );
             backend.install(view, crate::private::Private);
+            let id = world.next_view_id.fetch_add(1, Ordering::Relaxed);
+            world.views.lock().unwrap().push((id, view));
             Self(View {
                 view,
                 world,
@@ -132,6 +389,42 @@ impl<B: Backend> UnrealizedView<B> {
         self
     }
 
+    /// Set the initial state of the window, like [`ViewStyle::TALL`] and [`ViewStyle::WIDE`] for a maximized window,
+    /// [`ViewStyle::FULLSCREEN`] or [`ViewStyle::ABOVE`].
+    ///
+    /// The style is applied when the view is realized, before it is shown, so the window opens in that state without being restyled after it is mapped.
+    /// [`ViewStyle::MAPPED`] and [`ViewStyle::RESIZING`] only report the state of the window, and are ignored. See also [`View::set_style`].
+    pub fn with_style(self, style: ViewStyle) -> Self {
+        let style = style - (ViewStyle::MAPPED | ViewStyle::RESIZING);
+        unsafe { ViewState::<B>::from_raw(self.0.view) }
+            .initial_style
+            .store(style.bits(), Ordering::Relaxed);
+        self
+    }
+
+    /// Retry realizing the view with lower requirements if the graphics configuration is not supported, instead of failing.
+    ///
+    /// If [`UnrealizedView::realize`] fails to set the pixel format or to create the context, the view is realized again with
+    /// progressively lower requirements, until it succeeds or there is nothing left to lower. For [`OpenGl`](crate::OpenGl) views these are,
+    /// in order: no multisampling, no debug context, version 3.2, and a version 2.1 compatibility context.
+    /// The configuration that was actually used can be checked with [`OpenGlContext::config`](crate::OpenGlContext::config).
+    ///
+    /// This lets plugins show a (possibly less pretty) user interface on weak drivers. Disabled by default.
+    pub fn with_fallback_config(self, fallback: bool) -> Self {
+        unsafe { ViewState::<B>::from_raw(self.0.view) }
+            .fallback_config
+            .store(fallback, Ordering::Relaxed);
+        self
+    }
+
+    /// Set what happens when the view receives [`Event::Close`], after the event handler returns.
+    ///
+    /// The event handler can still keep the view open (for example to ask about unsaved changes) with [`View::keep_open`].
+    pub fn with_close_policy(self, policy: ClosePolicy) -> Self {
+        self.0.set_close_policy(policy);
+        self
+    }
+
     /// Set the parent window of the view.
     pub fn with_parent(self, parent: ViewParent) -> Self {
         unsafe {
@@ -147,9 +440,72 @@ impl<B: Backend> UnrealizedView<B> {
         self
     }
 
+    /// Embed the view inside a window given as a [`raw_window_handle::RawWindowHandle`], see [`NativeView::from_raw_window_handle`].
+    ///
+    /// Returns the view unchanged as an error if the handle is not supported on the current platform,
+    /// so it can still be realized as a top-level window (or embedded differently).
+    ///
+    /// # Safety
+    /// The handle must refer to a valid window that outlives the view.
+    #[cfg(feature = "raw-window-handle")]
+    pub unsafe fn with_raw_parent(
+        self,
+        handle: raw_window_handle::RawWindowHandle,
+    ) -> Result<Self, Self> {
+        match unsafe { NativeView::from_raw_window_handle(handle) } {
+            Some(parent) => Ok(self.with_parent(ViewParent::Embedding(parent))),
+            None => Err(self),
+        }
+    }
+
+    /// Embed the view inside another (realized) `pugl-rs` view.
+    ///
+    /// Unlike [`ViewParent::Embedding`], the parent view keeps track of its children:
+    /// - Children receive [`Event::ParentConfigure`] whenever the parent view is configured.
+    /// - Children are unrealized before the parent view is destroyed, so the native child window never outlives its parent.
+    ///
+    /// # Panics
+    /// Panics if `parent` was created in a different [`World`].
+    pub fn with_parent_view<P: Backend>(self, parent: &View<P>) -> Self {
+        assert!(
+            Arc::ptr_eq(&self.0.world, &parent.world),
+            "parent view belongs to a different world"
+        );
+
+        unsafe {
+            self.0.detach_parent_view();
+            sys::puglSetParent(self.0.view, parent.native().ptr);
+
+            family(self.0.view).lock().unwrap().parent = parent.view;
+            family(parent.view)
+                .lock()
+                .unwrap()
+                .children
+                .push(ChildView {
+                    view: self.0.view,
+                    forward: forward_parent_configure::<B>,
+                });
+        }
+        self
+    }
+
+    /// Add the view to the tab order of its siblings, see [`View::set_tab_index`].
+    pub fn with_tab_index(self, index: i32) -> Self {
+        self.0.set_tab_index(Some(index));
+        self
+    }
+
     /// Set the title of the window.
+    ///
+    /// Nul bytes can't be a part of the title, so they are silently removed (`"a\0b"` becomes `"ab"`).
     pub fn with_title(self, title: &str) -> Self {
-        self.0.set_title(title);
+        let _ = self.0.set_title(title);
+        self
+    }
+
+    /// Set the title of the window from a nul-terminated string.
+    pub fn with_title_cstr(self, title: &CStr) -> Self {
+        let _ = self.0.set_title_cstr(title);
         self
     }
 
@@ -171,45 +527,102 @@ impl<B: Backend> UnrealizedView<B> {
 
     /// Set the maximum size of the view in (physical) pixels.
     pub fn with_max_size(self, width: u32, height: u32) -> Self {
-        self.0.set_max_size(width, height);
+        let _ = self.0.set_max_size(width, height);
         self
     }
 
     /// Set the minimum size of the view in (physical) pixels.
     pub fn with_min_size(self, width: u32, height: u32) -> Self {
-        self.0.set_min_size(width, height);
+        let _ = self.0.set_min_size(width, height);
         self
     }
 
     /// Set the maximum aspect ratio of the view.
     pub fn with_max_aspect(self, x: u32, y: u32) -> Self {
-        self.0.set_max_aspect(x, y);
+        let _ = self.0.set_max_aspect(x, y);
         self
     }
 
     /// Set the minimum aspect ratio of the view.
     pub fn with_min_aspect(self, x: u32, y: u32) -> Self {
-        self.0.set_min_aspect(x, y);
+        let _ = self.0.set_min_aspect(x, y);
+        self
+    }
+
+    /// Set a fixed aspect ratio of the view, see [`View::set_fixed_aspect`].
+    pub fn with_fixed_aspect(self, x: u32, y: u32) -> Self {
+        let _ = self.0.set_fixed_aspect(x, y);
         self
     }
 
     /// Set the main event handler for the view.
+    ///
+    /// The handler (and everything it captures) is dropped after [`Event::Unrealize`] is delivered,
+    /// or when the [`View`] is dropped if the view was never realized.
     pub fn with_event_handler<E: FnMut(&View<B>, Event<B>) + Send + 'static>(
         self,
         event: E,
     ) -> Self {
         unsafe {
-            let old = sys::puglGetHandle(self.0.view);
-            if !old.is_null() {
-                drop(Box::from_raw(old as *mut EventHandler<B>));
-            }
+            let state = ViewState::<B>::from_raw(self.0.view);
+            *state.handler.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(event));
+        }
+        self
+    }
+
+    /// Set an event handler that receives the events in batches, once per iteration of the main loop.
+    ///
+    /// Instead of a closure call per event, the events accumulated during one [`World::update`] are delivered together,
+    /// right before the [`Event::Update`] (or before an [`Event::Expose`], if there is no update event),
+    /// which lets the application coalesce redundant events (like pointer motion) itself.
+    ///
+    /// [`Event::Realize`], [`Event::Unrealize`], [`Event::Update`] and [`Event::Expose`] are not batched and are still delivered to the main event handler
+    /// (see [`UnrealizedView::with_event_handler`]), since they carry backend contexts that are only valid during the event.
+    /// Events dispatched outside of [`World::update`] (for example by the event loop of a plugin host) are delivered right away.
+    pub fn with_batch_event_handler<E: FnMut(&View<B>, &[OwnedEvent]) + Send + 'static>(
+        self,
+        handler: E,
+    ) -> Self {
+        unsafe {
+            let state = ViewState::<B>::from_raw(self.0.view);
+            *state
+                .batch_handler
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(Box::new(handler));
+            *state.batch.lock().unwrap_or_else(PoisonError::into_inner) = Some(Vec::new());
+        }
+        self
+    }
+
+    /// Merge every [`Event::KeyPress`] with the [`Event::KeyText`] that follows it into a single [`Event::KeyboardInput`],
+    /// so text widgets don't have to correlate the two events themselves.
+    ///
+    /// Only the main event handler receives the merged events, the world hook, the event streams and the batch handler still receive the separate events.
+    /// A key press is held back until the next event arrives (the text, the key release or an [`Event::Update`]),
+    /// so it is delivered slightly later than the separate [`Event::KeyPress`] would be.
+    pub fn with_unified_keyboard_events(self, unified: bool) -> Self {
+        unsafe {
+            let state = ViewState::<B>::from_raw(self.0.view);
+            state.unified_keyboard.store(unified, Ordering::Relaxed);
+        }
+        self
+    }
 
-            let event: Box<EventHandler<B>> = Box::new(Mutex::new(Box::new(event)));
-            sys::puglSetHandle(self.0.view, Box::into_raw(event) as *mut _);
+    /// Send an [`Event::EndFrame`] after the last [`Event::Expose`] of every main loop iteration.
+    pub fn with_end_frame_events(self, enabled: bool) -> Self {
+        unsafe {
+            let state = ViewState::<B>::from_raw(self.0.view);
+            state.end_frame.store(enabled, Ordering::Relaxed);
         }
         self
     }
 
+    /// Set how the deltas of [`Event::Scroll`] are normalized, see [`ScrollConfig`].
+    pub fn with_scroll_config(self, config: ScrollConfig) -> Self {
+        self.0.set_scroll_config(config);
+        self
+    }
+
     /// Returns the associated world instance
     pub fn world(&self) -> &World {
         self.0.world()
@@ -241,86 +654,195 @@ impl<B: Backend> UnrealizedView<B> {
     /// The view should be fully configured using the above functions before this is called. This function may only be called once per view.
     ///
     /// The view will be kept alive as long as the [`View`] instance is not dropped
+    ///
+    /// On MacOS this must be called on the main thread, otherwise [`ViewError::NotMainThread`] is returned.
+    ///
+    /// On X11, views embedded with [`ViewParent::Embedding`] advertise themselves as XEmbed clients,
+    /// so that hosts based on GTK or Qt treat them as embedded windows.
     pub fn realize(self) -> Result<View<B>, ViewError> {
+        if !is_main_thread() {
+            return Err(ViewError::NotMainThread);
+        }
+
+        self.0.world.check_thread();
+
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("pugl_realize", backend = std::any::type_name::<B>()).entered();
+
         unsafe {
-            let error = match sys::puglRealize(self.0.view) {
-                sys::PUGL_SUCCESS => return Ok(self.0),
-                sys::PUGL_BAD_CONFIGURATION => ViewError::BadConfig,
-                sys::PUGL_BAD_BACKEND => ViewError::BadBackend,
-                sys::PUGL_BACKEND_FAILED => ViewError::BackendInit,
-                sys::PUGL_REGISTRATION_FAILED => ViewError::ClassRegister,
-                sys::PUGL_REALIZE_FAILED => ViewError::OsRealize,
-                sys::PUGL_CREATE_CONTEXT_FAILED => ViewError::CreateContext,
-                sys::PUGL_SET_FORMAT_FAILED => ViewError::SetPixelFormat,
-                sys::PUGL_NO_MEMORY => ViewError::OutOfMemory,
-                _ => ViewError::Unknown,
-            };
+            let state = ViewState::<B>::from_raw(self.0.view);
+            let mut status = Status::from_raw(sys::puglRealize(self.0.view));
+            while matches!(
+                status,
+                Status::SetFormatFailed | Status::CreateContextFailed
+            ) && state.fallback_config.load(Ordering::Relaxed)
+                && B::degrade(self.0.view, crate::private::Private)
+            {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(?status, "retrying to realize with a lowered configuration");
+
+                // the window may be created before the context, and the pixel format of a window can't be changed
+                state.retrying.store(true, Ordering::Relaxed);
+                sys::puglUnrealize(self.0.view);
+                state.retrying.store(false, Ordering::Relaxed);
+                status = Status::from_raw(sys::puglRealize(self.0.view));
+            }
+
+            match status {
+                Status::Success => {
+                    #[cfg(target_os = "linux")]
+                    if let Some(ViewParent::Embedding(_)) = self.0.parent() {
+                        xembed::embed::<B>(
+                            self.0.world().native().as_raw(),
+                            self.0.native().ptr,
+                            self.0.view,
+                        );
+                    }
+
+                    let style = state.initial_style.load(Ordering::Relaxed);
+                    if style != 0 {
+                        sys::puglSetViewStyle(self.0.view, style);
+                    }
 
-            Err(error)
+                    Ok(self.0)
+                }
+                status => Err(ViewError::from(status)),
+            }
         }
     }
 }
 
 impl<B: Backend> View<B> {
     /// Set the maximum size of the view in (physical) pixels.
-    pub fn set_max_size(&self, width: u32, height: u32) -> bool {
+    pub fn set_max_size(&self, width: u32, height: u32) -> Result<(), Status> {
         unsafe {
-            sys::puglSetSizeHint(self.view, sys::PUGL_MAX_SIZE, width, height) == sys::PUGL_SUCCESS
+            Status::from_raw(sys::puglSetSizeHint(
+                self.view,
+                sys::PUGL_MAX_SIZE,
+                width,
+                height,
+            ))
+            .into_result()
         }
     }
 
     /// Set the minimum size of the view in (physical) pixels.
-    pub fn set_min_size(&self, width: u32, height: u32) -> bool {
+    pub fn set_min_size(&self, width: u32, height: u32) -> Result<(), Status> {
         unsafe {
-            sys::puglSetSizeHint(self.view, sys::PUGL_MIN_SIZE, width, height) == sys::PUGL_SUCCESS
+            Status::from_raw(sys::puglSetSizeHint(
+                self.view,
+                sys::PUGL_MIN_SIZE,
+                width,
+                height,
+            ))
+            .into_result()
         }
     }
 
     /// Set the maximum aspect ratio of the view.
-    pub fn set_max_aspect(&self, x: u32, y: u32) -> bool {
-        unsafe { sys::puglSetSizeHint(self.view, sys::PUGL_MAX_ASPECT, x, y) == sys::PUGL_SUCCESS }
+    pub fn set_max_aspect(&self, x: u32, y: u32) -> Result<(), Status> {
+        self.set_fixed_aspect_state(None);
+        unsafe {
+            Status::from_raw(sys::puglSetSizeHint(self.view, sys::PUGL_MAX_ASPECT, x, y))
+                .into_result()
+        }
     }
 
     /// Set the minimum aspect ratio of the view.
-    pub fn set_min_aspect(&self, x: u32, y: u32) -> bool {
-        unsafe { sys::puglSetSizeHint(self.view, sys::PUGL_MIN_ASPECT, x, y) == sys::PUGL_SUCCESS }
+    pub fn set_min_aspect(&self, x: u32, y: u32) -> Result<(), Status> {
+        self.set_fixed_aspect_state(None);
+        unsafe {
+            Status::from_raw(sys::puglSetSizeHint(self.view, sys::PUGL_MIN_ASPECT, x, y))
+                .into_result()
+        }
+    }
+
+    /// Set both the minimum and the maximum aspect ratio of the view, so it keeps its proportions when resized (for example for video or scope views).
+    ///
+    /// While the aspect ratio is fixed, [`View::set_size`] adjusts the height to match it, since the window system may refuse sizes with other proportions.
+    /// Setting the minimum or maximum aspect ratio separately, or passing `0, 0`, removes the fixed aspect ratio.
+    pub fn set_fixed_aspect(&self, x: u32, y: u32) -> Result<(), Status> {
+        let fixed = (x != 0 && y != 0).then_some((x, y));
+        let (x, y) = fixed.unwrap_or((0, 0));
+
+        unsafe {
+            let min = sys::puglSetSizeHint(self.view, sys::PUGL_MIN_ASPECT, x, y);
+            let max = sys::puglSetSizeHint(self.view, sys::PUGL_MAX_ASPECT, x, y);
+            self.set_fixed_aspect_state(fixed);
+            Status::from_raw(min).into_result()?;
+            Status::from_raw(max).into_result()
+        }
+    }
+
+    fn set_fixed_aspect_state(&self, aspect: Option<(u32, u32)>) {
+        *unsafe { ViewState::<B>::from_raw(self.view) }
+            .fixed_aspect
+            .lock()
+            .unwrap() = aspect;
     }
 
     /// Set the current size of the view in (physical) pixels.
-    pub fn set_size(&self, width: u32, height: u32) -> bool {
+    pub fn set_size(&self, width: u32, height: u32) -> Result<(), Status> {
+        let fixed_aspect = *unsafe { ViewState::<B>::from_raw(self.view) }
+            .fixed_aspect
+            .lock()
+            .unwrap();
+        let height = match fixed_aspect {
+            Some((x, y)) => ((width as u64 * y as u64 + x as u64 / 2) / x as u64).max(1) as u32,
+            None => height,
+        };
+
         unsafe {
             // workaround for not being able to resize the view when it's not marked as resizable
             if sys::puglGetViewHint(self.view, sys::PUGL_RESIZABLE) == 0 {
                 sys::puglSetViewHint(self.view, sys::PUGL_RESIZABLE, 1);
                 sys::puglSetSizeHint(self.view, sys::PUGL_MAX_SIZE, width, height);
                 sys::puglSetSizeHint(self.view, sys::PUGL_MIN_SIZE, width, height);
-                let result = sys::puglSetSizeHint(self.view, sys::PUGL_CURRENT_SIZE, width, height)
-                    == sys::PUGL_SUCCESS;
+                let status = sys::puglSetSizeHint(self.view, sys::PUGL_CURRENT_SIZE, width, height);
                 sys::puglSetViewHint(self.view, sys::PUGL_RESIZABLE, 0);
-                result
+                Status::from_raw(status).into_result()
             } else {
-                sys::puglSetSizeHint(self.view, sys::PUGL_CURRENT_SIZE, width, height)
-                    == sys::PUGL_SUCCESS
+                Status::from_raw(sys::puglSetSizeHint(
+                    self.view,
+                    sys::PUGL_CURRENT_SIZE,
+                    width,
+                    height,
+                ))
+                .into_result()
             }
         }
     }
 
     /// Set the current position of the view in screen coordinates with an upper left origin.
-    pub fn set_position(&self, x: i32, y: i32) -> bool {
+    pub fn set_position(&self, x: i32, y: i32) -> Result<(), Status> {
         unsafe {
-            sys::puglSetPositionHint(self.view, sys::PUGL_CURRENT_POSITION, x, y)
-                == sys::PUGL_SUCCESS
+            Status::from_raw(sys::puglSetPositionHint(
+                self.view,
+                sys::PUGL_CURRENT_POSITION,
+                x,
+                y,
+            ))
+            .into_result()
         }
     }
 
     /// Set the title of the window.
-    pub fn set_title(&self, title: &str) -> bool {
+    ///
+    /// Nul bytes can't be a part of the title, so they are silently removed (`"a\0b"` becomes `"ab"`).
+    pub fn set_title(&self, title: &str) -> Result<(), Status> {
+        with_c_str(title, |title| self.set_title_cstr(title))
+    }
+
+    /// Set the title of the window from a nul-terminated string, which avoids copying the title.
+    pub fn set_title_cstr(&self, title: &CStr) -> Result<(), Status> {
         unsafe {
-            sys::puglSetViewString(
+            Status::from_raw(sys::puglSetViewString(
                 self.view,
                 sys::PUGL_WINDOW_TITLE,
-                CString::new(title).unwrap().as_ptr(),
-            ) == sys::PUGL_SUCCESS
+                title.as_ptr(),
+            ))
+            .into_result()
         }
     }
 
@@ -330,8 +852,72 @@ impl<B: Backend> View<B> {
     ///
     /// The cursor is reset to the default when the pointer leaves the view.
     /// The cursor is also reset to the default when the view is hidden or obscured, so this function should be called in the event handler if the cursor should be changed back when the view is exposed again.
-    pub fn set_cursor(&self, cursor: MouseCursor) -> bool {
-        unsafe { sys::puglSetCursor(self.view, cursor.into_raw()) == sys::PUGL_SUCCESS }
+    ///
+    /// The system cursors are loaded at [`View::cursor_size`] on X11 (which needs the `xcursor` feature for anything but the default cursor)
+    /// and on Windows. MacOS scales the cursors itself.
+    pub fn set_cursor(&self, cursor: MouseCursor) -> Result<(), Status> {
+        // the theme cursors are loaded by `puglSetCursor` at the default size of the display
+        #[cfg(all(target_os = "linux", feature = "xcursor"))]
+        unsafe {
+            crate::x11::xlib::XcursorSetDefaultSize(
+                self.world().native().as_raw() as crate::x11::xlib::Display,
+                self.cursor_size().min(i32::MAX as u32) as _,
+            );
+        }
+
+        unsafe {
+            Status::from_raw(sys::puglSetCursor(self.view, cursor.into_raw())).into_result()?
+        };
+
+        #[cfg(target_os = "windows")]
+        self.set_scaled_cursor(cursor);
+
+        Ok(())
+    }
+
+    /// Replaces the cursor that `pugl` loaded at the size of the primary monitor with one loaded at [`View::cursor_size`].
+    #[cfg(target_os = "windows")]
+    fn set_scaled_cursor(&self, cursor: MouseCursor) {
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        let hwnd = self.native().as_raw() as isize;
+
+        let scaled = match subclass::Cursor::load(cursor, self.cursor_size()) {
+            Some(scaled) if hwnd != 0 && unsafe { subclass::install::<B>(hwnd, self.view) } => {
+                scaled.show_if_current();
+                Some(scaled)
+            }
+            _ => None,
+        };
+
+        let previous = replace(&mut *state.cursor.lock().unwrap(), scaled);
+        drop(previous);
+    }
+
+    /// Returns the size of the mouse cursor in physical pixels, for drawing cursor images that match the size of the system cursors.
+    ///
+    /// On X11 this is `XCURSOR_SIZE` if it is set, otherwise the default cursor size is multiplied by [`View::system_scale`].
+    /// On Windows the cursor size of the accessibility settings is multiplied by [`View::system_scale`] (the DPI of the monitor).
+    /// On MacOS this is the size of the standard cursors in points multiplied by [`View::system_scale`], which ignores the accessibility cursor size.
+    pub fn cursor_size(&self) -> u32 {
+        #[cfg(target_os = "linux")]
+        if let Some(size) = std::env::var("XCURSOR_SIZE")
+            .ok()
+            .and_then(|size| size.trim().parse::<u32>().ok())
+            .filter(|&size| size > 0)
+        {
+            return size;
+        }
+
+        #[cfg(target_os = "windows")]
+        let base = subclass::base_cursor_size() as f64;
+        #[cfg(not(target_os = "windows"))]
+        let base = if cfg!(target_os = "macos") {
+            16.0
+        } else {
+            24.0
+        };
+
+        (base * self.system_scale()).round() as u32
     }
 
     /// Set a view state, if supported by the system.
@@ -339,8 +925,8 @@ impl<B: Backend> View<B> {
     /// This can be used to manipulate the window into various special states, but note that not all states are supported on all systems.
     /// This function may return failure or an error if the platform implementation doesn't "understand" how to set the given style, but the return value here can't be used to determine if the state has actually been set.
     /// Any changes to the actual state of the view will arrive in later configure events.
-    pub fn set_style(&self, style: ViewStyle) -> bool {
-        unsafe { sys::puglSetViewStyle(self.view, style.bits()) == sys::PUGL_SUCCESS }
+    pub fn set_style(&self, style: ViewStyle) -> Result<(), Status> {
+        unsafe { Status::from_raw(sys::puglSetViewStyle(self.view, style.bits())).into_result() }
     }
 
     /// Activate a repeating timer event.
@@ -353,70 +939,161 @@ impl<B: Backend> View<B> {
     /// ### Timer Resolution
     /// Timers are not guaranteed to have a resolution better than 10ms (the maximum timer resolution on Windows)
     /// and may be rounded up if it is too short. On X11 and MacOS, a resolution of about 1ms can usually be relied on.
-    pub fn start_timer(&self, id: TimerId, timeout: Duration) -> bool {
-        unsafe { sys::puglStartTimer(self.view, id, timeout.as_secs_f64()) == sys::PUGL_SUCCESS }
+    ///
+    /// The next event is scheduled when the previous one is handled, so the timer slowly falls behind,
+    /// see [`View::start_fixed_rate_timer`] for a timer that doesn't drift.
+    pub fn start_timer(&self, id: TimerId, timeout: Duration) -> Result<(), Status> {
+        unsafe { ViewState::<B>::from_raw(self.view) }
+            .timers
+            .unschedule(id);
+        unsafe {
+            Status::from_raw(sys::puglStartTimer(self.view, id, timeout.as_secs_f64()))
+                .into_result()
+        }
     }
 
     /// Stop an active timer.
-    pub fn stop_timer(&self, id: TimerId) -> bool {
-        unsafe { sys::puglStopTimer(self.view, id) == sys::PUGL_SUCCESS }
+    pub fn stop_timer(&self, id: TimerId) -> Result<(), Status> {
+        unsafe { ViewState::<B>::from_raw(self.view) }
+            .timers
+            .unschedule(id);
+        unsafe { Status::from_raw(sys::puglStopTimer(self.view, id)).into_result() }
     }
 
     /// Send a client event to a view via the window system.
     ///
     /// This can be used to send a custom message to a view, which is delivered via the window system and processed in the event loop as usual.
     /// Among other things, this makes it possible to wake up the event loop for any reason.
-    pub fn send_client_event(&self, data: [usize; 2]) -> bool {
-        unsafe {
-            sys::puglSendEvent(
-                self.view,
-                &sys::PuglEvent {
-                    client: sys::PuglClientEvent {
-                        type_: sys::PUGL_CLIENT,
-                        flags: sys::PUGL_IS_SEND_EVENT,
-                        data1: data[0],
-                        data2: data[1],
-                    },
-                },
-            ) == sys::PUGL_SUCCESS
-        }
+    /// Every value of `data` is available, `pugl-rs` marks its own client events so they never reach the event handler.
+    pub fn send_client_event(&self, data: [usize; 2]) -> Result<(), Status> {
+        unsafe { send_client_event(self.view, data) }
     }
 
     /// Send a close event to the event handler.
-    pub fn send_close_event(&self) -> bool {
-        unsafe {
-            sys::puglSendEvent(
-                self.view,
-                &sys::PuglEvent {
-                    any: sys::PuglAnyEvent {
-                        type_: sys::PUGL_CLOSE,
-                        flags: sys::PUGL_IS_SEND_EVENT,
-                    },
-                },
-            ) == sys::PUGL_SUCCESS
-        }
+    pub fn send_close_event(&self) -> Result<(), Status> {
+        unsafe { send_close_event(self.view) }
+    }
+
+    /// Synthesize an event and deliver it through the normal dispatch path.
+    ///
+    /// The event is passed to the world hook, the event streams and the event handler of the view as if it was sent by the system,
+    /// which can be used to simulate user input in integration tests and UI automation.
+    /// - [`Event::Configure`] is also forwarded to the child views, and its `changed` flags are computed from the previous style of the view.
+    /// - [`Event::Close`] marks the view as closed.
+    /// - [`Event::Expose`] requires the drawing context, so the region is obscured instead (see [`View::obscure_region`]),
+    ///   and the event is dispatched by the system on the next [`World::update`].
+    /// - [`Event::Realize`] and [`Event::Unrealize`] can't be injected, and `false` is returned.
+    ///
+    /// The event is dispatched immediately, so this must not be called from the event handler of this view.
+    pub fn inject_event(&self, event: OwnedEvent) -> bool {
+        unsafe { ViewState::<B>::from_raw(self.view) }.inject(self, event)
+    }
+
+    /// Send an event to the view via the window system.
+    ///
+    /// Unlike [`View::inject_event`], the event is queued behind the pending events of the view and delivered in the event loop as usual,
+    /// so this can also be called from the event handler of the view, or from other threads with [`ViewProxy::send_event`].
+    /// The events are delivered like injected events, and generalize [`View::send_client_event`] and [`View::send_close_event`] for UI automation and accessibility tools.
+    ///
+    /// [`Event::Realize`] and [`Event::Unrealize`] can't be sent, and [`Status::BadParameter`] is returned.
+    pub fn send_event(&self, event: OwnedEvent) -> Result<(), Status> {
+        unsafe { send_event(self.view, event) }
     }
 
     /// Raise the window to the top of the application's stack.
     ///
     /// This is the normal "well-behaved" way to show and raise the window, which should be used in most cases.
-    pub fn show(&self) -> bool {
-        unsafe { sys::puglShow(self.view, sys::PUGL_SHOW_RAISE) == sys::PUGL_SUCCESS }
+    pub fn show(&self) -> Result<(), Status> {
+        unsafe { Status::from_raw(sys::puglShow(self.view, sys::PUGL_SHOW_RAISE)).into_result()? };
+        self.set_embedded_mapped(true);
+        Ok(())
     }
 
     /// Realize and show the window without intentionally raising it.
     ///
     /// This will weakly "show" the window but without making any effort to raise it. Depending on the platform or system configuration, the window may be raised above some others regardless.
-    pub fn show_passive(&self) -> bool {
-        unsafe { sys::puglShow(self.view, sys::PUGL_SHOW_PASSIVE) == sys::PUGL_SUCCESS }
+    pub fn show_passive(&self) -> Result<(), Status> {
+        unsafe {
+            Status::from_raw(sys::puglShow(self.view, sys::PUGL_SHOW_PASSIVE)).into_result()?
+        };
+        self.set_embedded_mapped(true);
+        Ok(())
     }
 
     /// Aggressively force the window to be raised to the top.
     ///
     /// This will attempt to raise the window to the top, even if this isn't the active application, or if doing so would otherwise go against the platform's guidelines.
     /// This generally shouldn't be used, and isn't guaranteed to work. On modern Windows systems, the active application must explicitly grant permission for others to steal the foreground from it.
-    pub fn show_aggressive(&self) -> bool {
-        unsafe { sys::puglShow(self.view, sys::PUGL_SHOW_FORCE_RAISE) == sys::PUGL_SUCCESS }
+    pub fn show_aggressive(&self) -> Result<(), Status> {
+        unsafe {
+            Status::from_raw(sys::puglShow(self.view, sys::PUGL_SHOW_FORCE_RAISE)).into_result()?;
+        }
+
+        self.set_embedded_mapped(true);
+        Ok(())
+    }
+
+    /// Move the window to a position in screen coordinates with an upper left origin, then show and raise it like [`View::show`].
+    ///
+    /// Unlike calling [`View::set_position`] after showing the window, the window is moved before it is mapped, so it doesn't visibly jump.
+    /// If the window is already visible, it is moved and raised.
+    pub fn show_at(&self, x: i32, y: i32) -> Result<(), Status> {
+        unsafe {
+            // some window managers only respect the position of a new window if it is also the default position
+            sys::puglSetPositionHint(self.view, sys::PUGL_DEFAULT_POSITION, x, y);
+            Status::from_raw(sys::puglSetPositionHint(
+                self.view,
+                sys::PUGL_CURRENT_POSITION,
+                x,
+                y,
+            ))
+            .into_result()?;
+            Status::from_raw(sys::puglShow(self.view, sys::PUGL_SHOW_RAISE)).into_result()?;
+        }
+
+        self.set_embedded_mapped(true);
+        Ok(())
+    }
+
+    /// Reports whether an embedded view is visible to the host, see [`xembed`].
+    fn set_embedded_mapped(&self, mapped: bool) {
+        #[cfg(target_os = "linux")]
+        unsafe {
+            xembed::set_mapped(self.world().native().as_raw(), self.native().ptr, mapped);
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        let _ = mapped;
+    }
+
+    /// Set how the deltas of [`Event::Scroll`] are normalized, see [`ScrollConfig`].
+    ///
+    /// Only the scroll events of the window system are normalized, events passed to [`View::inject_event`] are delivered as they are.
+    pub fn set_scroll_config(&self, config: ScrollConfig) {
+        *unsafe { ViewState::<B>::from_raw(self.view) }
+            .scroll_config
+            .lock()
+            .unwrap() = config;
+    }
+
+    /// Set what happens when the view receives [`Event::Close`], see [`UnrealizedView::with_close_policy`].
+    pub fn set_close_policy(&self, policy: ClosePolicy) {
+        *unsafe { ViewState::<B>::from_raw(self.view) }
+            .close_policy
+            .lock()
+            .unwrap() = policy;
+    }
+
+    /// Cancels the [`ClosePolicy`] for the [`Event::Close`] that is being handled.
+    ///
+    /// Must be called from the event handler of the view while it handles the close event, otherwise it has no effect.
+    ///
+    /// A [batch event handler](UnrealizedView::with_batch_event_handler) receives the close event after the policy was applied,
+    /// so it can't keep the view open. Use [`ClosePolicy::Ignore`] and close the view from the batch handler instead.
+    pub fn keep_open(&self) {
+        unsafe { ViewState::<B>::from_raw(self.view) }
+            .keep_open
+            .store(true, Ordering::Relaxed);
     }
 
     /// Hide the current window.
@@ -426,6 +1103,8 @@ impl<B: Backend> View<B> {
         unsafe {
             sys::puglHide(self.view);
         }
+
+        self.set_embedded_mapped(false);
     }
 
     /// Request a redisplay for the entire view.
@@ -443,19 +1122,39 @@ impl<B: Backend> View<B> {
     /// This will cause an expose event to be dispatched later. If called from within the event handler, the expose should arrive at the end of the current event loop iteration, though this is not strictly guaranteed on all platforms.
     /// If called elsewhere, an expose will be enqueued to be processed in the next event loop iteration.
     /// The region is clamped to the size of the view if necessary.
+    ///
+    /// The regions are accumulated until the next frame ([`Event::Update`]) and overlapping or adjacent regions are merged,
+    /// so obscuring many small regions (like every widget of a widget tree) is cheap.
     pub fn obscure_region(&self, rect: Rect) {
         unsafe {
-            sys::puglObscureRegion(self.view, rect.x, rect.y, rect.w, rect.h);
+            header(self.view)
+                .damage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .add(rect);
         }
     }
 
     /// Grab the keyboard input focus.
     ///
     /// Note that this will fail if the view is not mapped and so should not, for example, be called immediately after show().
-    pub fn grab_focus(&self) {
-        unsafe {
-            sys::puglGrabFocus(self.view);
+    /// Use [`View::grab_focus_when_mapped`] instead in that case.
+    pub fn grab_focus(&self) -> Result<(), Status> {
+        unsafe { Status::from_raw(sys::puglGrabFocus(self.view)).into_result() }
+    }
+
+    /// Grab the keyboard input focus now if the view is mapped, or as soon as it is mapped otherwise (see [`View::when_mapped`]).
+    ///
+    /// Returns an error if grabbing the focus failed right away. A deferred grab can't report failure, check [`View::has_focus`] afterwards if needed.
+    pub fn grab_focus_when_mapped(&self) -> Result<(), Status> {
+        if self.is_mapped() {
+            return self.grab_focus();
         }
+
+        self.when_mapped(|view| {
+            let _ = view.grab_focus();
+        });
+        Ok(())
     }
 
     /// Return whether the view has the keyboard input focus
@@ -463,11 +1162,89 @@ impl<B: Backend> View<B> {
         unsafe { sys::puglHasFocus(self.view) }
     }
 
-    /// Returns the current position of the view in screen coordinates with an upper left origin
-    pub fn position(&self) -> (i32, i32) {
-        unsafe {
-            let point = sys::puglGetPositionHint(self.view, sys::PUGL_CURRENT_POSITION);
-            (point.x as i32, point.y as i32)
+    /// Add the view to (or remove it from, with `None`) the tab order of its siblings,
+    /// the views embedded in the same parent window (see [`UnrealizedView::with_parent_view`] and [`ViewParent::Embedding`]).
+    ///
+    /// Views are ordered by their index, and views with the same index are ordered by the time they were added.
+    /// The tab order is used by [`View::focus_next`] and [`View::focus_prev`].
+    pub fn set_tab_index(&self, index: Option<i32>) {
+        let mut tab_order = self.world.tab_order.lock().unwrap();
+        let stop = tab_order.iter_mut().find(|(view, _)| *view == self.view);
+        match (stop, index) {
+            (Some(stop), Some(index)) => stop.1 = index,
+            (Some(_), None) => tab_order.retain(|(view, _)| *view != self.view),
+            (None, Some(index)) => tab_order.push((self.view, index)),
+            (None, None) => {}
+        }
+    }
+
+    /// Returns the index of the view in the tab order of its siblings, see [`View::set_tab_index`].
+    pub fn tab_index(&self) -> Option<i32> {
+        let tab_order = self.world.tab_order.lock().unwrap();
+        tab_order
+            .iter()
+            .find(|(view, _)| *view == self.view)
+            .map(|&(_, index)| index)
+    }
+
+    /// Move the keyboard focus to the next visible sibling in the tab order (wrapping around), see [`View::set_tab_index`].
+    ///
+    /// `pugl` doesn't handle the tab key itself, so this is typically called by the event handler when tab is pressed.
+    /// Returns `false` if there is no other view to move the focus to.
+    pub fn focus_next(&self) -> bool {
+        self.move_focus(false)
+    }
+
+    /// Move the keyboard focus to the previous visible sibling in the tab order (wrapping around), see [`View::focus_next`].
+    pub fn focus_prev(&self) -> bool {
+        self.move_focus(true)
+    }
+
+    fn move_focus(&self, backwards: bool) -> bool {
+        let target = unsafe {
+            let parent = sys::puglGetParent(self.view);
+            let mut siblings = self
+                .world
+                .tab_order
+                .lock()
+                .unwrap()
+                .iter()
+                .copied()
+                .filter(|&(view, _)| {
+                    view == self.view
+                        || (sys::puglGetParent(view) == parent && sys::puglGetVisible(view))
+                })
+                .collect::<Vec<_>>();
+
+            // stable, so views with the same index keep their registration order
+            siblings.sort_by_key(|&(_, index)| index);
+            if backwards {
+                siblings.reverse();
+            }
+
+            match siblings.iter().position(|&(view, _)| view == self.view) {
+                Some(position) => siblings
+                    .iter()
+                    .cycle()
+                    .skip(position + 1)
+                    .take(siblings.len() - 1)
+                    .map(|&(view, _)| view)
+                    .next(),
+                None => siblings.first().map(|&(view, _)| view),
+            }
+        };
+
+        match target {
+            Some(view) => unsafe { sys::puglGrabFocus(view) == sys::PUGL_SUCCESS },
+            None => false,
+        }
+    }
+
+    /// Returns the current position of the view in screen coordinates with an upper left origin
+    pub fn position(&self) -> (i32, i32) {
+        unsafe {
+            let point = sys::puglGetPositionHint(self.view, sys::PUGL_CURRENT_POSITION);
+            (point.x as i32, point.y as i32)
         }
     }
 
@@ -481,7 +1258,7 @@ impl<B: Backend> View<B> {
 
     /// Returns the associated world instance
     pub fn world(&self) -> &World {
-        self.world.as_world()
+        WorldInner::as_world(&self.world)
     }
 
     /// Return the parent window this view, if any
@@ -501,6 +1278,13 @@ impl<B: Backend> View<B> {
         }
     }
 
+    /// Returns the graphics backend that the view actually uses, for diagnostics (like the output attached to bug reports).
+    ///
+    /// Returns `None` if the backend is not one of the backends of this crate (for example if it was replaced with the raw `pugl` API).
+    pub fn backend_kind(&self) -> Option<BackendKind> {
+        unsafe { BackendKind::of(self.view) }
+    }
+
     /// Returns the native window handle
     pub fn native(&self) -> NativeView {
         unsafe {
@@ -510,6 +1294,55 @@ impl<B: Backend> View<B> {
         }
     }
 
+    /// Observe or handle the messages of the window of the view before `pugl` does, returns `false` if the view is not realized or the window can't be subclassed.
+    ///
+    /// This is for messages that `pugl` doesn't translate into events, like `WM_APPCOMMAND`, `WM_DEVICECHANGE` or custom messages of a plugin host.
+    /// The hook returns `Some(result)` to consume the message, which is then not passed to `pugl` and `result` is returned from the window procedure,
+    /// or `None` to let `pugl` handle the message as usual. The window is subclassed, so the event loop of `pugl` keeps working unchanged.
+    ///
+    /// Replaces the previous hook. The hook is dropped when the view is unrealized.
+    /// ```no_run
+    /// # use pugl_rs::*;
+    /// # fn example(view: &View<()>) {
+    /// const WM_APPCOMMAND: u32 = 0x0319;
+    /// view.set_message_hook(|_, message| match message.message {
+    ///     WM_APPCOMMAND => Some(1),
+    ///     _ => None,
+    /// });
+    /// # }
+    /// ```
+    #[cfg(target_os = "windows")]
+    pub fn set_message_hook(
+        &self,
+        hook: impl FnMut(&View<B>, WindowMessage) -> Option<isize> + Send + 'static,
+    ) -> bool {
+        let hwnd = self.native().as_raw() as isize;
+        if hwnd == 0 {
+            return false;
+        }
+
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+        let previous = state.message_hook.lock().unwrap().replace(Box::new(hook));
+        drop(previous);
+
+        unsafe { subclass::install::<B>(hwnd, self.view) }
+    }
+
+    /// Remove the hook set with [`View::set_message_hook`].
+    #[cfg(target_os = "windows")]
+    pub fn remove_message_hook(&self) {
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+
+        // the subclass also sets the scaled cursor
+        let hwnd = self.native().as_raw() as isize;
+        if hwnd != 0 && state.cursor.lock().unwrap().is_none() {
+            unsafe { subclass::remove::<B>(hwnd) };
+        }
+
+        let hook = state.message_hook.lock().unwrap().take();
+        drop(hook);
+    }
+
     /// Returns the title of the window
     pub fn title(&self) -> String {
         unsafe {
@@ -517,9 +1350,7 @@ impl<B: Backend> View<B> {
             if title.is_null() {
                 String::new()
             } else {
-                std::ffi::CStr::from_ptr(title)
-                    .to_string_lossy()
-                    .into_owned()
+                CStr::from_ptr(title).to_string_lossy().into_owned()
             }
         }
     }
@@ -529,6 +1360,30 @@ impl<B: Backend> View<B> {
         unsafe { ViewStyle::from_bits_truncate(sys::puglGetViewStyle(self.view)) }
     }
 
+    /// Return true if the view is mapped, which is reported by the last [`Event::Configure`] (see [`ViewStyle::MAPPED`]).
+    ///
+    /// A view is mapped some time after [`View::show`], once the window system has actually put the window on the screen.
+    /// Several operations, like [`View::grab_focus`] and [`View::set_style`], fail before that.
+    pub fn is_mapped(&self) -> bool {
+        self.style().contains(ViewStyle::MAPPED)
+    }
+
+    /// Run a callback once the view is mapped, see [`View::is_mapped`].
+    ///
+    /// The callback runs immediately if the view is already mapped, otherwise after the handler of the first [`Event::Configure`] with [`ViewStyle::MAPPED`].
+    /// The callbacks are dropped without running if the view is unrealized before it is mapped.
+    pub fn when_mapped(&self, callback: impl FnOnce(&View<B>) + Send + 'static) {
+        if self.is_mapped() {
+            callback(self);
+        } else {
+            unsafe { ViewState::<B>::from_raw(self.view) }
+                .when_mapped
+                .lock()
+                .unwrap()
+                .push(Box::new(callback));
+        }
+    }
+
     /// Return true if the view is currently visible
     pub fn is_visible(&self) -> bool {
         unsafe { sys::puglGetVisible(self.view) }
@@ -540,48 +1395,293 @@ impl<B: Backend> View<B> {
     /// For example, 2.0 means the UI should be drawn twice as large.
     /// "Normal" is loosely defined, but means a good size on a "standard DPI" display (around 96 DPI).
     /// In other words, the scale 1.0 should have text that is reasonably sized on a 96 DPI display, and the scale 2.0 should have text twice that large.
+    ///
+    /// On Windows, this is the DPI of the monitor the view is on (with [`DpiAwareness::PerMonitorV2`](crate::DpiAwareness::PerMonitorV2), see [`set_dpi_awareness`](crate::set_dpi_awareness)),
+    /// and [`Event::ScaleChange`] is sent when the view moves to a monitor with a different DPI.
     pub fn system_scale(&self) -> f64 {
+        #[cfg(target_os = "windows")]
+        if let Some(scale) = crate::dpi::window_scale(self.native().as_raw()) {
+            return scale;
+        }
+
         unsafe { sys::puglGetScaleFactor(self.view) }
     }
 
+    /// Returns the refresh rate of the display the view is on, in Hz.
+    ///
+    /// Returns `None` if the refresh rate is not known, which happens on X11 without the `xrandr` feature.
+    pub fn refresh_rate(&self) -> Option<u32> {
+        match unsafe { sys::puglGetViewHint(self.view, sys::PUGL_REFRESH_RATE) } {
+            rate if rate > 0 => Some(rate as u32),
+            _ => None,
+        }
+    }
+
+    /// Returns the render statistics of the view over the last second, see [`FrameStats`].
+    ///
+    /// The refresh rate of the display is used to count the dropped frames,
+    /// or [`FramePacer::DEFAULT_RATE`] if it is not known.
+    pub fn frame_stats(&self) -> FrameStats {
+        let rate = self.refresh_rate().unwrap_or(FramePacer::DEFAULT_RATE);
+        unsafe { ViewState::<B>::from_raw(self.view) }
+            .frames
+            .lock()
+            .unwrap()
+            .stats(Duration::from_secs(1) / rate)
+    }
+
+    /// Clears the statistics returned by [`View::frame_stats`], including the total number of frames.
+    pub fn reset_frame_stats(&self) {
+        unsafe { ViewState::<B>::from_raw(self.view) }
+            .frames
+            .lock()
+            .unwrap()
+            .reset();
+    }
+
     /// Set the clipboard contents.
     ///
     /// This sets the system clipboard contents, which can be retrieved with [`View::paste_clipboard`] or pasted into other applications.
     ///
     /// For now only text data is supported by the `pugl-rs` (and `pugl` itself supports only text data on windows)
-    pub fn copy_clipboard(&self, string: &str) -> bool {
+    pub fn copy_clipboard(&self, string: &str) -> Result<(), Status> {
         unsafe {
-            sys::puglSetClipboard(
+            Status::from_raw(sys::puglSetClipboard(
                 self.view,
                 c"text/plain".as_ptr(),
                 string.as_ptr() as _,
                 string.len(),
-            ) == sys::PUGL_SUCCESS
+            ))
+            .into_result()
         }
     }
 
     /// Request the current clipboard contents.
     ///
     /// A [`Event::Clipboard`] event will be sent to the view with the clipboard contents if it is present.
-    pub fn paste_clipboard(&self) -> bool {
-        unsafe { sys::puglPaste(self.view) == sys::PUGL_SUCCESS }
+    pub fn paste_clipboard(&self) -> Result<(), Status> {
+        self.paste_clipboard_as(&["text/plain"])
+    }
+
+    /// Request the current clipboard contents in one of the given MIME types.
+    ///
+    /// The types are listed in the order of preference: the first type that is offered by the clipboard will be accepted.
+    /// The offered types are compared without their parameters (like `;charset=utf-8`), and `text/plain` also accepts the X11 text targets (`UTF8_STRING`, `STRING` and `TEXT`).
+    /// A [`Event::Clipboard`] event will be sent to the view with the clipboard contents if any of the types are present.
+    /// An [`Event::ClipboardFailed`] event is sent instead if none of the types are present, or if the data doesn't arrive in time (see [`View::set_clipboard_timeout`]).
+    pub fn paste_clipboard_as(&self, types: &[&str]) -> Result<(), Status> {
+        unsafe {
+            let state = ViewState::<B>::from_raw(self.view);
+            *state.paste_types.lock().unwrap() = types.iter().map(|t| t.to_string()).collect();
+
+            // started first, since some platforms deliver the data before `puglPaste` returns
+            state.paste_pending.store(true, Ordering::Relaxed);
+            if let Some(timeout) = *state.clipboard_timeout.lock().unwrap() {
+                let deadline = Instant::now() + timeout;
+                self.world
+                    .set_deadline(self.view, Some(deadline), clipboard_timeout::<B>);
+            }
+
+            let status = Status::from_raw(sys::puglPaste(self.view)).into_result();
+            if status.is_err() {
+                state.finish_paste(self.view);
+            }
+
+            status
+        }
+    }
+
+    /// Set how long a paste waits for the clipboard data before an [`Event::ClipboardFailed`] is sent, or `None` to wait forever.
+    ///
+    /// On X11, the clipboard data is transferred by the application that owns the clipboard, which may be busy or stuck, so a paste could otherwise never finish.
+    /// Data that arrives after the timeout is still delivered as an [`Event::Clipboard`]. The default timeout is 5 seconds.
+    ///
+    /// The timeout is checked by [`World::update`], which waits at most until the timeout passes.
+    pub fn set_clipboard_timeout(&self, timeout: Option<Duration>) {
+        *unsafe { ViewState::<B>::from_raw(self.view) }
+            .clipboard_timeout
+            .lock()
+            .unwrap() = timeout;
     }
 
-    unsafe fn from_raw(view: *mut sys::PuglView) -> ManuallyDrop<View<B>> {
+    /// Request the current clipboard contents as text, and return a future that resolves with them.
+    ///
+    /// This hides the round-trip with the clipboard owner, so the pasted contents don't have to be handled in a separate [`Event::Clipboard`] event
+    /// (which is still sent to the view). The future only makes progress while the events of the world are dispatched,
+    /// so it must not be blocked on in the thread that runs [`World::update`].
+    pub fn paste_clipboard_async(&self) -> ClipboardPaste {
+        self.paste_clipboard_as_async(&["text/plain"])
+    }
+
+    /// Request the current clipboard contents in one of the given MIME types, see [`View::paste_clipboard_as`] and [`View::paste_clipboard_async`].
+    pub fn paste_clipboard_as_async(&self, types: &[&str]) -> ClipboardPaste {
+        let slot = Arc::new(Mutex::new(PasteSlot::default()));
+        let state = unsafe { ViewState::<B>::from_raw(self.view) };
+
+        // some platforms deliver the data before `puglPaste` returns
+        state.pastes.lock().unwrap().push(slot.clone());
+        if self.paste_clipboard_as(types).is_err() {
+            state.complete_pastes(None);
+        }
+
+        ClipboardPaste(slot)
+    }
+
+    /// Returns the MIME types of the data currently offered by the clipboard.
+    ///
+    /// Note that on some platforms (notably X11) the clipboard types are only known after the clipboard has been offered to the view,
+    /// so this will return the types of the most recent offer (i.e. after [`View::paste_clipboard`]), or nothing before that.
+    pub fn clipboard_types(&self) -> Vec<String> {
         unsafe {
-            ManuallyDrop::new(Self {
+            (0..sys::puglGetNumClipboardTypes(self.view))
+                .map(|i| sys::puglGetClipboardType(self.view, i))
+                .filter(|type_| !type_.is_null())
+                .map(|type_| CStr::from_ptr(type_).to_string_lossy().into_owned())
+                .collect()
+        }
+    }
+
+    /// Returns `true` if the clipboard contains text data.
+    ///
+    /// See [`View::clipboard_types`] for the caveats.
+    pub fn has_clipboard_text(&self) -> bool {
+        self.clipboard_types()
+            .iter()
+            .any(|type_| clipboard_type_rank(type_, "text/plain").is_some())
+    }
+
+    /// Returns a stream of the events received by this view.
+    ///
+    /// Events are delivered to the stream (in addition to the event handler) as they are dispatched by [`World::update`],
+    /// so the main loop still has to be driven for the stream to make progress.
+    /// The stream ends when the view is unrealized or dropped.
+    #[cfg(feature = "async")]
+    pub fn events(&self) -> crate::EventStream {
+        unsafe {
+            let queue = Arc::new(Mutex::new(crate::stream::EventQueue::default()));
+            ViewState::<B>::from_raw(self.view)
+                .streams
+                .lock()
+                .unwrap()
+                .push(queue.clone());
+            crate::EventStream(queue)
+        }
+    }
+
+    /// Returns a non-owning handle to this view.
+    pub fn proxy(&self) -> ViewProxy {
+        let id = self
+            .world
+            .views
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|&&(_, view)| view == self.view)
+            .map_or(0, |&(id, _)| id);
+        ViewProxy::new(&self.world, id, self.view)
+    }
+
+    /// Returns the underlying `PuglView` pointer.
+    ///
+    /// This can be used to call `pugl` functions that are not wrapped by `pugl-rs`.
+    /// The pointer stays valid for as long as this [`View`] is alive, and must not be freed manually.
+    pub fn as_pugl_ptr(&self) -> *mut sys::PuglView {
+        self.view
+    }
+
+    /// Releases the ownership of the underlying `PuglView` pointer, without freeing the view.
+    ///
+    /// The view stays registered with its world, and can be owned again with [`View::from_pugl_ptr`].
+    /// The view is leaked if it isn't, and the world must not be dropped before it.
+    pub fn into_pugl_ptr(self) -> *mut sys::PuglView {
+        let this = ManuallyDrop::new(self);
+        // the world was cloned by the view, and is cloned again by `from_pugl_ptr`
+        drop(unsafe { std::ptr::read(&this.world) });
+        this.view
+    }
+
+    /// Constructs a [`View`] from a `PuglView` pointer, taking ownership of it.
+    ///
+    /// # Safety
+    /// The view must have been created by `pugl-rs` with the same backend `B`, and must not be owned by any other [`View`]
+    /// (a pointer obtained with [`View::into_pugl_ptr`], but not with [`View::as_pugl_ptr`] while the original [`View`] is alive).
+    pub unsafe fn from_pugl_ptr(view: *mut sys::PuglView) -> Self {
+        unsafe {
+            Self {
                 view,
-                world: ManuallyDrop::into_inner(WorldInner::from_raw(sys::puglGetWorld(view))),
+                world: Arc::clone(&WorldInner::from_raw(sys::puglGetWorld(view))),
                 phantom: PhantomData,
-            })
+            }
+        }
+    }
+
+    /// Unlinks the view from its parent view and unrealizes its child views (see [`UnrealizedView::with_parent_view`])
+    unsafe fn detach_family(&self) {
+        unsafe {
+            self.detach_parent_view();
+
+            let children = take(&mut family(self.view).lock().unwrap().children);
+            for child in children {
+                family(child.view).lock().unwrap().parent = null_mut();
+                sys::puglUnrealize(child.view);
+            }
+        }
+    }
+
+    unsafe fn detach_parent_view(&self) {
+        unsafe {
+            let parent = replace(&mut family(self.view).lock().unwrap().parent, null_mut());
+            if !parent.is_null() {
+                family(parent)
+                    .lock()
+                    .unwrap()
+                    .children
+                    .retain(|child| child.view != self.view);
+            }
         }
     }
 }
 
 impl<B: Backend> Drop for View<B> {
     fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.world.check_thread();
+        }
+
         unsafe {
+            self.detach_family();
+
+            self.world
+                .views
+                .lock()
+                .unwrap()
+                .retain(|&(_, view)| view != self.view);
+            self.world
+                .closed
+                .lock()
+                .unwrap()
+                .retain(|&view| view != self.view);
+            self.world
+                .tab_order
+                .lock()
+                .unwrap()
+                .retain(|&(view, _)| view != self.view);
+
+            #[cfg(target_os = "linux")]
+            xembed::release(self.world().native().as_raw(), self.native().ptr);
+
+            let state = sys::puglGetHandle(self.view) as *mut ViewState<B>;
             sys::puglFreeView(self.view);
+
+            // a panic in the drop impl of the user state must not leak the rest of the view state
+            let released = catch_unwind(AssertUnwindSafe(|| (*state).release_handlers()));
+            drop(Box::from_raw(state));
+
+            if let Err(panic) = released
+                && !std::thread::panicking()
+            {
+                resume_unwind(panic);
+            }
         }
     }
 }
@@ -605,10 +1705,45 @@ pub enum ViewError {
     SetPixelFormat,
     /// Failed to allocate memory
     OutOfMemory,
+    /// The view was realized outside of the main thread on a platform that requires it (MacOS)
+    NotMainThread,
     /// Unknown error
     Unknown,
 }
 
+impl ViewError {
+    /// Returns the status reported by pugl, if any.
+    pub fn status(&self) -> Option<Status> {
+        match *self {
+            Self::BadConfig => Some(Status::BadConfiguration),
+            Self::BadBackend => Some(Status::BadBackend),
+            Self::BackendInit => Some(Status::BackendFailed),
+            Self::ClassRegister => Some(Status::RegistrationFailed),
+            Self::OsRealize => Some(Status::RealizeFailed),
+            Self::CreateContext => Some(Status::CreateContextFailed),
+            Self::SetPixelFormat => Some(Status::SetFormatFailed),
+            Self::OutOfMemory => Some(Status::NoMemory),
+            Self::NotMainThread | Self::Unknown => None,
+        }
+    }
+}
+
+impl From<Status> for ViewError {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::BadConfiguration => Self::BadConfig,
+            Status::BadBackend => Self::BadBackend,
+            Status::BackendFailed => Self::BackendInit,
+            Status::RegistrationFailed => Self::ClassRegister,
+            Status::RealizeFailed => Self::OsRealize,
+            Status::CreateContextFailed => Self::CreateContext,
+            Status::SetFormatFailed => Self::SetPixelFormat,
+            Status::NoMemory => Self::OutOfMemory,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 impl std::error::Error for ViewError {}
 impl fmt::Display for ViewError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -621,6 +1756,7 @@ impl fmt::Display for ViewError {
             Self::OsRealize => write!(f, "failed to create os window"),
             Self::SetPixelFormat => write!(f, "failed to set pixel format"),
             Self::OutOfMemory => write!(f, "out of memory"),
+            Self::NotMainThread => write!(f, "view must be realized on the main thread"),
             Self::Unknown => write!(f, "unknown error"),
         }
     }
@@ -651,29 +1787,954 @@ impl<B: Backend> fmt::Debug for UnrealizedView<B> {
     }
 }
 
-/// double boxing to make it ffi safe :c
-type EventHandler<B> = Mutex<Box<dyn FnMut(&View<B>, Event<B>) + Send>>;
+/// Marks the client events that carry an event queued by [`View::send_event`], see [`marker`].
+static SENT_EVENT: u8 = 0;
+
+/// Returns the first word of the client events sent by `pugl-rs` itself, which is the address of a private static,
+/// so it can't collide with the data of [`View::send_client_event`].
+pub(crate) fn marker(marker: &'static u8) -> usize {
+    marker as *const u8 as usize
+}
+
+unsafe fn send_event(view: *mut sys::PuglView, event: OwnedEvent) -> Result<(), Status> {
+    unsafe {
+        match event {
+            OwnedEvent::Realize | OwnedEvent::Unrealize => Err(Status::BadParameter),
+            OwnedEvent::Client { data } => send_client_event(view, data),
+            event => {
+                let sent = &header(view).sent;
+                sent.lock().unwrap().push_back(event);
+                let status = send_client_event(view, [marker(&SENT_EVENT), 0]);
+                if status.is_err() {
+                    sent.lock().unwrap().pop_back();
+                }
+
+                status
+            }
+        }
+    }
+}
+
+/// Returns how well an offered clipboard type matches a requested MIME type (lower is better), or `None` if it doesn't match.
+///
+/// The types are compared without their parameters, so `text/plain;charset=utf-8` is accepted as `text/plain`,
+/// and the X11 text targets are accepted as `text/plain` too.
+fn clipboard_type_rank(offered: &str, requested: &str) -> Option<usize> {
+    let (essence, parameters) = offered.split_once(';').unwrap_or((offered, ""));
+    let requested = requested.split(';').next().unwrap_or(requested).trim();
+    if !essence.trim().eq_ignore_ascii_case(requested) {
+        // `STRING` is Latin-1 and `TEXT` may use any encoding, so they are only used if nothing better is offered
+        let targets = ["UTF8_STRING", "STRING", "TEXT"];
+        return match requested.eq_ignore_ascii_case("text/plain") {
+            true => targets.iter().position(|&t| t == offered).map(|i| i + 1),
+            false => None,
+        };
+    }
+
+    // text in another encoding can't be decoded as UTF-8
+    let charset = parameters.split(';').find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    });
+
+    match charset {
+        Some(charset) if !charset.eq_ignore_ascii_case("utf-8") => None,
+        _ => Some(0),
+    }
+}
+
+pub(crate) unsafe fn send_client_event(
+    view: *mut sys::PuglView,
+    data: [usize; 2],
+) -> Result<(), Status> {
+    unsafe {
+        Status::from_raw(sys::puglSendEvent(
+            view,
+            &sys::PuglEvent {
+                client: sys::PuglClientEvent {
+                    type_: sys::PUGL_CLIENT,
+                    flags: sys::PUGL_IS_SEND_EVENT,
+                    data1: data[0],
+                    data2: data[1],
+                },
+            },
+        ))
+        .into_result()
+    }
+}
+
+pub(crate) unsafe fn send_close_event(view: *mut sys::PuglView) -> Result<(), Status> {
+    unsafe {
+        Status::from_raw(sys::puglSendEvent(
+            view,
+            &sys::PuglEvent {
+                any: sys::PuglAnyEvent {
+                    type_: sys::PUGL_CLOSE,
+                    flags: sys::PUGL_IS_SEND_EVENT,
+                },
+            },
+        ))
+        .into_result()
+    }
+}
+
+/// Per-view state, stored in the pugl view handle and owned by the [`View`].
+#[repr(C)]
+pub(crate) struct ViewState<B: Backend> {
+    // must be the first fields, so they can be accessed without knowing `B`, see `ViewHeader`
+    family: Mutex<ViewFamily>,
+    damage: Mutex<Damage>,
+    sent: Mutex<VecDeque<OwnedEvent>>,
+    #[cfg(feature = "cairo")]
+    backend_flags: AtomicU32,
+    /// The view passed to the event handlers, which borrows the world of the owning [`View`] instead of reconstructing it for every event
+    pub(crate) wrapper: ManuallyDrop<View<B>>,
+    handler: Mutex<Option<EventHandler<B>>>,
+    /// `true` while the main handler is running, see [`ViewState::call_handler`]
+    dispatching: AtomicBool,
+    /// Events dispatched while the main handler is running
+    nested: Mutex<Vec<OwnedEvent>>,
+    batch_handler: Mutex<Option<BatchEventHandler<B>>>,
+    /// Events queued for the batch handler, `None` if there is no batch handler
+    batch: Mutex<Option<Vec<OwnedEvent>>>,
+    paste_types: Mutex<Vec<String>>,
+    /// See [`UnrealizedView::with_unified_keyboard_events`]
+    unified_keyboard: AtomicBool,
+    /// See [`UnrealizedView::with_end_frame_events`]
+    end_frame: AtomicBool,
+    /// A key press waiting for the text event that may follow it
+    pending_key: Mutex<Option<(EventInput, u32, Key)>>,
+    /// The style of the last configure event, for [`Event::Configure`]'s `changed`
+    style: AtomicU32,
+    /// The scale of the last configure event as `f64` bits, zero before the first one
+    scale: AtomicU64,
+    /// See [`UnrealizedView::with_style`]
+    initial_style: AtomicU32,
+    /// See [`View::set_clipboard_timeout`]
+    clipboard_timeout: Mutex<Option<Duration>>,
+    /// `true` from [`View::paste_clipboard_as`] until the data arrives or the paste fails
+    paste_pending: AtomicBool,
+    /// See [`View::paste_clipboard_async`]
+    pastes: Mutex<Vec<Arc<Mutex<PasteSlot>>>>,
+    /// See [`View::set_scroll_config`]
+    scroll_config: Mutex<ScrollConfig>,
+    /// See [`View::when_mapped`]
+    when_mapped: Mutex<Vec<MappedCallback<B>>>,
+    /// See [`View::set_fixed_aspect`]
+    fixed_aspect: Mutex<Option<(u32, u32)>>,
+    /// See [`UnrealizedView::with_fallback_config`]
+    fallback_config: AtomicBool,
+    /// `true` while a view that failed to realize is unrealized to retry with a fallback configuration
+    retrying: AtomicBool,
+    /// See [`UnrealizedView::with_close_policy`]
+    close_policy: Mutex<ClosePolicy>,
+    /// Set by [`View::keep_open`] while the close event is handled
+    keep_open: AtomicBool,
+    /// Timers started with [`View::start_fixed_rate_timer`] or [`View::start_deadline_timer`]
+    pub(crate) timers: Timers,
+    /// See [`View::frame_stats`]
+    frames: Mutex<FrameHistory>,
+    /// See [`View::start_display_link`]
+    pub(crate) display_link: DisplayLinkState<B>,
+    /// See [`View::set_message_hook`]
+    #[cfg(target_os = "windows")]
+    message_hook: Mutex<Option<MessageHook<B>>>,
+    /// See [`View::set_cursor`]
+    #[cfg(target_os = "windows")]
+    pub(crate) cursor: Mutex<Option<subclass::Cursor>>,
+    #[cfg(feature = "async")]
+    streams: Mutex<Vec<Arc<Mutex<crate::stream::EventQueue>>>>,
+}
+
+/// The first fields of [`ViewState`], which don't depend on the backend.
+#[repr(C)]
+struct ViewHeader {
+    family: Mutex<ViewFamily>,
+    damage: Mutex<Damage>,
+    /// Events sent with [`View::send_event`] that wait for their client event
+    sent: Mutex<VecDeque<OwnedEvent>>,
+    /// Options of the backend of the view, see [`set_backend_flags`]
+    #[cfg(feature = "cairo")]
+    backend_flags: AtomicU32,
+}
+
+/// Views embedded with [`UnrealizedView::with_parent_view`]
+struct ViewFamily {
+    parent: *mut sys::PuglView,
+    children: Vec<ChildView>,
+}
+
+#[derive(Clone, Copy)]
+struct ChildView {
+    view: *mut sys::PuglView,
+    forward: unsafe fn(*mut sys::PuglView, Rect, f64),
+}
+
+impl<B: Backend> ViewState<B> {
+    /// The state must not outlive the [`View`] that owns `view` (and keeps `world` alive).
+    unsafe fn new(view: *mut sys::PuglView, world: &Arc<WorldInner>) -> Box<Self> {
+        Box::new(Self {
+            family: Mutex::new(ViewFamily {
+                parent: null_mut(),
+                children: Vec::new(),
+            }),
+            damage: Mutex::new(Damage::default()),
+            sent: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "cairo")]
+            backend_flags: AtomicU32::new(0),
+            wrapper: ManuallyDrop::new(View {
+                view,
+                // never dropped, so the reference count is not incremented
+                world: unsafe { Arc::from_raw(Arc::as_ptr(world)) },
+                phantom: PhantomData,
+            }),
+            handler: Mutex::new(None),
+            dispatching: AtomicBool::new(false),
+            nested: Mutex::new(Vec::new()),
+            batch_handler: Mutex::new(None),
+            batch: Mutex::new(None),
+            paste_types: Mutex::new(Vec::new()),
+            unified_keyboard: AtomicBool::new(false),
+            end_frame: AtomicBool::new(false),
+            style: AtomicU32::new(0),
+            scale: AtomicU64::new(0),
+            initial_style: AtomicU32::new(0),
+            clipboard_timeout: Mutex::new(Some(Duration::from_secs(5))),
+            paste_pending: AtomicBool::new(false),
+            pastes: Mutex::new(Vec::new()),
+            scroll_config: Mutex::new(ScrollConfig::default()),
+            when_mapped: Mutex::new(Vec::new()),
+            fixed_aspect: Mutex::new(None),
+            fallback_config: AtomicBool::new(false),
+            retrying: AtomicBool::new(false),
+            close_policy: Mutex::new(ClosePolicy::Ignore),
+            keep_open: AtomicBool::new(false),
+            timers: Timers::default(),
+            frames: Mutex::new(FrameHistory::default()),
+            display_link: DisplayLinkState::new(),
+            #[cfg(target_os = "windows")]
+            message_hook: Mutex::new(None),
+            #[cfg(target_os = "windows")]
+            cursor: Mutex::new(None),
+            pending_key: Mutex::new(None),
+            #[cfg(feature = "async")]
+            streams: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub(crate) unsafe fn from_raw<'a>(view: *mut sys::PuglView) -> &'a Self {
+        unsafe { &*(sys::puglGetHandle(view) as *const Self) }
+    }
+
+    /// Stops waiting for the clipboard data, returns `false` if no paste was pending.
+    unsafe fn finish_paste(&self, view: *mut sys::PuglView) -> bool {
+        self.wrapper
+            .world
+            .set_deadline(view, None, clipboard_timeout::<B>);
+        self.paste_pending.swap(false, Ordering::Relaxed)
+    }
+
+    /// Sends [`Event::ClipboardFailed`] if a paste is pending, and resolves the futures of [`View::paste_clipboard_async`] without contents.
+    unsafe fn fail_paste(&self, view: &View<B>) {
+        if unsafe { self.finish_paste(view.view) } {
+            self.deliver(view, Event::ClipboardFailed);
+        }
+
+        self.complete_pastes(None);
+    }
+
+    /// Resolves the futures of [`View::paste_clipboard_async`].
+    fn complete_pastes(&self, contents: Option<ClipboardContents>) {
+        let pastes = take(&mut *self.pastes.lock().unwrap_or_else(PoisonError::into_inner));
+        for paste in pastes {
+            paste
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .complete(contents.clone());
+        }
+    }
+
+    /// Accept the most preferred clipboard type (see [`View::paste_clipboard_as`]), returns `false` if none of them is offered.
+    unsafe fn accept_offer(
+        &self,
+        view: *mut sys::PuglView,
+        offer: &sys::PuglDataOfferEvent,
+    ) -> bool {
+        unsafe {
+            let offered = (0..sys::puglGetNumClipboardTypes(view))
+                .map(|i| sys::puglGetClipboardType(view, i))
+                .collect::<Vec<_>>();
+
+            for preferred in self.paste_types.lock().unwrap().iter() {
+                let best = offered
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, type_)| !type_.is_null())
+                    .filter_map(|(index, &type_)| {
+                        let type_ = CStr::from_ptr(type_).to_str().ok()?;
+                        Some((clipboard_type_rank(type_, preferred)?, index))
+                    })
+                    .min();
+
+                if let Some((_, index)) = best {
+                    sys::puglAcceptOffer(view, offer, index as u32);
+                    return true;
+                }
+            }
+
+            false
+        }
+    }
+
+    /// Dispatches an event and forwards configure events (with the changed style flags) to the child views.
+    pub(crate) fn deliver(&self, view: &View<B>, event: Event<B>) {
+        if let Event::Configure { rect, style, .. } = event {
+            let scale = view.system_scale();
+            let previous_scale = self.scale.swap(scale.to_bits(), Ordering::Relaxed);
+            if previous_scale != 0 && previous_scale != scale.to_bits() {
+                self.dispatch(view, Event::ScaleChange { scale });
+            }
+
+            let previous = self.style.swap(style.bits(), Ordering::Relaxed);
+            let changed = style ^ ViewStyle::from_bits_retain(previous);
+            self.dispatch(
+                view,
+                Event::Configure {
+                    rect,
+                    style,
+                    changed,
+                },
+            );
+
+            if style.contains(ViewStyle::MAPPED) {
+                let callbacks = take(&mut *self.when_mapped.lock().unwrap());
+                for callback in callbacks {
+                    callback(view);
+                }
+            }
+
+            let children = unsafe { family(view.view) }
+                .lock()
+                .unwrap()
+                .children
+                .clone();
+            let scale = view.system_scale();
+            for child in children {
+                unsafe { (child.forward)(child.view, rect, scale) };
+            }
+        } else {
+            self.dispatch(view, event);
+        }
+    }
+
+    /// Queues the event for the batch handler, or returns it back if it should be delivered to the main handler.
+    fn queue_batched<'a>(&self, event: Event<'a, B>) -> Option<Event<'a, B>> {
+        if matches!(
+            event,
+            Event::Realize { .. }
+                | Event::Unrealize { .. }
+                | Event::Update
+                | Event::Expose { .. }
+                | Event::EndFrame
+        ) {
+            return Some(event);
+        }
+
+        match self.batch.lock().as_deref_mut() {
+            Ok(Some(batch)) => {
+                batch.push(OwnedEvent::from(&event));
+                None
+            }
+            _ => Some(event),
+        }
+    }
+
+    /// Delivers the queued events to the batch handler.
+    fn flush_batch(&self, view: &View<B>) {
+        // the handler is taken out while it runs, so events dispatched by the handler itself are queued for the next batch
+        let Some(mut handler) = self
+            .batch_handler
+            .lock()
+            .ok()
+            .and_then(|mut handler| handler.take())
+        else {
+            return;
+        };
+
+        let events = match self.batch.lock().as_deref_mut() {
+            Ok(Some(batch)) => take(batch),
+            _ => Vec::new(),
+        };
+
+        if !events.is_empty() {
+            handler(view, &events);
+        }
+
+        if let Ok(mut slot) = self.batch_handler.lock()
+            && slot.is_none()
+        {
+            *slot = Some(handler);
+        }
+    }
+
+    /// Calls the main event handler, merging key presses with their text if enabled (see [`UnrealizedView::with_unified_keyboard_events`]).
+    fn call_keyboard_handler(&self, view: &View<B>, event: Event<B>) {
+        if !self.unified_keyboard.load(Ordering::Relaxed) {
+            return self.call_handler(view, event);
+        }
+
+        match event {
+            Event::KeyPress {
+                input,
+                keycode,
+                key,
+            } => {
+                self.flush_key(view);
+                *self
+                    .pending_key
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some((input, keycode, key));
+            }
+            Event::KeyText {
+                input,
+                keycode,
+                text,
+            } => {
+                // text of another key (like the result of an input method) doesn't belong to the pending key
+                let pending = self
+                    .pending_key
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .take_if(|&mut (_, pending, _)| pending == keycode);
+                if pending.is_none() {
+                    self.flush_key(view);
+                }
+
+                let (input, keycode, key) = pending.unwrap_or((input, keycode, Key::None));
+                self.call_handler(
+                    view,
+                    Event::KeyboardInput {
+                        input,
+                        keycode,
+                        key,
+                        text: Some(text),
+                    },
+                );
+            }
+            event => {
+                self.flush_key(view);
+                self.call_handler(view, event);
+            }
+        }
+    }
+
+    /// Delivers the held back key press without text.
+    fn flush_key(&self, view: &View<B>) {
+        let pending = self
+            .pending_key
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        if let Some((input, keycode, key)) = pending {
+            self.call_handler(
+                view,
+                Event::KeyboardInput {
+                    input,
+                    keycode,
+                    key,
+                    text: None,
+                },
+            );
+        }
+    }
+
+    /// Applies the [`ScrollConfig`] to a scroll event of the window system.
+    fn normalize_scroll<'a>(&self, view: &View<B>, event: Event<'a, B>) -> Event<'a, B> {
+        match event {
+            Event::Scroll {
+                input,
+                direction,
+                dx,
+                dy,
+            } => {
+                let config = *self.scroll_config.lock().unwrap();
+                let (direction, dx, dy) =
+                    config.normalize(direction, dx, dy, input.mods, view.system_scale());
+                Event::Scroll {
+                    input,
+                    direction,
+                    dx,
+                    dy,
+                }
+            }
+            event => event,
+        }
+    }
+
+    /// Delivers a synthesized event, see [`View::inject_event`].
+    fn inject(&self, view: &View<B>, event: OwnedEvent) -> bool {
+        if let OwnedEvent::Expose { rect } = event {
+            view.obscure_region(rect);
+            return true;
+        }
+
+        let Some(borrowed) = event.as_event::<B>() else {
+            return false;
+        };
+
+        if event == OwnedEvent::Close {
+            self.keep_open.store(false, Ordering::Relaxed);
+        }
+
+        self.deliver(view, borrowed);
+        if event == OwnedEvent::Close {
+            view.world.mark_closed(view.view);
+            self.apply_close_policy(view.view);
+        }
+
+        true
+    }
+
+    /// Applies the [`ClosePolicy`] after the close event was handled, unless the handler called [`View::keep_open`].
+    fn apply_close_policy(&self, view: *mut sys::PuglView) {
+        if self.keep_open.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        match *self.close_policy.lock().unwrap() {
+            ClosePolicy::Ignore => {}
+            ClosePolicy::Hide => unsafe {
+                sys::puglHide(view);
+            },
+            ClosePolicy::Unrealize => {
+                // unrealizing destroys the window, which can't be done while its events are dispatched.
+                // The views of the world must not be locked while the unrealize event is handled, since its handler may use them.
+                self.wrapper.proxy().defer(|proxy| {
+                    if proxy.is_alive() {
+                        unsafe { sys::puglUnrealize(proxy.view) };
+                    }
+                });
+            }
+        }
+    }
+
+    /// Calls the hook set with [`View::set_message_hook`], returns `None` if there is no hook or it didn't consume the message.
+    ///
+    /// The hook is taken out of its lock while it runs, so it can replace or remove itself.
+    #[cfg(target_os = "windows")]
+    pub(crate) fn call_message_hook(&self, message: WindowMessage) -> Option<isize> {
+        let mut hook = self.message_hook.lock().unwrap().take()?;
+        let view = &*self.wrapper;
+
+        let result = catch_unwind(AssertUnwindSafe(|| hook(view, message)));
+
+        let mut slot = self.message_hook.lock().unwrap();
+        if slot.is_none() {
+            *slot = Some(hook);
+        }
+        drop(slot);
+
+        match result {
+            Ok(result) => result,
+            Err(panic) => {
+                view.world.replace_poison(Some(panic));
+                None
+            }
+        }
+    }
+
+    /// Drops the event handlers, and with them the state captured by the user.
+    ///
+    /// Called after the view is unrealized, and again when the [`View`] is dropped in case the view was never realized.
+    fn release_handlers(&self) {
+        // stopped first, so no more refreshes are posted
+        let display_link_callback = self.display_link.release();
+        let handler = self
+            .handler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        let batch_handler = self
+            .batch_handler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        let when_mapped = take(
+            &mut *self
+                .when_mapped
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        #[cfg(target_os = "windows")]
+        let message_hook = self
+            .message_hook
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        // dropped outside of the locks, so the drop impls of the captured state can use the view
+        drop(handler);
+        drop(batch_handler);
+        drop(when_mapped);
+        drop(display_link_callback);
+        #[cfg(target_os = "windows")]
+        drop(message_hook);
+        #[cfg(target_os = "windows")]
+        self.cursor
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+
+    /// Calls the main event handler.
+    ///
+    /// Events dispatched while the handler is running (for example by [`View::set_size`] on some platforms) are queued,
+    /// and delivered once the handler returns instead of deadlocking. Nested exposes are turned into a redraw request.
+    fn call_handler(&self, view: &View<B>, event: Event<B>) {
+        if self.dispatching.swap(true, Ordering::Acquire) {
+            match event {
+                Event::Expose { rect, .. } => view.obscure_region(rect),
+                event => self
+                    .nested
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(OwnedEvent::from(&event)),
+            }
+            return;
+        }
+
+        let _dispatching = ResetOnDrop(&self.dispatching);
+        let Some(mut handler) = self
+            .handler
+            .lock()
+            .ok()
+            .and_then(|mut handler| handler.take())
+        else {
+            return;
+        };
+
+        handler(view, event);
+
+        loop {
+            let nested = take(&mut *self.nested.lock().unwrap_or_else(PoisonError::into_inner));
+            if nested.is_empty() {
+                break;
+            }
+
+            for event in &nested {
+                if let Some(event) = event.as_event::<B>() {
+                    handler(view, event);
+                }
+            }
+        }
+
+        if let Ok(mut slot) = self.handler.lock()
+            && slot.is_none()
+        {
+            *slot = Some(handler);
+        }
+    }
+
+    fn dispatch(&self, view: &View<B>, event: Event<B>) {
+        // the hook is taken out while it runs, so events it dispatches itself skip it instead of deadlocking
+        let hook = view.world.hook.lock().ok().and_then(|mut hook| hook.take());
+        if let Some(mut hook) = hook {
+            hook(&view.proxy(), &OwnedEvent::from(&event));
+
+            if let Ok(mut slot) = view.world.hook.lock()
+                && slot.is_none()
+            {
+                *slot = Some(hook);
+            }
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let mut streams = self.streams.lock().unwrap();
+            streams.retain(|queue| Arc::strong_count(queue) > 1);
+            if !streams.is_empty() {
+                let event = OwnedEvent::from(&event);
+                for queue in streams.iter() {
+                    queue.lock().unwrap().push(event.clone());
+                }
+            }
+        }
+
+        let slow = match view.world.slow.lock() {
+            Ok(slow) => slow
+                .as_ref()
+                .map(|slow| (slow.threshold, OwnedEvent::from(&event))),
+            Err(_) => None,
+        };
+
+        let start = Instant::now();
+        if matches!(event, Event::Update | Event::Expose { .. }) {
+            self.flush_batch(view);
+        }
+
+        if let Some(event) = self.queue_batched(event) {
+            self.call_keyboard_handler(view, event);
+        }
+
+        let elapsed = start.elapsed();
+        if let Some((threshold, event)) = slow
+            && elapsed > threshold
+            && let Ok(mut slow) = view.world.slow.lock()
+            && let Some(slow) = slow.as_mut()
+        {
+            (slow.callback)(&view.proxy(), &event, elapsed);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B: Backend> ViewState<B> {
+    fn close_streams(&self) {
+        let streams = take(&mut *self.streams.lock().unwrap_or_else(PoisonError::into_inner));
+        for queue in streams {
+            queue.lock().unwrap_or_else(PoisonError::into_inner).close();
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B: Backend> Drop for ViewState<B> {
+    fn drop(&mut self) {
+        self.close_streams();
+    }
+}
+
+unsafe fn header<'a>(view: *mut sys::PuglView) -> &'a ViewHeader {
+    unsafe { &*(sys::puglGetHandle(view) as *const ViewHeader) }
+}
+
+unsafe fn family<'a>(view: *mut sys::PuglView) -> &'a Mutex<ViewFamily> {
+    unsafe { &header(view).family }
+}
+
+/// Stores backend-specific options of a view created by `pugl-rs`, called by [`Backend::install`].
+#[cfg(feature = "cairo")]
+pub(crate) unsafe fn set_backend_flags(view: *mut sys::PuglView, flags: u32) {
+    unsafe { header(view).backend_flags.store(flags, Ordering::Relaxed) }
+}
+
+/// Returns the options stored with [`set_backend_flags`].
+#[cfg(feature = "cairo")]
+pub(crate) unsafe fn backend_flags(view: *mut sys::PuglView) -> u32 {
+    unsafe { header(view).backend_flags.load(Ordering::Relaxed) }
+}
+
+/// Submits the regions accumulated by [`View::obscure_region`] to `pugl`.
+pub(crate) unsafe fn flush_damage(view: *mut sys::PuglView) {
+    unsafe {
+        let regions = header(view)
+            .damage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        for rect in regions {
+            sys::puglObscureRegion(view, rect.x, rect.y, rect.w, rect.h);
+        }
+    }
+}
+
+/// Fails the pending paste of a view once the clipboard timeout passed, see [`View::set_clipboard_timeout`].
+unsafe fn clipboard_timeout<B: Backend>(raw_view: *mut sys::PuglView) {
+    unsafe {
+        let state = ViewState::<B>::from_raw(raw_view);
+        let view = &*state.wrapper;
+        let result = catch_unwind(AssertUnwindSafe(|| state.fail_paste(view)));
+        if let Err(panic) = result {
+            view.world.replace_poison(Some(panic));
+        }
+    }
+}
+
+/// Sends [`Event::EndFrame`] to a view that was exposed during [`World::update`].
+unsafe fn end_frame<B: Backend>(raw_view: *mut sys::PuglView) {
+    unsafe {
+        let state = ViewState::<B>::from_raw(raw_view);
+        let view = &*state.wrapper;
+        let result = catch_unwind(AssertUnwindSafe(|| state.deliver(view, Event::EndFrame)));
+        if let Err(panic) = result {
+            view.world.replace_poison(Some(panic));
+        }
+    }
+}
+
+unsafe fn forward_parent_configure<B: Backend>(
+    raw_view: *mut sys::PuglView,
+    rect: Rect,
+    scale: f64,
+) {
+    unsafe {
+        let state = ViewState::<B>::from_raw(raw_view);
+        state.dispatch(&state.wrapper, Event::ParentConfigure { rect, scale });
+    }
+}
+
+type EventHandler<B> = Box<dyn FnMut(&View<B>, Event<B>) + Send>;
+
+/// Clears the flag when dropped, even if the handler panics.
+pub(crate) struct ResetOnDrop<'a>(pub &'a AtomicBool);
+
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+type BatchEventHandler<B> = Box<dyn FnMut(&View<B>, &[OwnedEvent]) + Send>;
+type MappedCallback<B> = Box<dyn FnOnce(&View<B>) + Send>;
+#[cfg(target_os = "windows")]
+type MessageHook<B> = Box<dyn FnMut(&View<B>, WindowMessage) -> Option<isize> + Send>;
 
 unsafe extern "C" fn event_handler<B: Backend>(
     raw_view: *mut sys::PuglView,
     raw_event: *const sys::PuglEvent,
 ) -> sys::PuglStatus {
     unsafe {
-        let view = View::from_raw(raw_view);
-        let handler = sys::puglGetHandle(raw_view) as *mut EventHandler<B>;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!(
+            "pugl_event",
+            event = event_type_name((*raw_event).type_),
+            view = ?raw_view,
+        )
+        .entered();
+
+        let state = ViewState::<B>::from_raw(raw_view);
+        let view = &*state.wrapper;
+
+        let lookup_text = match (*raw_event).type_ {
+            sys::PUGL_TEXT => take_lookup_text(),
+            _ => None,
+        };
+
+        // the view was never realized as far as the user is concerned, so its handlers must survive the unrealize
+        if state.retrying.load(Ordering::Relaxed) {
+            return sys::PUGL_SUCCESS;
+        }
 
+        // a stale call to `View::keep_open` must not cancel the policy of this close event
+        if (*raw_event).type_ == sys::PUGL_CLOSE {
+            state.keep_open.store(false, Ordering::Relaxed);
+        }
+
+        let start = Instant::now();
         let result = catch_unwind(AssertUnwindSafe(|| {
-            if let Some(event) = Event::<B>::process(raw_view, raw_event) {
-                if !handler.is_null() {
-                    if let Ok(mut handler) = (&mut *handler).lock() {
-                        (handler)(&view, event);
+            if (*raw_event).type_ == sys::PUGL_DATA_OFFER
+                && !state.accept_offer(raw_view, &(*raw_event).offer)
+            {
+                state.fail_paste(view);
+            }
+
+            if (*raw_event).type_ == sys::PUGL_DATA {
+                state.finish_paste(raw_view);
+            }
+
+            if (*raw_event).type_ == sys::PUGL_CLIENT
+                && (*raw_event).client.data1 == marker(&DISPLAY_LINK_EVENT)
+            {
+                state.display_link.refresh(view, (*raw_event).client.data2);
+                return;
+            }
+
+            if (*raw_event).type_ == sys::PUGL_CONFIGURE {
+                state.display_link.update_display(raw_view);
+            }
+
+            if (*raw_event).type_ == sys::PUGL_CLIENT
+                && (*raw_event).client.data1 == marker(&SENT_EVENT)
+            {
+                let event = header(raw_view).sent.lock().unwrap().pop_front();
+                if let Some(event) = event {
+                    state.inject(view, event);
+                }
+
+                return;
+            }
+
+            if (sys::PUGL_KEY_PRESS..=sys::PUGL_SCROLL).contains(&(*raw_event).type_) {
+                *view.world.last_input.lock().unwrap() = start;
+            }
+
+            let scheduled = (*raw_event).type_ != sys::PUGL_TIMER
+                || state.timers.reschedule(raw_view, (*raw_event).timer.id);
+
+            if scheduled
+                && let Some(event) =
+                    Event::<B>::process(raw_view, raw_event, lookup_text.as_deref())
+            {
+                let dead_key = match event {
+                    Event::KeyPress { input, keycode, .. } => {
+                        dead_key(view.world(), keycode, input.mods).map(|accent| Event::DeadKey {
+                            input,
+                            keycode,
+                            accent,
+                        })
+                    }
+                    _ => None,
+                };
+
+                let pasted = ClipboardContents::from_event(&event);
+
+                state.deliver(view, state.normalize_scroll(view, event));
+                if let Some(dead_key) = dead_key {
+                    state.deliver(view, dead_key);
+                }
+
+                if pasted.is_some() {
+                    state.complete_pastes(pasted);
+                }
+
+                if (*raw_event).type_ == sys::PUGL_EXPOSE {
+                    state.frames.lock().unwrap().record(start, start.elapsed());
+
+                    if state.end_frame.load(Ordering::Relaxed) {
+                        if view.world.updating.load(Ordering::Relaxed) {
+                            let mut exposed = view.world.exposed.lock().unwrap();
+                            if !exposed.iter().any(|&(exposed, _)| exposed == raw_view) {
+                                exposed.push((raw_view, end_frame::<B>));
+                            }
+                        } else {
+                            state.deliver(view, Event::EndFrame);
+                        }
                     }
                 }
+
+                let mut stats = view.world.stats.lock().unwrap();
+                stats.events += 1;
+                stats.busy += start.elapsed();
+            }
+
+            // there is no update event to wait for outside of `World::update`
+            if !view.world.updating.load(Ordering::Relaxed) {
+                state.flush_batch(view);
+            }
+
+            if (*raw_event).type_ == sys::PUGL_UNREALIZE {
+                state.release_handlers();
+                state.complete_pastes(None);
             }
         }));
 
+        // the damage is submitted once per frame, or after every event if the events are dispatched outside of `World::update`
+        // (for example by the event loop of a plugin host)
+        if (*raw_event).type_ == sys::PUGL_UPDATE || !view.world.updating.load(Ordering::Relaxed) {
+            flush_damage(raw_view);
+        }
+
+        if (*raw_event).type_ == sys::PUGL_CLOSE {
+            view.world.mark_closed(raw_view);
+            state.apply_close_policy(raw_view);
+        }
+
+        #[cfg(feature = "async")]
         if (*raw_event).type_ == sys::PUGL_UNREALIZE {
-            drop(Box::from_raw(handler));
+            state.close_streams();
         }
 
         if let Err(panic) = result {
@@ -683,3 +2744,33 @@ unsafe extern "C" fn event_handler<B: Backend>(
         sys::PUGL_SUCCESS
     }
 }
+
+#[cfg(feature = "tracing")]
+fn event_type_name(type_: sys::PuglEventType) -> &'static str {
+    match type_ {
+        sys::PUGL_REALIZE => "realize",
+        sys::PUGL_UNREALIZE => "unrealize",
+        sys::PUGL_CONFIGURE => "configure",
+        sys::PUGL_UPDATE => "update",
+        sys::PUGL_EXPOSE => "expose",
+        sys::PUGL_CLOSE => "close",
+        sys::PUGL_FOCUS_IN => "focus_in",
+        sys::PUGL_FOCUS_OUT => "focus_out",
+        sys::PUGL_KEY_PRESS => "key_press",
+        sys::PUGL_KEY_RELEASE => "key_release",
+        sys::PUGL_TEXT => "text",
+        sys::PUGL_POINTER_IN => "pointer_in",
+        sys::PUGL_POINTER_OUT => "pointer_out",
+        sys::PUGL_BUTTON_PRESS => "button_press",
+        sys::PUGL_BUTTON_RELEASE => "button_release",
+        sys::PUGL_MOTION => "motion",
+        sys::PUGL_SCROLL => "scroll",
+        sys::PUGL_CLIENT => "client",
+        sys::PUGL_TIMER => "timer",
+        sys::PUGL_LOOP_ENTER => "loop_enter",
+        sys::PUGL_LOOP_LEAVE => "loop_leave",
+        sys::PUGL_DATA_OFFER => "data_offer",
+        sys::PUGL_DATA => "data",
+        _ => "unknown",
+    }
+}
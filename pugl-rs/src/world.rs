@@ -1,22 +1,80 @@
-use crate::{Backend, UnrealizedView, sys};
+use crate::{
+    Backend, OwnedEvent, Status, UnrealizedView, ViewProxy, WorldInstant, sys,
+    view::{ResetOnDrop, flush_damage, send_close_event},
+    with_c_str,
+};
 use std::{
     any::Any,
     ffi::CStr,
-    mem::{ManuallyDrop, replace},
+    mem::{ManuallyDrop, replace, take},
     os::raw::c_void,
     panic::resume_unwind,
-    sync::{Arc, Mutex},
-    time::Duration,
+    ptr::null_mut,
+    sync::{
+        Arc, Mutex, OnceLock, PoisonError, Weak,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
 };
 
 /// World creation/update error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct WorldError;
+pub enum WorldError {
+    /// Failed to create the world, for example because the display server is not available
+    Create,
+    /// Failed to process events in [`World::update`], with the status reported by pugl
+    Update(Status),
+    /// Failed to create the hidden view used by [`World::waker`], with the status reported by pugl
+    Waker(Status),
+    /// The world was used outside of the main thread on a platform that requires it (MacOS)
+    NotMainThread,
+    /// The world is owned by another thread, see [`World#thread-safety`]
+    WrongThread,
+    /// [`World::update`] was called while the world is already being updated,
+    /// for example from an event handler through another handle of [`World::module_shared`]
+    Reentrant,
+}
+
+impl WorldError {
+    /// Returns the status reported by pugl, if any.
+    pub fn status(&self) -> Option<Status> {
+        match *self {
+            Self::Create | Self::NotMainThread | Self::WrongThread | Self::Reentrant => None,
+            Self::Update(status) | Self::Waker(status) => Some(status),
+        }
+    }
+}
 
 impl std::error::Error for WorldError {}
 impl std::fmt::Display for WorldError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "unknown pugl world error")
+        match *self {
+            Self::Create => write!(f, "failed to create world"),
+            Self::Update(status) => write!(f, "failed to update world: {}", status),
+            Self::Waker(status) => write!(f, "failed to create world waker: {}", status),
+            Self::NotMainThread => write!(f, "world must be used on the main thread"),
+            Self::WrongThread => write!(f, "world is owned by another thread"),
+            Self::Reentrant => write!(f, "world is already being updated"),
+        }
+    }
+}
+
+/// Returns `false` if called outside of the main thread on a platform that requires the event loop
+/// and the windows to live on the main thread (AppKit on MacOS).
+pub(crate) fn is_main_thread() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        unsafe extern "C" {
+            fn pthread_main_np() -> std::os::raw::c_int;
+        }
+
+        unsafe { pthread_main_np() != 0 }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        true
     }
 }
 
@@ -26,12 +84,133 @@ impl std::fmt::Display for WorldError {
 /// Several worlds can be created in a single process,
 /// but code using different worlds must be isolated so they are never mixed.
 /// Views are strongly associated with the world they were created in.
+///
+/// Every view keeps its world alive: dropping a [`World`] while some of its views still exist only releases this handle,
+/// and the underlying `pugl` world is freed once the last view is dropped.
+///
+/// # Thread safety
+/// `pugl` itself is not thread-safe, so a world and its views must only be used from one thread: the thread that runs the event loop.
+/// - X11: all calls go through a single `Display` connection, which is only safe to share for sending events (see [`WorldWaker`]).
+/// - Windows: a window only receives messages on the thread that created it, so views must be realized on the event loop thread.
+/// - MacOS: AppKit must only be used on the main thread, which is checked at runtime (see [`WorldError::NotMainThread`]).
+///
+/// Worlds and views are [`Send`], so they can be created on one thread and moved to the thread that runs the event loop,
+/// but they are not [`Sync`] unless the `unsafe-sync` feature is enabled.
+/// The first thread that runs [`World::update`] or realizes a view owns the world. Afterwards, [`World::update`] returns [`WorldError::WrongThread`]
+/// on another thread, and realizing or dropping a view there panics. [`WorldWaker`] can be used from any thread, and [`ViewProxy`] forwards its requests to the thread running the event loop.
 #[repr(transparent)]
 pub struct World(Arc<WorldInner>);
 
 unsafe impl Send for World {}
+#[cfg(feature = "unsafe-sync")]
 unsafe impl Sync for World {}
 
+/// Controls how [`World::run`] waits for events in the next iteration of the main loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ControlFlow {
+    /// Process pending events without blocking. Used for continuously animating programs.
+    Poll,
+    /// Block until an event is received.
+    Wait,
+    /// Block until an event is received or the deadline is reached.
+    WaitUntil(Instant),
+    /// Exit the main loop.
+    Exit,
+}
+
+/// Statistics of a single [`World::update`] call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct UpdateStats {
+    /// `true` if an event was received, `false` if the timeout was reached
+    pub received: bool,
+    /// Number of events dispatched to the event handlers of the views
+    pub events: usize,
+    /// Time spent dispatching the events (not including the time spent waiting for them)
+    pub busy: Duration,
+    /// Total time spent in the update, including waiting for events, deferred functions and idle callbacks
+    pub duration: Duration,
+}
+
+/// An identifier of an idle callback, see [`World::add_idle_callback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IdleCallbackId(usize);
+
+type IdleCallback = Box<dyn FnMut(&World) + Send>;
+pub(crate) type Deferred = Box<dyn FnOnce(&World) + Send>;
+pub(crate) type EventHook = Box<dyn FnMut(&ViewProxy, &OwnedEvent) + Send>;
+type SlowEventCallback = Box<dyn FnMut(&ViewProxy, &OwnedEvent, Duration) + Send>;
+type ViewCallback = unsafe fn(*mut sys::PuglView);
+
+pub(crate) struct SlowEventHandler {
+    pub threshold: Duration,
+    pub callback: SlowEventCallback,
+}
+
+#[derive(Default)]
+pub(crate) struct IdleCallbacks {
+    next_id: usize,
+    callbacks: Vec<(IdleCallbackId, IdleCallback)>,
+    /// `true` while the callbacks are taken out of the lock to run them
+    running: bool,
+    /// Callbacks removed while they were running, only recorded then (so the list doesn't grow without updates)
+    removed: Vec<IdleCallbackId>,
+}
+
+/// A handle that can wake up the main loop of a [`World`] from any thread.
+///
+/// Calling [`WorldWaker::wake`] interrupts a blocking [`World::update`] (or [`World::run`]) call,
+/// which can be used to make the main loop pick up work queued by other threads (like audio or network threads).
+///
+/// See [`World::waker`].
+#[derive(Clone)]
+pub struct WorldWaker {
+    view: Arc<Mutex<WakerView>>,
+    quit: Arc<AtomicBool>,
+}
+
+impl WorldWaker {
+    /// Request the main loop to quit and wake it up, see [`World::quit`].
+    pub fn quit(&self) -> bool {
+        self.quit.store(true, Ordering::Relaxed);
+        self.wake()
+    }
+
+    /// Wake up the main loop.
+    ///
+    /// Returns `false` if the world no longer exists or the wake up event could not be sent.
+    pub fn wake(&self) -> bool {
+        let view = self.view.lock().unwrap();
+        if view.0.is_null() {
+            return false;
+        }
+
+        unsafe {
+            sys::puglSendEvent(
+                view.0,
+                &sys::PuglEvent {
+                    client: sys::PuglClientEvent {
+                        type_: sys::PUGL_CLIENT,
+                        flags: sys::PUGL_IS_SEND_EVENT,
+                        data1: 0,
+                        data2: 0,
+                    },
+                },
+            ) == sys::PUGL_SUCCESS
+        }
+    }
+}
+
+impl std::fmt::Debug for WorldWaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorldWaker").finish_non_exhaustive()
+    }
+}
+
+/// A hidden view used as a target for [`WorldWaker`] events.
+pub(crate) struct WakerView(*mut sys::PuglView);
+
+unsafe impl Send for WakerView {}
+
 ///A pointer to the native handle of the world.
 /// - X11: A pointer to the `Display`.
 /// - MacOS: A pointer to the `NSApplication`.
@@ -61,11 +240,23 @@ impl World {
     /// Create a new world in a `PROGRAM` mode.
     ///
     /// Used for top-level applications.
+    /// Thread support is enabled for the world, so it can be woken up from other threads with a [`WorldWaker`].
+    ///
+    /// On Windows, the process is made per-monitor DPI aware, unless it already is (see [`set_dpi_awareness`](crate::set_dpi_awareness)).
+    ///
+    /// On MacOS this must be called on the main thread, otherwise [`WorldError::NotMainThread`] is returned.
     pub fn new_program() -> Result<Self, WorldError> {
+        if !is_main_thread() {
+            return Err(WorldError::NotMainThread);
+        }
+
+        // must happen before the first window of the process is created
+        crate::set_dpi_awareness(crate::DpiAwareness::PerMonitorV2);
+
         unsafe {
-            let world = sys::puglNewWorld(sys::PUGL_PROGRAM, 0);
+            let world = sys::puglNewWorld(sys::PUGL_PROGRAM, sys::PUGL_WORLD_THREADS);
             if world.is_null() {
-                Err(WorldError)
+                Err(WorldError::Create)
             } else {
                 Ok(Self(WorldInner::wrap(world)))
             }
@@ -75,29 +266,63 @@ impl World {
     /// Create a new world in a `MODULE` mode.
     ///
     /// Used for plugins or modules within a larger applications.
+    ///
+    /// On MacOS this must be called on the main thread, otherwise [`WorldError::NotMainThread`] is returned.
     pub fn new_module() -> Result<Self, WorldError> {
+        if !is_main_thread() {
+            return Err(WorldError::NotMainThread);
+        }
+
         unsafe {
             let world = sys::puglNewWorld(sys::PUGL_MODULE, sys::PUGL_WORLD_THREADS);
             if world.is_null() {
-                Err(WorldError)
+                Err(WorldError::Create)
             } else {
                 Ok(Self(WorldInner::wrap(world)))
             }
         }
     }
 
+    /// Returns the `MODULE` world shared by the whole process, creating it if necessary.
+    ///
+    /// Plugin formats often create many editors in a single process, and using several worlds in one process is error-prone,
+    /// so plugins should prefer this over [`World::new_module`].
+    /// The world is reference counted: it is created on the first call and destroyed once every [`World`] returned by this function
+    /// (and every view created in it) is dropped, after which the next call creates a new world.
+    ///
+    /// The shared world is owned by the thread that calls this function first, see [`World#thread-safety`].
+    /// Calling it on another thread while the world exists returns [`WorldError::WrongThread`].
+    pub fn module_shared() -> Result<Self, WorldError> {
+        struct SharedWorld(Weak<WorldInner>);
+        unsafe impl Send for SharedWorld {}
+
+        static SHARED: Mutex<SharedWorld> = Mutex::new(SharedWorld(Weak::new()));
+
+        let mut shared = SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(world) = shared.0.upgrade() {
+            // handles on several threads could update the non thread-safe world at the same time
+            return match world.bind_thread() {
+                true => Ok(Self(world)),
+                false => Err(WorldError::WrongThread),
+            };
+        }
+
+        let world = Self::new_module()?;
+        world.0.bind_thread();
+        shared.0 = Arc::downgrade(&world.0);
+        Ok(world)
+    }
+
     /// Sets the application class name.
     ///
     /// This is a stable identifier for the application, which should be a short camel-case name like "MyApp". This should be the same for every instance of the application, but different from any other application.
     /// On X11 and Windows, it is used to set the class name of windows (that underlie realized views), which is used for things like loading configuration, or custom window management rules.
+    ///
+    /// Nul bytes can't be a part of the class name, so they are silently removed (`"a\0b"` becomes `"ab"`).
     pub fn with_class_name(self, string: &str) -> Self {
-        unsafe {
-            sys::puglSetWorldString(
-                self.0.raw,
-                sys::PUGL_CLASS_NAME,
-                string.as_ptr() as *const _,
-            );
-        }
+        with_c_str(string, |string| unsafe {
+            sys::puglSetWorldString(self.0.raw, sys::PUGL_CLASS_NAME, string.as_ptr());
+        });
         self
     }
 
@@ -112,33 +337,312 @@ impl World {
         }
     }
 
-    /// Return the time in seconds
+    /// Return the current time.
     ///
-    /// This is a monotonically increasing clock with high resolution. The returned time is only useful to compare against other times returned by this function, its absolute value has no meaning.
-    pub fn time(&self) -> f64 {
-        unsafe { sys::puglGetTime(self.0.raw) }
+    /// This is a monotonically increasing clock with high resolution. The returned time is only useful to compare against other times returned by this function
+    /// (including event timestamps), its absolute value has no meaning.
+    pub fn time(&self) -> WorldInstant {
+        WorldInstant::from_secs_f64(unsafe { sys::puglGetTime(self.0.raw) })
     }
 
     /// Update by processing events from the window system.
     /// - This function is a single iteration of the main loop, and should be called repeatedly to update all views.
     /// - If `timeout` is `None`, this function will block until an event is received. If `timeout` is `Some(duration)`, this function will block for at most `duration` before returning.
     /// - For continuously animating programs, a timeout that is a reasonable fraction of the ideal frame period should be used, to minimize input latency by ensuring that as many input events are consumed as possible before drawing.
-    /// - Returns `true` if an event was received, `false` if the timeout was reached
-    pub fn update(&mut self, timeout: Option<Duration>) -> Result<bool, WorldError> {
+    /// - Returns the statistics of the update (see [`UpdateStats`]), which can be used to decide whether to keep draining events before rendering.
+    /// - On MacOS this must be called on the main thread, otherwise [`WorldError::NotMainThread`] is returned.
+    /// - Returns [`WorldError::WrongThread`] if the world is owned by another thread, and [`WorldError::Reentrant`] if it is already being updated.
+    pub fn update(&mut self, timeout: Option<Duration>) -> Result<UpdateStats, WorldError> {
+        if !is_main_thread() {
+            return Err(WorldError::NotMainThread);
+        }
+
+        if !self.0.bind_thread() {
+            return Err(WorldError::WrongThread);
+        }
+
+        // several handles of a shared world don't get the exclusive access of `&mut self`
+        if self.0.in_update.swap(true, Ordering::Acquire) {
+            return Err(WorldError::Reentrant);
+        }
+        let _in_update = ResetOnDrop(&self.0.in_update);
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("pugl_update", timeout = ?timeout).entered();
+
         unsafe {
+            let start = Instant::now();
+            let deadline = self
+                .0
+                .next_deadline()
+                .map(|d| d.saturating_duration_since(start));
+            let timeout = match (timeout, deadline) {
+                (Some(timeout), Some(deadline)) => Some(timeout.min(deadline)),
+                (timeout, deadline) => timeout.or(deadline),
+            };
             let timeout = timeout.map(|d| d.as_secs_f64()).unwrap_or(-1.0);
-            let result = match sys::puglUpdate(self.0.raw, timeout) {
-                sys::PUGL_SUCCESS => Ok(true),
-                sys::PUGL_FAILURE => Ok(false),
-                _ => Err(WorldError),
+            *self.0.stats.lock().unwrap() = UpdateStats::default();
+
+            // regions obscured outside of the event handlers
+            for (_, view) in self.0.views.lock().unwrap().clone() {
+                flush_damage(view);
+            }
+
+            self.0.updating.store(true, Ordering::Relaxed);
+            let status = sys::puglUpdate(self.0.raw, timeout);
+            self.0.updating.store(false, Ordering::Relaxed);
+
+            #[cfg(target_os = "linux")]
+            crate::xembed::dispatch(sys::puglGetNativeWorld(self.0.raw) as *mut c_void);
+
+            // a view may have been dropped by an event handler after it was exposed
+            let exposed = take(&mut *self.0.exposed.lock().unwrap());
+            for (view, end_frame) in exposed {
+                if self.0.views.lock().unwrap().iter().any(|&(_, v)| v == view) {
+                    end_frame(view);
+                }
+            }
+
+            self.0.expire_deadlines();
+
+            let signaled = self.0.close_views_if_signaled();
+            let stats = *self.0.stats.lock().unwrap();
+            let result = match status {
+                sys::PUGL_SUCCESS => Ok(UpdateStats {
+                    received: true,
+                    ..stats
+                }),
+                sys::PUGL_FAILURE => Ok(stats),
+                // waiting for events is interrupted by the signal itself, which is reported as an unknown error
+                sys::PUGL_UNKNOWN_ERROR if signaled => Ok(stats),
+                status => Err(WorldError::Update(Status::from_raw(status))),
             };
 
             if let Some(poison) = self.0.replace_poison(None) {
                 resume_unwind(poison);
             }
 
-            result
+            if result.is_ok() {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::trace_span!("pugl_idle").entered();
+
+                self.run_deferred();
+                self.run_idle_callbacks();
+            }
+
+            result.map(|stats| UpdateStats {
+                duration: start.elapsed(),
+                ..stats
+            })
+        }
+    }
+
+    /// Add a callback that is called once per [`World::update`] call, after the events are dispatched.
+    ///
+    /// This is a place for polling work that has to happen on the main thread regularly,
+    /// like synchronizing parameters with the audio thread, without abusing timers.
+    /// Note that a blocking [`World::update`] only returns after an event was received, so the callback is not called while waiting.
+    pub fn add_idle_callback(
+        &self,
+        callback: impl FnMut(&World) + Send + 'static,
+    ) -> IdleCallbackId {
+        let mut idle = self.0.idle.lock().unwrap();
+        let id = IdleCallbackId(idle.next_id);
+        idle.next_id += 1;
+        idle.callbacks.push((id, Box::new(callback)));
+        id
+    }
+
+    /// Remove an idle callback added with [`World::add_idle_callback`].
+    pub fn remove_idle_callback(&self, id: IdleCallbackId) {
+        let mut idle = self.0.idle.lock().unwrap();
+        idle.callbacks.retain(|(callback, _)| *callback != id);
+        if idle.running {
+            idle.removed.push(id);
+        }
+    }
+
+    /// Queue a closure to run on the main loop thread during the next [`World::update`] call.
+    ///
+    /// The main loop is woken up if a [`WorldWaker`] was created for this world (see [`World::waker`]),
+    /// otherwise the closure runs whenever the main loop wakes up next.
+    pub fn spawn(&self, f: impl FnOnce(&World) + Send + 'static) {
+        self.0.defer(Box::new(f));
+    }
+
+    fn run_deferred(&self) {
+        let deferred = take(&mut *self.0.deferred.lock().unwrap());
+        for f in deferred {
+            f(self);
+        }
+    }
+
+    fn run_idle_callbacks(&self) {
+        // callbacks are taken out of the lock, so they can add or remove callbacks themselves
+        let mut idle = self.0.idle.lock().unwrap();
+        let mut callbacks = take(&mut idle.callbacks);
+        // left over if a callback panicked in the previous update
+        idle.removed.clear();
+        idle.running = true;
+        drop(idle);
+
+        for (_, callback) in callbacks.iter_mut() {
+            callback(self);
+        }
+
+        let mut idle = self.0.idle.lock().unwrap();
+        idle.running = false;
+        let removed = take(&mut idle.removed);
+        callbacks.retain(|(id, _)| !removed.contains(id));
+        callbacks.append(&mut idle.callbacks);
+        idle.callbacks = callbacks;
+    }
+
+    /// Run the main loop until every view of the world is closed, or [`World::quit`] is called.
+    ///
+    /// A view counts as closed once it received an [`Event::Close`](crate::Event::Close) or was dropped.
+    pub fn run_until_all_views_closed(&mut self) -> Result<(), WorldError> {
+        self.run(|world| {
+            let views = world.0.views.lock().unwrap();
+            let closed = world.0.closed.lock().unwrap();
+            if views.iter().all(|(_, view)| closed.contains(view)) {
+                ControlFlow::Exit
+            } else {
+                ControlFlow::Wait
+            }
+        })
+    }
+
+    /// Process pending events without blocking.
+    ///
+    /// Equivalent to `update(Some(Duration::ZERO))`: events that are already queued are processed and the function returns immediately,
+    /// with [`UpdateStats::received`] set to `false` if there were none.
+    ///
+    /// This is meant for driving the world from an external event loop (like `tokio`, `calloop` or `mio`) instead of a dedicated blocking loop.
+    /// On X11 the world implements [`AsFd`](std::os::fd::AsFd), so the loop can be woken up once the X11 connection becomes readable.
+    ///
+    /// Note that Xlib buffers events internally, so events may already be pending even when the connection is not readable.
+    /// This should also be called once before waiting on the connection.
+    pub fn poll(&mut self) -> Result<UpdateStats, WorldError> {
+        self.update(Some(Duration::ZERO))
+    }
+
+    /// Block until an event is received and process it.
+    ///
+    /// Equivalent to `update(None)`.
+    pub fn wait(&mut self) -> Result<UpdateStats, WorldError> {
+        self.update(None)
+    }
+
+    /// Process all pending events without blocking, the same as [`World::poll`].
+    pub fn dispatch_pending(&mut self) -> Result<UpdateStats, WorldError> {
+        self.poll()
+    }
+
+    /// Send an [`Event::Close`](crate::Event::Close) to every view of the world when the process receives `SIGINT` or `SIGTERM`.
+    ///
+    /// This replaces the default handlers of these signals (which terminate the process immediately),
+    /// so that command-line tools can shut down their windows cleanly on Ctrl+C.
+    /// The close events are sent from [`World::update`], which means a program using [`World::run_until_all_views_closed`]
+    /// returns from the main loop once the views are closed, and views that ignore [`Event::Close`](crate::Event::Close) stay open.
+    ///
+    /// The previous handlers are restored when the world is dropped (or when the last of several worlds that handle the signals is dropped).
+    ///
+    /// Only available on Unix platforms.
+    #[cfg(unix)]
+    pub fn close_views_on_signal(&self) {
+        let mut seen = self.0.signals.lock().unwrap();
+        if seen.is_none() {
+            signals::install();
+        }
+
+        *seen = Some(SIGNALS.load(Ordering::Relaxed));
+    }
+
+    /// Request the main loop to quit.
+    ///
+    /// This makes [`World::run`] (and [`World::run_until_all_views_closed`]) return after the current iteration,
+    /// and wakes up a blocking [`World::update`] if a [`WorldWaker`] was created.
+    /// This can be called from the event handlers, or from other threads with [`WorldWaker::quit`].
+    ///
+    /// [`World::update`] and [`World::poll`] are not affected, so an external event loop that drives the world with them
+    /// has to check [`World::is_quitting`] after each call instead.
+    pub fn quit(&self) {
+        self.0.waker().quit();
+    }
+
+    /// Returns `true` if [`World::quit`] was called and the main loop has not returned yet.
+    ///
+    /// The request is only cleared when [`World::run`] returns, so without [`World::run`] it stays set once made.
+    pub fn is_quitting(&self) -> bool {
+        self.0.quit.load(Ordering::Relaxed)
+    }
+
+    /// Run the main loop until the handler returns [`ControlFlow::Exit`] or [`World::quit`] is called.
+    ///
+    /// The handler is called once per iteration of the main loop, before waiting for events, and decides how the next iteration waits.
+    /// Events are delivered to the views' event handlers as usual, so the handler is only responsible for the control flow.
+    pub fn run(
+        &mut self,
+        mut handler: impl FnMut(&World) -> ControlFlow,
+    ) -> Result<(), WorldError> {
+        loop {
+            if self.0.quit.swap(false, Ordering::Relaxed) {
+                return Ok(());
+            }
+
+            let timeout = match handler(self) {
+                ControlFlow::Exit => return Ok(()),
+                ControlFlow::Poll => Some(Duration::ZERO),
+                ControlFlow::Wait => None,
+                ControlFlow::WaitUntil(deadline) => {
+                    Some(deadline.saturating_duration_since(Instant::now()))
+                }
+            };
+
+            self.update(timeout)?;
+        }
+    }
+
+    /// Returns a handle that can wake up the main loop from any thread.
+    ///
+    /// Pugl has no way to wake up the main loop by itself, so the world creates a hidden view the first time this is called,
+    /// which is then used as the target for wake up events. This should be called from the main thread.
+    pub fn waker(&self) -> Result<WorldWaker, WorldError> {
+        if !is_main_thread() {
+            return Err(WorldError::NotMainThread);
         }
+
+        let mut view = self.0.waker.lock().unwrap();
+        if view.0.is_null() {
+            unsafe {
+                let raw = sys::puglNewView(self.0.raw);
+                if raw.is_null() {
+                    return Err(WorldError::Waker(Status::NoMemory));
+                }
+
+                sys::puglSetBackend(raw, sys::puglStubBackend());
+                sys::puglSetEventFunc(raw, Some(waker_event_handler));
+                sys::puglSetSizeHint(raw, sys::PUGL_DEFAULT_SIZE, 1, 1);
+                let status = sys::puglRealize(raw);
+                if status != sys::PUGL_SUCCESS {
+                    sys::puglFreeView(raw);
+                    return Err(WorldError::Waker(Status::from_raw(status)));
+                }
+
+                view.0 = raw;
+            }
+        }
+
+        Ok(self.0.waker())
+    }
+
+    /// Returns the native handle of the hidden view of [`World::waker`], creating the view if needed.
+    #[cfg(target_os = "windows")]
+    pub(crate) fn waker_native(&self) -> Option<usize> {
+        self.waker().ok()?;
+        let view = self.0.waker.lock().unwrap();
+        Some(unsafe { sys::puglGetNativeView(view.0) })
     }
 
     /// Return a pointer to the native handle of the world.
@@ -152,6 +656,74 @@ impl World {
         }
     }
 
+    /// Returns handles to all views created in this world (including unrealized ones).
+    pub fn views(&self) -> Vec<ViewProxy> {
+        self.0
+            .views
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|&(id, view)| ViewProxy::new(&self.0, id, view))
+            .collect()
+    }
+
+    /// Set a hook that observes the events of every view in this world.
+    ///
+    /// The hook is called before the event handler of the view, which makes it suitable for application-wide shortcuts,
+    /// focus bookkeeping or debugging multi-window applications.
+    /// Events are passed as [`OwnedEvent`]s, since the views may use different backends.
+    ///
+    /// The hook must not set or clear the hook itself.
+    pub fn set_global_event_hook(
+        &self,
+        hook: impl FnMut(&ViewProxy, &OwnedEvent) + Send + 'static,
+    ) {
+        *self.0.hook.lock().unwrap() = Some(Box::new(hook));
+    }
+
+    /// Remove the hook set with [`World::set_global_event_hook`].
+    pub fn clear_global_event_hook(&self) {
+        *self.0.hook.lock().unwrap() = None;
+    }
+
+    /// Set a callback that is called whenever the event handler of a view takes longer than `threshold` to handle an event.
+    ///
+    /// The callback receives the view, the event and the time spent in the event handler,
+    /// which helps to attribute stutters in complex user interfaces to specific events.
+    /// See also [`UpdateStats`] for the time spent in each [`World::update`].
+    ///
+    /// The callback must not set or clear the callback itself.
+    pub fn set_slow_event_handler(
+        &self,
+        threshold: Duration,
+        callback: impl FnMut(&ViewProxy, &OwnedEvent, Duration) + Send + 'static,
+    ) {
+        *self.0.slow.lock().unwrap() = Some(SlowEventHandler {
+            threshold,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Remove the callback set with [`World::set_slow_event_handler`].
+    pub fn clear_slow_event_handler(&self) {
+        *self.0.slow.lock().unwrap() = None;
+    }
+
+    /// Returns the time since the last keyboard, pointer or scroll event of any view of the world (or since the world was created),
+    /// so the application can dim meters or pause animations while the user is away.
+    ///
+    /// See [`World::system_idle_time`] for the input in other applications.
+    pub fn user_idle_time(&self) -> Duration {
+        self.0.last_input.lock().unwrap().elapsed()
+    }
+
+    /// Returns the number of views created in this world (including unrealized ones).
+    ///
+    /// This can be used to exit the main loop once the last view is closed and dropped.
+    pub fn view_count(&self) -> usize {
+        self.0.views.lock().unwrap().len()
+    }
+
     /// Creates a new unrealized view with a specified backend.
     ///
     /// See [`Backend`] for more info.
@@ -160,20 +732,184 @@ impl World {
     }
 }
 
+/// The file descriptor of the X11 connection, which becomes readable when there are new events to process.
+///
+/// See [`World::poll`].
+#[cfg(target_os = "linux")]
+impl std::os::fd::AsRawFd for World {
+    fn as_raw_fd(&self) -> std::os::fd::RawFd {
+        unsafe { crate::x11::xlib::XConnectionNumber(self.native().as_raw()) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl std::os::fd::AsFd for World {
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        use std::os::fd::AsRawFd;
+        unsafe { std::os::fd::BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
 pub(crate) struct WorldInner {
     pub raw: *mut sys::PuglWorld,
     pub poison: Mutex<Option<Box<dyn Any + Send>>>,
+    pub waker: Arc<Mutex<WakerView>>,
+    /// The views of the world with their ids, which are never reused (unlike the addresses of the views), see [`ViewProxy`]
+    pub views: Mutex<Vec<(u64, *mut sys::PuglView)>>,
+    pub next_view_id: AtomicU64,
+    pub idle: Mutex<IdleCallbacks>,
+    pub deferred: Mutex<Vec<Deferred>>,
+    pub stats: Mutex<UpdateStats>,
+    pub hook: Mutex<Option<EventHook>>,
+    pub slow: Mutex<Option<SlowEventHandler>>,
+    pub quit: Arc<AtomicBool>,
+    pub closed: Mutex<Vec<*mut sys::PuglView>>,
+    /// Views with a tab index and the index, in registration order (see [`View::set_tab_index`](crate::View::set_tab_index))
+    pub tab_order: Mutex<Vec<(*mut sys::PuglView, i32)>>,
+    /// Views that want an [`Event::EndFrame`](crate::Event::EndFrame) once [`World::update`] dispatched all exposes
+    pub exposed: Mutex<Vec<(*mut sys::PuglView, ViewCallback)>>,
+    /// Views with a deadline (like the clipboard timeout) and the callback for when it passed, see [`WorldInner::set_deadline`]
+    pub deadlines: Mutex<Vec<(*mut sys::PuglView, Instant, ViewCallback)>>,
+    /// `true` while events are dispatched by [`World::update`]
+    pub updating: AtomicBool,
+    /// `true` for the whole [`World::update`] call, see [`WorldError::Reentrant`]
+    pub in_update: AtomicBool,
+    /// The thread that runs the event loop, see [`WorldInner::check_thread`]
+    pub owner: OnceLock<ThreadId>,
+    /// Number of signals seen by the world, or `None` if signals are not handled (see [`World::close_views_on_signal`])
+    pub signals: Mutex<Option<usize>>,
+    /// The time of the last input event of any view, see [`World::user_idle_time`]
+    pub last_input: Mutex<Instant>,
+    /// The time a [`PluginEditor`](crate::PluginEditor) last polled the world, see [`PluginEditor::idle`](crate::PluginEditor::idle)
+    pub last_idle: Mutex<Option<Instant>>,
+}
+
+/// Number of `SIGINT`/`SIGTERM` signals received by the process.
+static SIGNALS: AtomicUsize = AtomicUsize::new(0);
+
+/// The signal handlers of [`World::close_views_on_signal`], shared by all worlds.
+#[cfg(unix)]
+mod signals {
+    use super::SIGNALS;
+    use std::{
+        os::raw::c_int,
+        sync::{Mutex, PoisonError, atomic::Ordering},
+    };
+
+    unsafe extern "C" {
+        fn signal(signum: c_int, handler: usize) -> usize;
+    }
+
+    const SIGINT: c_int = 2;
+    const SIGTERM: c_int = 15;
+    const SIG_ERR: usize = usize::MAX;
+
+    /// The number of worlds that handle the signals, and the handlers that were replaced.
+    static INSTALLED: Mutex<(usize, [usize; 2])> = Mutex::new((0, [0; 2]));
+
+    extern "C" fn handler(_signum: c_int) {
+        SIGNALS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn install() {
+        let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+        if installed.0 == 0 {
+            let handler = handler as extern "C" fn(c_int) as usize;
+            installed.1 = unsafe { [signal(SIGINT, handler), signal(SIGTERM, handler)] };
+        }
+
+        installed.0 += 1;
+    }
+
+    pub fn uninstall() {
+        let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+        installed.0 -= 1;
+        if installed.0 == 0 {
+            for (signum, previous) in [SIGINT, SIGTERM].into_iter().zip(installed.1) {
+                if previous != SIG_ERR {
+                    unsafe { signal(signum, previous) };
+                }
+            }
+        }
+    }
 }
 
 impl WorldInner {
+    /// Sets the deadline of a view, after which `callback` is called by [`World::update`], or removes it if `deadline` is `None`.
+    ///
+    /// The deadlines don't use `pugl` timers, since every timer id is available to the user.
+    pub fn set_deadline(
+        &self,
+        view: *mut sys::PuglView,
+        deadline: Option<Instant>,
+        callback: ViewCallback,
+    ) {
+        let mut deadlines = self
+            .deadlines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        deadlines.retain(|&(v, _, _)| v != view);
+        if let Some(deadline) = deadline {
+            deadlines.push((view, deadline, callback));
+        }
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        let deadlines = self.deadlines.lock().unwrap();
+        deadlines.iter().map(|&(_, deadline, _)| deadline).min()
+    }
+
+    /// Calls the callbacks of the deadlines that have passed.
+    unsafe fn expire_deadlines(&self) {
+        let now = Instant::now();
+        let expired = {
+            let mut deadlines = self.deadlines.lock().unwrap();
+            let (expired, pending) = take(&mut *deadlines)
+                .into_iter()
+                .partition::<Vec<_>, _>(|&(_, deadline, _)| deadline <= now);
+            *deadlines = pending;
+            expired
+        };
+
+        // a view may have been dropped since its deadline was set
+        for (view, _, callback) in expired {
+            if self.views.lock().unwrap().iter().any(|&(_, v)| v == view) {
+                unsafe { callback(view) };
+            }
+        }
+    }
+
     pub fn wrap(world: *mut sys::PuglWorld) -> Arc<Self> {
         unsafe {
             let arc = Arc::new(WorldInner {
                 raw: world,
                 poison: Mutex::new(None),
+                waker: Arc::new(Mutex::new(WakerView(null_mut()))),
+                views: Mutex::new(Vec::new()),
+                next_view_id: AtomicU64::new(1),
+                idle: Mutex::new(IdleCallbacks::default()),
+                deferred: Mutex::new(Vec::new()),
+                stats: Mutex::new(UpdateStats::default()),
+                hook: Mutex::new(None),
+                slow: Mutex::new(None),
+                quit: Arc::new(AtomicBool::new(false)),
+                closed: Mutex::new(Vec::new()),
+                tab_order: Mutex::new(Vec::new()),
+                exposed: Mutex::new(Vec::new()),
+                deadlines: Mutex::new(Vec::new()),
+                updating: AtomicBool::new(false),
+                in_update: AtomicBool::new(false),
+                owner: OnceLock::new(),
+                signals: Mutex::new(None),
+                last_input: Mutex::new(Instant::now()),
+                last_idle: Mutex::new(None),
             });
 
             sys::puglSetWorldHandle(world, Arc::as_ptr(&arc) as _);
+
+            #[cfg(target_os = "linux")]
+            crate::input::watch_keys(sys::puglGetNativeWorld(world) as *mut c_void);
+
             arc
         }
     }
@@ -183,8 +919,70 @@ impl WorldInner {
         unsafe { ManuallyDrop::new(Arc::from_raw(sys::puglGetWorldHandle(world) as *const Self)) }
     }
 
-    pub fn as_world(&self) -> &World {
-        unsafe { &*(self as *const _ as *const World) }
+    /// Records that the view received a close event, see [`World::run_until_all_views_closed`].
+    pub fn mark_closed(&self, view: *mut sys::PuglView) {
+        let mut closed = self.closed.lock().unwrap();
+        if !closed.contains(&view) {
+            closed.push(view);
+        }
+    }
+
+    pub fn defer(&self, f: Deferred) {
+        self.deferred.lock().unwrap().push(f);
+        self.waker().wake();
+    }
+
+    pub fn waker(&self) -> WorldWaker {
+        WorldWaker {
+            view: self.waker.clone(),
+            quit: self.quit.clone(),
+        }
+    }
+
+    /// Sends a close event to every view if a signal was received since the last call, see [`World::close_views_on_signal`].
+    pub fn close_views_if_signaled(&self) -> bool {
+        let current = SIGNALS.load(Ordering::Relaxed);
+        match &mut *self.signals.lock().unwrap() {
+            Some(seen) if *seen != current => *seen = current,
+            _ => return false,
+        }
+
+        let views = self.views.lock().unwrap().clone();
+        for (_, view) in views {
+            unsafe {
+                let _ = send_close_event(view);
+            }
+        }
+
+        true
+    }
+
+    /// Binds the world to the current thread on the first call. Returns `false` if the world is owned by another thread.
+    pub fn bind_thread(&self) -> bool {
+        *self.owner.get_or_init(|| thread::current().id()) == thread::current().id()
+    }
+
+    /// Binds the world to the current thread on the first call. Panics if the world is owned by another thread.
+    pub fn check_thread(&self) {
+        assert!(
+            self.bind_thread(),
+            "pugl-rs: world used from a different thread than the one running its event loop"
+        );
+    }
+
+    /// Returns `true` if the current thread runs the event loop, `false` if the world is not bound to a thread yet.
+    pub fn is_owner_thread(&self) -> bool {
+        self.owner
+            .get()
+            .is_some_and(|&owner| owner == thread::current().id())
+    }
+
+    pub fn as_world(this: &Arc<Self>) -> &World {
+        unsafe { &*(this as *const Arc<Self> as *const World) }
+    }
+
+    pub fn to_world(this: &Arc<Self>) -> World {
+        World(this.clone())
     }
 
     pub fn replace_poison(
@@ -198,8 +996,39 @@ impl WorldInner {
 
 impl Drop for WorldInner {
     fn drop(&mut self) {
+        #[cfg(unix)]
+        if self
+            .signals
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+        {
+            signals::uninstall();
+        }
+
+        // idle callbacks may hold resources of the native world, like the watchers of `World::add_color_scheme_callback`
+        drop(take(
+            self.idle.get_mut().unwrap_or_else(PoisonError::into_inner),
+        ));
+
         unsafe {
+            let mut waker = self.waker.lock().unwrap_or_else(PoisonError::into_inner);
+            if !waker.0.is_null() {
+                sys::puglFreeView(replace(&mut waker.0, null_mut()));
+            }
+            drop(waker);
+
+            #[cfg(target_os = "linux")]
+            crate::input::unwatch_keys(sys::puglGetNativeWorld(self.raw) as *mut c_void);
+
             sys::puglFreeWorld(self.raw);
         }
     }
 }
+
+unsafe extern "C" fn waker_event_handler(
+    _view: *mut sys::PuglView,
+    _event: *const sys::PuglEvent,
+) -> sys::PuglStatus {
+    sys::PUGL_SUCCESS
+}
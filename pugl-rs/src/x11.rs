@@ -0,0 +1,337 @@
+use crate::{Backend, View, World};
+use std::os::raw::c_ulong;
+
+/// An Xlib `Display` connection, only used behind a pointer.
+#[repr(C)]
+pub struct XDisplay {
+    _private: [u8; 0],
+}
+
+/// An X11 window id (`XID`).
+pub type XWindow = c_ulong;
+
+/// Typed access to the Xlib connection of a [`World`], for making supplemental Xlib calls.
+///
+/// With the `x11-dl` feature, the connection can also be accessed as an `x11_dl::xlib::Display`.
+pub trait X11WorldExt {
+    /// Returns the Xlib connection of the world.
+    fn x11_display(&self) -> *mut XDisplay;
+
+    /// Returns the Xlib connection of the world as an [`x11_dl::xlib::Display`].
+    #[cfg(feature = "x11-dl")]
+    fn xlib_display(&self) -> *mut x11_dl::xlib::Display {
+        self.x11_display() as *mut x11_dl::xlib::Display
+    }
+}
+
+/// Typed access to the Xlib connection and the window of a [`View`], for making supplemental Xlib calls.
+///
+/// The window is owned by `pugl`, so it must not be destroyed, and changes to its attributes may be overwritten.
+/// With the `x11rb` feature, the window can also be accessed as an `x11rb::protocol::xproto::Window`.
+/// ```no_run
+/// # use pugl_rs::*;
+/// # fn example(view: &View<()>) {
+/// let display = view.x11_display();
+/// let window = view.x11_window();
+/// // XChangeProperty(display, window, ...)
+/// # }
+/// ```
+pub trait X11ViewExt {
+    /// Returns the Xlib connection of the world of the view.
+    fn x11_display(&self) -> *mut XDisplay;
+
+    /// Returns the window of the view, or `0` if the view is not realized.
+    fn x11_window(&self) -> XWindow;
+
+    /// Returns the Xlib connection of the world of the view as an [`x11_dl::xlib::Display`].
+    #[cfg(feature = "x11-dl")]
+    fn xlib_display(&self) -> *mut x11_dl::xlib::Display {
+        self.x11_display() as *mut x11_dl::xlib::Display
+    }
+
+    /// Returns the window of the view as an [`x11rb::protocol::xproto::Window`] (X11 window ids have 29 bits).
+    #[cfg(feature = "x11rb")]
+    fn x11rb_window(&self) -> x11rb::protocol::xproto::Window {
+        self.x11_window() as x11rb::protocol::xproto::Window
+    }
+}
+
+impl X11WorldExt for World {
+    fn x11_display(&self) -> *mut XDisplay {
+        self.native().as_raw() as *mut XDisplay
+    }
+}
+
+impl<B: Backend> X11ViewExt for View<B> {
+    fn x11_display(&self) -> *mut XDisplay {
+        self.world().x11_display()
+    }
+
+    fn x11_window(&self) -> XWindow {
+        self.native().as_raw() as XWindow
+    }
+}
+
+/// The Xlib functions used by `pugl-rs` itself, which are not covered by `pugl`.
+///
+/// `pugl` links Xlib already, so they are declared here instead of depending on a binding crate.
+pub(crate) mod xlib {
+    use std::os::raw::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong, c_void};
+
+    pub type Display = *mut c_void;
+    pub type WireToEvent = unsafe extern "C" fn(Display, *mut c_void, *mut c_void) -> c_int;
+
+    pub const SUCCESS: c_int = 0;
+    pub const ANY_PROPERTY_TYPE: c_ulong = 0;
+    pub const PROP_MODE_REPLACE: c_int = 0;
+    pub const STRUCTURE_NOTIFY_MASK: c_long = 1 << 17;
+    pub const PROPERTY_CHANGE_MASK: c_long = 1 << 22;
+    pub const KEY_PRESS: c_int = 2;
+    pub const PROPERTY_NOTIFY: c_int = 28;
+    pub const CLIENT_MESSAGE: c_int = 33;
+    pub const XKB_USE_CORE_KBD: c_uint = 0x0100;
+
+    #[repr(C)]
+    pub struct XWindowAttributes {
+        pub x: c_int,
+        pub y: c_int,
+        pub width: c_int,
+        pub height: c_int,
+        pub border_width: c_int,
+        pub depth: c_int,
+        pub visual: *mut c_void,
+        pub root: c_ulong,
+        pub class: c_int,
+        pub bit_gravity: c_int,
+        pub win_gravity: c_int,
+        pub backing_store: c_int,
+        pub backing_planes: c_ulong,
+        pub backing_pixel: c_ulong,
+        pub save_under: c_int,
+        pub colormap: c_ulong,
+        pub map_installed: c_int,
+        pub map_state: c_int,
+        pub all_event_masks: c_long,
+        pub your_event_mask: c_long,
+        pub do_not_propagate_mask: c_long,
+        pub override_redirect: c_int,
+        pub screen: *mut c_void,
+    }
+
+    /// The fields shared by `XPropertyEvent` and `XClientMessageEvent`, followed by the data of a client message.
+    #[repr(C)]
+    pub struct XEventHeader {
+        pub type_: c_int,
+        pub serial: c_ulong,
+        pub send_event: c_int,
+        pub display: Display,
+        pub window: c_ulong,
+        /// `atom` of a property event, `message_type` of a client message
+        pub atom: c_ulong,
+        pub format: c_int,
+        pub data: [c_long; 5],
+    }
+
+    /// The start of `XKeyEvent`, up to the keycode.
+    #[repr(C)]
+    pub struct XKeyEvent {
+        pub type_: c_int,
+        pub serial: c_ulong,
+        pub send_event: c_int,
+        pub display: Display,
+        pub window: c_ulong,
+        pub root: c_ulong,
+        pub subwindow: c_ulong,
+        pub time: c_ulong,
+        pub x: c_int,
+        pub y: c_int,
+        pub x_root: c_int,
+        pub y_root: c_int,
+        pub state: c_uint,
+        pub keycode: c_uint,
+    }
+
+    unsafe extern "C" {
+        pub fn XInternAtom(display: Display, name: *const c_char, only_if_exists: c_int)
+        -> c_ulong;
+        pub fn XDefaultScreen(display: Display) -> c_int;
+        pub fn XDefaultRootWindow(display: Display) -> c_ulong;
+        pub fn XConnectionNumber(display: Display) -> c_int;
+        pub fn XFlush(display: Display) -> c_int;
+        pub fn XFree(data: *mut c_void) -> c_int;
+        pub fn XGetSelectionOwner(display: Display, selection: c_ulong) -> c_ulong;
+        pub fn XGetWindowAttributes(
+            display: Display,
+            window: c_ulong,
+            attributes: *mut XWindowAttributes,
+        ) -> c_int;
+        pub fn XSelectInput(display: Display, window: c_ulong, mask: c_long) -> c_int;
+        pub fn XGetWindowProperty(
+            display: Display,
+            window: c_ulong,
+            property: c_ulong,
+            long_offset: c_long,
+            long_length: c_long,
+            delete: c_int,
+            req_type: c_ulong,
+            actual_type: *mut c_ulong,
+            actual_format: *mut c_int,
+            items: *mut c_ulong,
+            bytes_after: *mut c_ulong,
+            data: *mut *mut c_uchar,
+        ) -> c_int;
+        pub fn XChangeProperty(
+            display: Display,
+            window: c_ulong,
+            property: c_ulong,
+            type_: c_ulong,
+            format: c_int,
+            mode: c_int,
+            data: *const c_uchar,
+            elements: c_int,
+        ) -> c_int;
+        pub fn XTranslateCoordinates(
+            display: Display,
+            src_window: c_ulong,
+            dest_window: c_ulong,
+            src_x: c_int,
+            src_y: c_int,
+            dest_x: *mut c_int,
+            dest_y: *mut c_int,
+            child: *mut c_ulong,
+        ) -> c_int;
+        pub fn XESetWireToEvent(
+            display: Display,
+            event_number: c_int,
+            proc_: Option<WireToEvent>,
+        ) -> Option<WireToEvent>;
+        pub fn XkbGetAutoRepeatRate(
+            display: Display,
+            device: c_uint,
+            delay: *mut c_uint,
+            interval: *mut c_uint,
+        ) -> c_int;
+        pub fn XkbKeycodeToKeysym(
+            display: Display,
+            keycode: c_uchar,
+            group: c_int,
+            level: c_int,
+        ) -> c_ulong;
+    }
+
+    // linked by `pugl-rs-sys` with the `xcursor` feature
+    #[cfg(feature = "xcursor")]
+    unsafe extern "C" {
+        pub fn XcursorSetDefaultSize(display: Display, size: c_int) -> c_int;
+    }
+}
+
+/// Callbacks for the events that `pugl` doesn't expose, like the events of other windows or the raw state of key events.
+///
+/// Xlib converts the events it receives from the server with a function that can be replaced per event type (`XESetWireToEvent`),
+/// so the callbacks see the events before `pugl` handles (or ignores) them.
+pub(crate) mod event_hook {
+    use super::xlib::{Display, WireToEvent, XESetWireToEvent};
+    use std::{
+        os::raw::{c_int, c_void},
+        ptr::fn_addr_eq,
+        sync::{Mutex, PoisonError},
+    };
+
+    /// Called with the display and the converted `XEvent`.
+    pub type Callback = fn(Display, *const c_void);
+
+    struct Hook {
+        display: usize,
+        event: c_int,
+        previous: Option<WireToEvent>,
+        callbacks: Vec<Callback>,
+    }
+
+    static HOOKS: Mutex<Vec<Hook>> = Mutex::new(Vec::new());
+
+    /// Calls `callback` with every event of the given type that the display receives, until [`remove`] is called.
+    ///
+    /// The callback is called while Xlib reads the events, so it must not make requests.
+    pub unsafe fn add(display: Display, event: c_int, callback: Callback) {
+        let mut hooks = HOOKS.lock().unwrap_or_else(PoisonError::into_inner);
+        let hook = hooks
+            .iter_mut()
+            .find(|hook| hook.display == display as usize && hook.event == event);
+
+        match hook {
+            Some(hook) => hook.callbacks.push(callback),
+            None => hooks.push(Hook {
+                display: display as usize,
+                event,
+                previous: unsafe { XESetWireToEvent(display, event, Some(wire_to_event)) },
+                callbacks: vec![callback],
+            }),
+        }
+    }
+
+    /// Removes a callback added with [`add`], which must be done before the display is closed.
+    pub unsafe fn remove(display: Display, event: c_int, callback: Callback) {
+        let mut hooks = HOOKS.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(index) = hooks
+            .iter()
+            .position(|hook| hook.display == display as usize && hook.event == event)
+        else {
+            return;
+        };
+
+        let callbacks = &mut hooks[index].callbacks;
+        if let Some(position) = callbacks.iter().position(|&c| fn_addr_eq(c, callback)) {
+            callbacks.remove(position);
+        }
+
+        if !callbacks.is_empty() {
+            return;
+        }
+
+        unsafe {
+            // a hook installed after this one still calls it, in which case it has to stay
+            let current = XESetWireToEvent(display, event, hooks[index].previous);
+            if !current.is_some_and(|current| fn_addr_eq(current, wire_to_event as WireToEvent)) {
+                XESetWireToEvent(display, event, current);
+                return;
+            }
+        }
+
+        hooks.remove(index);
+    }
+
+    unsafe extern "C" fn wire_to_event(
+        display: Display,
+        event: *mut c_void,
+        wire: *mut c_void,
+    ) -> c_int {
+        unsafe {
+            // the most significant bit of the wire event type is set for sent events
+            let type_ = (*(wire as *const u8) & 0x7f) as c_int;
+            let (previous, callbacks) = {
+                let hooks = HOOKS.lock().unwrap_or_else(PoisonError::into_inner);
+                match hooks
+                    .iter()
+                    .find(|hook| hook.display == display as usize && hook.event == type_)
+                {
+                    Some(hook) => (hook.previous, hook.callbacks.clone()),
+                    None => return 0,
+                }
+            };
+
+            let Some(previous) = previous else {
+                return 0;
+            };
+
+            let result = previous(display, event, wire);
+            if result != 0 {
+                for callback in callbacks {
+                    callback(display, event);
+                }
+            }
+
+            result
+        }
+    }
+}
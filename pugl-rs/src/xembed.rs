@@ -0,0 +1,190 @@
+//! Minimal XEmbed client support.
+//!
+//! Embedded views announce the protocol in `_XEMBED_INFO`, and report whether they are mapped there, since the host maps the window.
+//! The host keeps the keyboard focus and forwards the key events, and tells the client whether it has the focus with `_XEMBED` messages.
+//! `pugl` drops these messages, so they are picked up by an [`event_hook`] and delivered as [`Event::FocusIn`] and [`Event::FocusOut`] by [`World::update`](crate::World::update).
+
+use crate::{
+    Backend, CrossingMode, Event, sys,
+    view::ViewState,
+    x11::{event_hook, xlib::*},
+};
+use std::{
+    os::raw::{c_int, c_long, c_uchar, c_ulong, c_void},
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{Mutex, PoisonError},
+};
+
+const XEMBED_VERSION: c_ulong = 0;
+const XEMBED_MAPPED: c_ulong = 1 << 0;
+
+const XEMBED_EMBEDDED_NOTIFY: c_long = 0;
+const XEMBED_WINDOW_ACTIVATE: c_long = 1;
+const XEMBED_WINDOW_DEACTIVATE: c_long = 2;
+const XEMBED_FOCUS_IN: c_long = 4;
+const XEMBED_FOCUS_OUT: c_long = 5;
+
+type FocusCallback = unsafe fn(*mut sys::PuglView, bool);
+
+struct Client {
+    display: usize,
+    window: c_ulong,
+    view: usize,
+    callback: FocusCallback,
+    /// The `_XEMBED` atom of the display
+    atom: c_ulong,
+    active: bool,
+    focused: bool,
+    /// Whether the view was told it has the focus
+    reported: bool,
+}
+
+impl Client {
+    /// The client has the keyboard focus if it is focused within an active host window.
+    fn has_focus(&self) -> bool {
+        self.active && self.focused
+    }
+}
+
+static CLIENTS: Mutex<Vec<Client>> = Mutex::new(Vec::new());
+
+/// Registers an embedded view that was realized, and announces the protocol as unmapped until it is shown.
+pub unsafe fn embed<B: Backend>(display: Display, window: usize, view: *mut sys::PuglView) {
+    unsafe {
+        let mut clients = CLIENTS.lock().unwrap_or_else(PoisonError::into_inner);
+        if !clients.iter().any(|c| c.display == display as usize) {
+            event_hook::add(display, CLIENT_MESSAGE, client_message);
+        }
+
+        clients.push(Client {
+            display: display as usize,
+            window: window as c_ulong,
+            view: view as usize,
+            callback: focus_changed::<B>,
+            atom: XInternAtom(display, c"_XEMBED".as_ptr(), 0),
+            active: false,
+            focused: false,
+            reported: false,
+        });
+        drop(clients);
+
+        set_info(display, window, false);
+    }
+}
+
+/// Forgets a view, which must be done before it is freed. Does nothing if the view isn't embedded.
+pub unsafe fn release(display: Display, window: usize) {
+    let mut clients = CLIENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    clients.retain(|c| !(c.display == display as usize && c.window == window as c_ulong));
+    if !clients.iter().any(|c| c.display == display as usize) {
+        unsafe { event_hook::remove(display, CLIENT_MESSAGE, client_message) };
+    }
+}
+
+/// Updates the `XEMBED_MAPPED` flag after the view was shown or hidden. Does nothing if the view isn't embedded.
+pub unsafe fn set_mapped(display: Display, window: usize, mapped: bool) {
+    let embedded = CLIENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .any(|c| c.display == display as usize && c.window == window as c_ulong);
+
+    if embedded {
+        unsafe { set_info(display, window, mapped) };
+    }
+}
+
+/// Delivers the focus changes received since the last call to the views of the display.
+pub unsafe fn dispatch(display: Display) {
+    let changed: Vec<_> = CLIENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter_mut()
+        .filter(|c| c.display == display as usize && c.has_focus() != c.reported)
+        .map(|c| {
+            c.reported = c.has_focus();
+            (c.view, c.callback, c.reported)
+        })
+        .collect();
+
+    // the lock is released, since the event handlers may drop views
+    for (view, callback, focused) in changed {
+        let registered = CLIENTS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|c| c.view == view);
+
+        if registered {
+            unsafe { callback(view as *mut sys::PuglView, focused) };
+        }
+    }
+}
+
+fn client_message(display: Display, event: *const c_void) {
+    let event = unsafe { &*(event as *const XEventHeader) };
+    let mut clients = CLIENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(client) = clients
+        .iter_mut()
+        .find(|c| c.display == display as usize && c.window == event.window)
+    else {
+        return;
+    };
+
+    if event.atom != client.atom || event.format != 32 {
+        return;
+    }
+
+    // the first value is the timestamp, followed by the opcode
+    match event.data[1] {
+        XEMBED_EMBEDDED_NOTIFY => {
+            // the host tells the client about the focus again after embedding it
+            client.active = false;
+            client.focused = false;
+        }
+        XEMBED_WINDOW_ACTIVATE => client.active = true,
+        XEMBED_WINDOW_DEACTIVATE => client.active = false,
+        XEMBED_FOCUS_IN => client.focused = true,
+        XEMBED_FOCUS_OUT => client.focused = false,
+        _ => {}
+    }
+}
+
+unsafe fn focus_changed<B: Backend>(raw_view: *mut sys::PuglView, focused: bool) {
+    unsafe {
+        let state = ViewState::<B>::from_raw(raw_view);
+        let view = &*state.wrapper;
+        let mode = CrossingMode::Normal;
+        let event = match focused {
+            true => Event::FocusIn { mode },
+            false => Event::FocusOut { mode },
+        };
+
+        let result = catch_unwind(AssertUnwindSafe(|| state.deliver(view, event)));
+        if let Err(panic) = result {
+            view.world.replace_poison(Some(panic));
+        }
+    }
+}
+
+/// Sets the `_XEMBED_INFO` property of the window.
+unsafe fn set_info(display: Display, window: usize, mapped: bool) {
+    unsafe {
+        let atom = XInternAtom(display, c"_XEMBED_INFO".as_ptr(), 0);
+        let flags = if mapped { XEMBED_MAPPED } else { 0 };
+        let info: [c_ulong; 2] = [XEMBED_VERSION, flags];
+
+        // properties with format 32 are passed as an array of longs
+        XChangeProperty(
+            display,
+            window as c_ulong,
+            atom,
+            atom,
+            32,
+            PROP_MODE_REPLACE,
+            info.as_ptr() as *const c_uchar,
+            info.len() as c_int,
+        );
+        XFlush(display);
+    }
+}